@@ -10,18 +10,42 @@ pub trait RunMut {
 }
 
 pub mod add;
+pub mod agenda;
+pub mod batch;
+pub mod block;
+pub mod board;
+pub mod burndown;
+pub mod calendar;
+pub mod cancel;
 pub mod clean;
 pub mod done;
+pub mod estimates;
+pub mod export;
+pub mod graph;
+pub mod import;
 pub mod init;
 pub mod list_add;
 pub mod list_default;
+pub mod list_move;
 pub mod list_remove;
 pub mod list_show;
+pub mod log_time;
 pub mod modify;
 pub mod move_;
+pub mod next;
+pub mod note;
 pub mod remove;
+pub mod scan;
+pub mod search;
+pub mod start;
+pub mod stats;
+pub mod stop;
+pub mod tag;
+pub mod timesheet;
 pub mod track;
 pub mod tree;
+pub mod unblock;
+pub mod wait;
 
 /*
 *
@@ -62,16 +86,64 @@ pub enum TodoCmd {
     Tree(tree::TreeOptions),
     /// Show detailed information of a todo.
     Track(track::TrackOptions),
+    /// Search todos by description, link, tags and notes, in one list, all
+    /// lists or also the global store.
+    Search(search::SearchOptions),
+    /// Show open todos of all lists by day for the coming days.
+    Agenda(agenda::AgendaOptions),
+    /// Show todos due, created and completed per day in a month calendar.
+    Calendar(calendar::CalendarOptions),
+    /// Lay out todos of a list in columns by status or by tag, cards can be
+    /// moved between columns.
+    Board(board::BoardOptions),
     /// Modify an existing todo.
     ///
     /// interactive mode when --desc and --link options are missing.
     Modify(modify::ModifyOptions),
-    /// Change the level of a todo and its children.
+    /// Change the level of todo(s) and their children.
     Move(move_::MoveOptions),
-    /// Remove an exist todo(s), <TODO_ID> must have no children.
+    /// Remove an exist todo(s), <TODO_ID> with children which are not
+    /// removed as well needs --recursive.
     Remove(remove::RemoveOptions),
-    /// Mark a todo(s) as DONE, <TODO_ID> must have no children.
+    /// Mark a todo(s) as DONE, <TODO_ID> with open children needs
+    /// --recursive.
     Done(done::DoneOptions),
+    /// Add or remove tags of todo(s).
+    Tag(tag::TagOptions),
+    /// Add a dated note to a todo.
+    Note(note::NoteOptions),
+    /// Mark a todo(s) as IN PROGRESS.
+    ///
+    /// the timer is started as well when a single todo is given.
+    Start(start::StartOptions),
+    /// Stop the running timer and log the elapsed time on its todo.
+    Stop(stop::StopOptions),
+    /// Log time spent on a todo, e.g. 1h30m.
+    Log(log_time::LogTimeOptions),
+    /// Report time logged by day or week in specified DATE_OPTION.
+    Timesheet(timesheet::TimesheetOptions),
+    /// Compare estimates with time spent on todos completed in specified
+    /// DATE_OPTION.
+    Estimates(estimates::EstimatesOptions),
+    /// Summarize created and completed todos in specified DATE_OPTION.
+    Stats(stats::StatsOptions),
+    /// Chart remaining (or completed) todos of a list or a subtree over time.
+    Burndown(burndown::BurndownOptions),
+    /// Draw the hierarchy and blockers of todos as a Graphviz DOT or Mermaid
+    /// graph.
+    Graph(graph::GraphOptions),
+    /// Mark a todo(s) as WAITING (blocked by something else).
+    Wait(wait::WaitOptions),
+    /// Mark a todo(s) as CANCELLED, it no longer counts as open or done.
+    Cancel(cancel::CancelOptions),
+    /// Mark a todo as blocked by other todo(s).
+    Block(block::BlockOptions),
+    /// Remove blocker(s) of a todo.
+    Unblock(unblock::UnblockOptions),
+    /// List open todos which are ready to work on, i.e. not blocked and
+    /// without open children.
+    #[command(visible_alias = "ready")]
+    Next(next::NextOptions),
     /// Clean up all completed todos in specified DATE_OPTION.
     Clean(clean::CleanOptions),
     /// Show all lists.
@@ -80,6 +152,68 @@ pub enum TodoCmd {
     ListAdd(list_add::ListAddOptions),
     /// Remove lists.
     ListRemove(list_remove::ListRemoveOptions),
+    /// Move todo(s) and their children to another list.
+    ListMove(list_move::ListMoveOptions),
     /// Set default list.
     ListDefault(list_default::ListDefaultOptions),
+    /// Run commands read from a file or stdin, one per line, and write the
+    /// store once if all of them succeed.
+    ///
+    /// `$NAME = add ...` labels the created todo, `$NAME` in a later command
+    /// stands for its id.
+    Batch(batch::BatchOptions),
+    /// Export todos to the file format of another tool
+    Export(export::ExportOptions),
+    /// Import todos from the file format of another tool, as new todos or
+    /// updates of the ones they were exported from
+    Import(import::ImportOptions),
+    /// Sync TODO, FIXME and HACK comments of source files into a list.
+    ///
+    /// todos are tagged `scan:KEYWORD` and linked to `FILE:LINE`, they follow
+    /// the comments when they move and are marked as DONE once the comments
+    /// are gone.
+    Scan(scan::ScanOptions),
+}
+
+impl TodoCmd {
+    pub fn run(self, ctx: &mut Context) -> Result<()> {
+        match self {
+            Self::Add(add) => add.run_mut(ctx),
+            Self::Tree(tree) => tree.run(ctx),
+            Self::Track(track) => track.run(ctx),
+            Self::Search(search) => search.run(ctx),
+            Self::Agenda(agenda) => agenda.run(ctx),
+            Self::Calendar(calendar) => calendar.run(ctx),
+            Self::Board(board) => board.run_mut(ctx),
+            Self::Modify(modify) => modify.run_mut(ctx),
+            Self::Move(move_) => move_.run_mut(ctx),
+            Self::Remove(remove) => remove.run_mut(ctx),
+            Self::Done(done) => done.run_mut(ctx),
+            Self::Tag(tag) => tag.run_mut(ctx),
+            Self::Note(note) => note.run_mut(ctx),
+            Self::Start(start) => start.run_mut(ctx),
+            Self::Stop(stop) => stop.run_mut(ctx),
+            Self::Log(log_time) => log_time.run_mut(ctx),
+            Self::Timesheet(timesheet) => timesheet.run(ctx),
+            Self::Estimates(estimates) => estimates.run(ctx),
+            Self::Stats(stats) => stats.run(ctx),
+            Self::Burndown(burndown) => burndown.run(ctx),
+            Self::Graph(graph) => graph.run(ctx),
+            Self::Wait(wait) => wait.run_mut(ctx),
+            Self::Cancel(cancel) => cancel.run_mut(ctx),
+            Self::Block(block) => block.run_mut(ctx),
+            Self::Unblock(unblock) => unblock.run_mut(ctx),
+            Self::Next(next) => next.run(ctx),
+            Self::Clean(clean) => clean.run_mut(ctx),
+            Self::ListShow(l_show) => l_show.run(ctx),
+            Self::ListAdd(l_add) => l_add.run_mut(ctx),
+            Self::ListRemove(l_remove) => l_remove.run_mut(ctx),
+            Self::ListMove(l_move) => l_move.run_mut(ctx),
+            Self::ListDefault(l_default) => l_default.run_mut(ctx),
+            Self::Batch(batch) => batch.run_mut(ctx),
+            Self::Export(export) => export.run(ctx),
+            Self::Import(import) => import.run_mut(ctx),
+            Self::Scan(scan) => scan.run_mut(ctx),
+        }
+    }
 }
@@ -1,12 +1,12 @@
 use crate::{
     command::RunMut,
     context::Context,
-    model::Todo,
+    date::DateExpr,
+    model::{Priority, Todo, effort::Estimate, recur::Recur},
     ui::form::AddUI,
     util::{SyncBlock, get_list},
 };
-use anyhow::Result;
-use chrono::Local;
+use anyhow::{Result, anyhow};
 use clap::Args;
 use log::{debug, info, trace};
 
@@ -17,6 +17,32 @@ pub struct AddOptions {
     into: Option<u32>,
     #[command(flatten)]
     todo_from_args: Option<AddArgs>,
+    /// Planned date of todo, e.g. 2025-06-01, tomorrow, next friday, in 3 days
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = DateExpr::parse,
+        help_heading = "Todo"
+    )]
+    assign: Option<DateExpr>,
+    /// Recurrence rule, e.g. `daily`, `every 2 weeks on mon,thu` or `after 3 days`.
+    /// Planned for today if --assign is missing
+    #[arg(long, value_name = "RULE", help_heading = "Todo")]
+    recur: Option<Recur>,
+    /// Planned effort, e.g. 2h, 3d (working days) or 5pt (story points)
+    #[arg(long, value_name = "ESTIMATE", help_heading = "Todo")]
+    estimate: Option<Estimate>,
+    /// Tag(s) of todo, e.g. bug,infra
+    #[arg(
+        long,
+        value_name = "TAG",
+        value_delimiter = ',',
+        help_heading = "Todo"
+    )]
+    tag: Vec<String>,
+    /// Priority of todo, from A (highest) to Z
+    #[arg(long, value_name = "PRIORITY", help_heading = "Todo")]
+    priority: Option<Priority>,
     /// Add todo in specified list
     #[arg(short, long, value_name = "LIST_NAME")]
     list: Option<String>,
@@ -59,18 +85,24 @@ impl RunMut for AddOptions {
         let list = get_list(self.list, ctx);
         debug!(target: "add", "list [{list}]");
 
-        let create_at = Local::now().date_naive();
+        let tz = ctx.config.timezone();
+        let create_at = tz.now();
         info!(target: "add", "todo create at: {create_at:?}");
+        let assign_at = self
+            .assign
+            .map(|assign| assign.resolve(tz.date(&create_at)))
+            .transpose()
+            .map_err(|err| anyhow!(err))?;
 
         let todo_id = ctx.store.generate_id(&list)?;
         info!(target: "add", "todo id: {todo_id:?}");
 
         if let Some(pa_id) = &self.into {
-            let pa_todo = ctx.store.todo_by_id_mut(&list, &pa_id)?;
+            let pa_todo = ctx.store.todo_by_id_mut(&list, pa_id)?;
             pa_todo.children.get_or_insert_with(Vec::new).push(todo_id);
         }
 
-        let todo = Todo::new(
+        let mut todo = Todo::new(
             desc.clone(),
             link.clone(),
             None,
@@ -78,9 +110,22 @@ impl RunMut for AddOptions {
             create_at,
             None,
         );
+        todo.assign_at = assign_at
+            .or_else(|| self.recur.as_ref().map(|_| tz.date(&create_at)));
+        todo.recur = self.recur;
+        todo.estimate = self.estimate;
+        todo.tags = Some(self.tag).filter(|tags| !tags.is_empty());
+        todo.priority = self.priority;
         debug!(target: "add", "todo instance: {todo:#?}");
 
-        let todo = ctx.store.todos_mut(&list)?.insert(todo_id, todo);
+        let todos_mut = ctx.store.todos_mut(&list)?;
+        let todo = todos_mut.insert(todo_id, todo);
+
+        if let Some(pa_id) = self.into {
+            // a done parent is reopened by its new child
+            let rolled = todos_mut.roll_up(pa_id, create_at);
+            debug!(target: "add", "roll up todo [{rolled:?}]");
+        }
 
         println!("{:#?}", todo);
 
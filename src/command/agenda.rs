@@ -0,0 +1,180 @@
+use crate::{
+    command::Run,
+    context::Context,
+    model::{Status, Todo, TodoMap},
+    ui::tree::{AgendaUI, hack::TodoText, todo_text},
+    util::SyncBlock,
+};
+use anyhow::Result;
+use chrono::{Datelike, Days, NaiveDate};
+use clap::Args;
+use log::{debug, trace};
+
+/// Upper bound of the coming days, a year.
+const MAX_DAYS: u32 = 366;
+
+#[derive(Debug, Args)]
+pub struct AgendaOptions {
+    /// Number of coming days, starting from today
+    #[arg(
+        short = 'n',
+        long,
+        value_name = "N",
+        default_value_t = 7,
+        value_parser = clap::value_parser!(u32).range(1..=MAX_DAYS as i64),
+    )]
+    days: u32,
+    /// Print the agenda as plain text instead of an interactive view
+    #[arg(long)]
+    plain: bool,
+}
+
+/// A todo in the agenda.
+struct Item<'a> {
+    list: &'a String,
+    id: u32,
+    todo: &'a Todo,
+    todos: &'a TodoMap,
+}
+
+impl Run for AgendaOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "agenda", "{self:#?}");
+
+        let tz = ctx.config.timezone();
+        let today = tz.today();
+        let days: Vec<_> = (0..self.days)
+            .filter_map(|n| today.checked_add_days(Days::new(n.into())))
+            .collect();
+        debug!(target: "agenda", "{} ~ {:?}", today, days.last());
+
+        let mut overdue = Vec::new();
+        let mut by_day: Vec<Vec<Item>> =
+            days.iter().map(|_| Vec::new()).collect();
+        let mut someday = Vec::new();
+        let mut done_today = Vec::new();
+
+        let mut lists: Vec<_> = ctx.store.lists().iter().collect();
+        lists.sort_by_key(|(list, _)| *list);
+        for (list, todos) in lists {
+            let mut ids: Vec<_> = todos.keys().collect();
+            ids.sort();
+            for id in ids {
+                let todo = &todos[id];
+                let item = Item {
+                    list,
+                    id: *id,
+                    todo,
+                    todos,
+                };
+                let status = todo.status();
+                if status == Status::Done {
+                    if todo.complete_at().is_some_and(|c| tz.date(&c) == today)
+                    {
+                        done_today.push(item);
+                    }
+                    continue;
+                }
+                if !status.is_open() {
+                    continue;
+                }
+                match todo.assign_at {
+                    None => someday.push(item),
+                    Some(date) if date < today => overdue.push(item),
+                    Some(date) => {
+                        // beyond the coming days
+                        if let Some(i) = days.iter().position(|d| *d == date) {
+                            by_day[i].push(item);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut sections = vec![("Overdue".to_owned(), overdue)];
+        for (date, items) in days.iter().zip(by_day) {
+            sections.push((_day_title(*date, today), items));
+        }
+        sections.push(("Someday".to_owned(), someday));
+        sections.push(("Done Today".to_owned(), done_today));
+        sections.retain(|(_, items)| !items.is_empty());
+        debug!(target: "agenda", "{} section(s)", sections.len());
+
+        if self.plain {
+            for (title, items) in &sections {
+                println!("{title}");
+                for item in items {
+                    let blockers =
+                        ctx.store.open_blockers(item.list, &item.id)?;
+                    _print(item, today, !blockers.is_empty());
+                }
+            }
+            return Ok(());
+        }
+
+        let sections = sections
+            .into_iter()
+            .map(|(title, items)| {
+                let texts = items
+                    .iter()
+                    .map(|item| {
+                        let blockers =
+                            ctx.store.open_blockers(item.list, &item.id)?;
+                        Ok(_todo_text(item, !blockers.is_empty()))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((title, texts))
+            })
+            .collect::<Result<_>>()?;
+        let rt = SyncBlock::new()?;
+        let result = rt.block_on(AgendaUI::run(
+            &format!("Agenda {today}"),
+            sections,
+            ctx.config.tree_line(),
+        ))?;
+
+        println!("{result:?}");
+
+        Ok(())
+    }
+}
+
+fn _day_title(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => format!("Today {date}"),
+        1 => format!("Tomorrow {date}"),
+        _ => format!("{} {date}", date.weekday()),
+    }
+}
+
+fn _print(item: &Item, today: NaiveDate, blocked: bool) {
+    let Item { list, id, todo, .. } = item;
+    let assign_at = todo
+        .assign_at
+        .filter(|d| *d < today)
+        .map_or_else(String::new, |d| format!(" ({d})"));
+    let blocked = if blocked {
+        "⊘ "
+    } else {
+        ""
+    };
+    println!(
+        "  {}{list}#{id} {blocked}{}{assign_at}",
+        todo.status().marker(),
+        todo.desc
+    );
+}
+
+fn _todo_text(item: &Item, blocked: bool) -> TodoText {
+    let Item {
+        list,
+        id,
+        todo,
+        todos,
+    } = item;
+    TodoText {
+        list: Some(list.to_string()),
+        complete: None,
+        ..todo_text(todos, id, todo, blocked)
+    }
+}
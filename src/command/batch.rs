@@ -0,0 +1,295 @@
+use crate::{
+    command::{Cli, Cmd, RunMut, TodoCmd},
+    context::Context,
+    model::TodoRef,
+};
+use anyhow::{Result, anyhow, bail};
+use clap::{Args, Parser};
+use log::{debug, trace};
+use regex::{Captures, Regex};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+#[derive(Debug, Args)]
+pub struct BatchOptions {
+    /// File of commands, one per line, `-` reads them from stdin
+    #[arg(value_name = "FILE", default_value = "-")]
+    file: PathBuf,
+}
+
+/// A command of a batch, with the label of the todo it creates.
+#[derive(Debug, Deserialize)]
+struct BatchLine {
+    #[serde(rename = "as")]
+    label: Option<String>,
+    args: Vec<String>,
+}
+
+impl RunMut for BatchOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "batch", "{self:#?}");
+
+        let input = if self.file.as_os_str() == "-" {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        } else {
+            fs::read_to_string(&self.file)?
+        };
+
+        let label_re = Regex::new(r"\$(\$|[A-Za-z_][A-Za-z0-9_-]*)")?;
+        let mut labels: HashMap<String, u32> = HashMap::new();
+        ctx.store.defer_writes();
+
+        let mut count = 0;
+        for (n, raw) in input.lines().enumerate() {
+            let n = n + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fail = |err: anyhow::Error| {
+                anyhow!("line {n} [{line}], nothing is written: {err}")
+            };
+
+            let BatchLine { label, args } = _parse_line(line).map_err(fail)?;
+            let args = args
+                .into_iter()
+                .map(|arg| _substitute(&label_re, &arg, &labels))
+                .collect::<Result<Vec<_>>>()
+                .map_err(fail)?;
+            debug!(target: "batch", "line {n}: {label:?} {args:?}");
+
+            let todo_cmd = _parse_cmd(args).map_err(fail)?;
+            let before = _todo_refs(ctx);
+            todo_cmd.run(ctx).map_err(fail)?;
+            count += 1;
+
+            if let Some(label) = label {
+                let created: Vec<_> =
+                    _todo_refs(ctx).difference(&before).cloned().collect();
+                let [todo] = created.as_slice() else {
+                    return Err(fail(anyhow!(
+                        "${label} must create exactly one todo, {} created",
+                        created.len()
+                    )));
+                };
+                debug!(target: "batch", "${label} = {todo}");
+                labels.insert(label, todo.id);
+            }
+        }
+
+        ctx.store.commit(&ctx.store_path)?;
+        println!("Batch: {count} command(s) applied");
+        Ok(())
+    }
+}
+
+/// Reads a line as a JSON object `{"as": LABEL, "args": [...]}`, a JSON
+/// array of arguments, or a command line optionally labelled `$LABEL = `.
+fn _parse_line(line: &str) -> Result<BatchLine> {
+    if line.starts_with('{') {
+        return Ok(serde_json::from_str(line)?);
+    }
+    if line.starts_with('[') {
+        return Ok(BatchLine {
+            label: None,
+            args: serde_json::from_str(line)?,
+        });
+    }
+    let (label, command) = match line.split_once('=') {
+        Some((label, command))
+            if label.trim().starts_with('$')
+                && !label.trim().contains(char::is_whitespace) =>
+        {
+            (Some(label.trim()[1..].to_owned()), command)
+        }
+        _ => (None, line),
+    };
+    Ok(BatchLine {
+        label,
+        args: _split_words(command)?,
+    })
+}
+
+/// Splits a command line into words like a shell does, with single and
+/// double quotes and backslash escapes.
+fn _split_words(line: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => bail!("missing closing quote"),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => word.extend(chars.next()),
+                        Some(c) => word.push(c),
+                        None => bail!("missing closing quote"),
+                    }
+                }
+            }
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Replaces `$LABEL` in `arg` by the id of the todo created as `LABEL`, and
+/// `$$` by `$`.
+fn _substitute(
+    label_re: &Regex,
+    arg: &str,
+    labels: &HashMap<String, u32>,
+) -> Result<String> {
+    let mut unknown = None;
+    let arg = label_re.replace_all(arg, |caps: &Captures| {
+        match labels.get(&caps[1]) {
+            _ if &caps[1] == "$" => "$".to_owned(),
+            Some(id) => id.to_string(),
+            None => {
+                unknown.get_or_insert_with(|| caps[1].to_owned());
+                String::new()
+            }
+        }
+    });
+    match unknown {
+        Some(label) => bail!("unknown label [${label}], write $$ for a $"),
+        None => Ok(arg.into_owned()),
+    }
+}
+
+fn _parse_cmd(args: Vec<String>) -> Result<TodoCmd> {
+    let cli = Cli::try_parse_from(["taso".to_owned()].into_iter().chain(args))
+        .map_err(|err| anyhow!("{}", err.render().to_string().trim_end()))?;
+    if cli.global {
+        bail!("a batch runs in a single store, --global can't be used");
+    }
+    match cli.cmd {
+        Some(Cmd::Todo(TodoCmd::Batch(_))) => bail!("batches can't be nested"),
+        Some(Cmd::Todo(todo_cmd)) => Ok(todo_cmd),
+        Some(Cmd::Init(_)) => bail!("init can't be used in a batch"),
+        None => bail!("missing command"),
+    }
+}
+
+/// All todos of the store.
+fn _todo_refs(ctx: &Context) -> HashSet<TodoRef> {
+    ctx.store
+        .lists()
+        .iter()
+        .flat_map(|(list, todos)| {
+            todos.keys().map(|id| TodoRef {
+                list: Some(list.to_owned()),
+                id: *id,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, model::Status, store::Store};
+    use std::path::Path;
+
+    /// Runs the batch `input` against a new store written in `dir`.
+    fn batch(dir: &Path, input: &str) -> (Context, Result<()>) {
+        fs::create_dir_all(dir).unwrap();
+        let store = Store::create("default".to_owned(), Vec::new());
+        let store_path = dir.join(".todo");
+        store.write(&store_path).unwrap();
+        let mut ctx = Context::new(Config::default(), store_path, store);
+        let file = dir.join("batch.txt");
+        fs::write(&file, input).unwrap();
+        let result = BatchOptions { file }.run_mut(&mut ctx);
+        (ctx, result)
+    }
+
+    fn stored(ctx: &Context) -> Store {
+        Store::read(&ctx.store_path, &ctx.config).unwrap()
+    }
+
+    #[test]
+    fn substitute() {
+        let re = Regex::new(r"\$(\$|[A-Za-z_][A-Za-z0-9_-]*)").unwrap();
+        let labels = HashMap::from([("p".to_owned(), 3)]);
+        assert_eq!(_substitute(&re, "$p/*", &labels).unwrap(), "3/*");
+        assert_eq!(
+            _substitute(&re, "Set $$HOME, $$5", &labels).unwrap(),
+            "Set $HOME, $5"
+        );
+        assert_eq!(_substitute(&re, "costs 5$", &labels).unwrap(), "costs 5$");
+        assert!(_substitute(&re, "Set $HOME", &labels).is_err());
+    }
+
+    #[test]
+    fn labels() {
+        let dir = std::env::temp_dir()
+            .join(format!("taso-batch-labels-{}", std::process::id()));
+        let input = "# a release\n\
+                     $release = add --desc Release\n\
+                     $docs = add --desc \"Docs in $$DOCS\" --into $release\n\
+                     [\"add\", \"--desc\", \"Ship\", \"--into\", \"$release\"]\n\
+                     done $docs\n";
+        let (ctx, result) = batch(&dir, input);
+        result.unwrap();
+        let store = stored(&ctx);
+        let todos = store.todos(&"default".to_owned()).unwrap();
+        assert_eq!(todos[&1].desc, "Release");
+        assert_eq!(todos[&1].children, Some(vec![2, 3]));
+        assert_eq!(todos[&2].desc, "Docs in $DOCS");
+        assert_eq!(todos[&2].status(), Status::Done);
+        assert_eq!(todos[&3].parent, Some(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rollback() {
+        let dir = std::env::temp_dir()
+            .join(format!("taso-batch-rollback-{}", std::process::id()));
+        let input = "add --desc Plan\n\
+                     done 9\n\
+                     add --desc Never\n";
+        let (ctx, result) = batch(&dir, input);
+        let err = result.unwrap_err().to_string();
+        assert!(err.starts_with("line 2 [done 9], nothing is written"));
+        assert!(
+            stored(&ctx)
+                .todos(&"default".to_owned())
+                .unwrap()
+                .is_empty()
+        );
+
+        // unknown labels fail the same way
+        let (ctx, result) = batch(&dir, "add --desc \"Set $HOME\"\n");
+        assert!(result.unwrap_err().to_string().contains("[$HOME]"));
+        assert!(
+            stored(&ctx)
+                .todos(&"default".to_owned())
+                .unwrap()
+                .is_empty()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    command::RunMut, context::Context, model::TodoRef, util::get_list,
+};
+use anyhow::{Result, bail};
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct BlockOptions {
+    todo_id: u32,
+    /// Todo(s) blocking <TODO_ID>, `LIST_NAME#ID` refers to a todo in another list
+    #[arg(
+        short,
+        long,
+        required = true,
+        value_name = "TODO_ID",
+        value_delimiter = ','
+    )]
+    by: Vec<TodoRef>,
+    /// Block todo in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for BlockOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "block", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "block", "list [{list}]");
+
+        let target = TodoRef {
+            list: Some(list.clone()),
+            id: self.todo_id,
+        };
+        ctx.store.todo_by_id(&list, &self.todo_id)?;
+
+        let mut blockers = Vec::new();
+        for blocker in self.by {
+            let blocker = blocker.resolve(&list);
+            debug!(target: "block", "blocker {blocker}");
+            if ctx.store.todo_by_ref(&list, &blocker).is_none() {
+                bail!("todo [{blocker}] does not exist");
+            }
+            if blocker == target {
+                bail!("todo [{target}] can't block itself");
+            }
+            if ctx.store.depends_on(&blocker, &target) {
+                bail!("todo [{blocker}] already depends on todo [{target}]");
+            }
+            blockers.push(blocker);
+        }
+
+        let todo = ctx.store.todo_by_id_mut(&list, &self.todo_id)?;
+        let depends_on = todo.depends_on.get_or_insert_with(Vec::new);
+        for blocker in blockers {
+            if depends_on.contains(&blocker) {
+                debug!(target: "block", "todo [{blocker}] is already a blocker");
+                continue;
+            }
+            depends_on.push(blocker);
+        }
+
+        println!("{}", todo);
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    command::{RunMut, done::set_status},
+    context::Context,
+    model::Status,
+    ui::board::{BoardUI, Card},
+    util::{SyncBlock, get_list},
+};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use log::{debug, trace};
+use std::collections::BTreeSet;
+
+/// Columns of the board by status, from left to right.
+const COLUMNS: [Status; 4] = [
+    Status::Todo,
+    Status::Progress,
+    Status::Waiting,
+    Status::Done,
+];
+
+/// Title of the column of the open todos without any of the column tags.
+const UNTAGGED: &str = "Untagged";
+
+#[derive(Debug, Args)]
+pub struct BoardOptions {
+    /// Lay out todos in columns by status, or by tag
+    #[arg(short, long, value_name = "BY", default_value = "status")]
+    by: By,
+    /// Tag(s) of the columns by tag, e.g. backlog,review. All tags of the
+    /// list by default
+    #[arg(short, long, value_name = "TAG", value_delimiter = ',')]
+    tags: Vec<String>,
+    /// Lay out todos in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum By {
+    Status,
+    Tag,
+}
+
+impl RunMut for BoardOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "board", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "board", "list [{list}]");
+
+        // parents follow their children, only leaves are cards
+        let todos = ctx.store.todos(&list)?;
+        let mut ids: Vec<_> = todos
+            .iter()
+            .filter(|(_, todo)| {
+                todo.children.as_ref().is_none_or(|ch| ch.is_empty())
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort();
+
+        // the tags of the columns, after the untagged one
+        let tags: Vec<_> = match (self.by, self.tags.is_empty()) {
+            (By::Status, _) => Vec::new(),
+            (By::Tag, false) => self.tags.clone(),
+            (By::Tag, true) => ids
+                .iter()
+                .flat_map(|id| todos[id].tags.iter().flatten())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .cloned()
+                .collect(),
+        };
+        debug!(target: "board", "by {:?} {tags:?}", self.by);
+
+        let mut columns: Vec<_> = match self.by {
+            By::Status => COLUMNS
+                .iter()
+                .map(|status| (_column_title(status).to_owned(), Vec::new()))
+                .collect(),
+            By::Tag => [UNTAGGED.to_owned()]
+                .into_iter()
+                .chain(tags.iter().cloned())
+                .map(|tag| (tag, Vec::new()))
+                .collect(),
+        };
+        for id in ids {
+            let todo = ctx.store.todo_by_id(&list, &id)?;
+            let column = match self.by {
+                By::Status => COLUMNS.iter().position(|s| *s == todo.status()),
+                // closed todos are off a board by tag
+                By::Tag if !todo.status().is_open() => None,
+                By::Tag => Some(
+                    tags.iter()
+                        .position(|tag| {
+                            todo.tags.iter().flatten().any(|t| t == tag)
+                        })
+                        .map_or(0, |i| i + 1),
+                ),
+            };
+            let Some(column) = column else {
+                continue;
+            };
+            columns[column].1.push(Card {
+                id,
+                desc: todo.desc.clone(),
+                blocked: !ctx.store.open_blockers(&list, &id)?.is_empty(),
+            });
+        }
+
+        let rt = SyncBlock::new()?;
+        let title = format!("{list} ({})", ctx.store_path.to_string_lossy());
+        let Some(moved) = rt.block_on(BoardUI::run(&title, columns))? else {
+            debug!(target: "board", "changes discarded");
+            return Ok(());
+        };
+        debug!(target: "board", "moved {moved:?}");
+
+        match self.by {
+            By::Status => _move_status(ctx, &list, &moved)?,
+            By::Tag => _move_tag(ctx, &list, &tags, &moved)?,
+        }
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
+
+/// Sets the status of the todos `moved` into the columns by status.
+fn _move_status(
+    ctx: &mut Context,
+    list: &String,
+    moved: &[(u32, usize)],
+) -> Result<()> {
+    for (column, status) in COLUMNS.into_iter().enumerate() {
+        let todo_id: Vec<_> = moved
+            .iter()
+            .filter(|(_, c)| *c == column)
+            .map(|(id, _)| *id)
+            .collect();
+        if todo_id.is_empty() {
+            continue;
+        }
+        // only a reopened todo can leave DONE
+        if status != Status::Todo {
+            let reopened: Vec<_> = todo_id
+                .iter()
+                .copied()
+                .filter(|id| {
+                    ctx.store
+                        .todo_by_id(list, id)
+                        .is_ok_and(|t| t.status() == Status::Done)
+                })
+                .collect();
+            if !reopened.is_empty() {
+                set_status(ctx, list, reopened, Status::Todo)?;
+            }
+        }
+        let changed = set_status(ctx, list, todo_id, status)?;
+        changed.print(&status.to_string());
+    }
+    Ok(())
+}
+
+/// Swaps the column tags of the todos `moved` for the tag of their new
+/// column, the untagged one removes them all.
+fn _move_tag(
+    ctx: &mut Context,
+    list: &String,
+    tags: &[String],
+    moved: &[(u32, usize)],
+) -> Result<()> {
+    let mut changed = Vec::new();
+    for (id, column) in moved {
+        let todo = ctx.store.todo_by_id_mut(list, id)?;
+        let todo_tags = todo.tags.get_or_insert_with(Vec::new);
+        todo_tags.retain(|tag| !tags.contains(tag));
+        if let Some(tag) = column.checked_sub(1).map(|i| &tags[i]) {
+            todo_tags.push(tag.to_owned());
+        }
+        if todo_tags.is_empty() {
+            todo.tags = None;
+        }
+        debug!(target: "board", "todo [{id}] tags {:?}", todo.tags);
+        changed.push(*id);
+    }
+
+    println!("Tagged:");
+    println!("{changed:?}");
+    Ok(())
+}
+
+fn _column_title(status: &Status) -> &'static str {
+    match status {
+        Status::Todo => "Todo",
+        Status::Progress => "In Progress",
+        Status::Waiting => "Waiting",
+        Status::Cancelled => "Cancelled",
+        Status::Done => "Done",
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    command::Run,
+    context::Context,
+    date::{AbsDateOptions, DateRange, RelDateOptions, Tz, date_range},
+    model::{Status, Todo, TodoMap},
+    ui::chart::{Line, columns, svg_chart},
+    util::get_list,
+};
+use anyhow::{Result, anyhow};
+use clap::{Args, ValueEnum};
+use log::{debug, trace};
+use std::{fs, path::PathBuf};
+
+#[derive(Debug, Args)]
+pub struct BurndownOptions {
+    #[command(flatten)]
+    rel_date: Option<RelDateOptions>,
+    #[command(flatten)]
+    abs_date: Option<AbsDateOptions>,
+    /// Chart the subtree of PARENT_TODO_ID instead of the whole list
+    #[arg(short, long, value_name = "PARENT_TODO_ID")]
+    todo: Option<u32>,
+    /// Remaining todos (down) or completed todos against the scope (up)
+    #[arg(short, long, value_name = "KIND", default_value = "down")]
+    kind: Kind,
+    /// Export the chart to a standalone SVG file
+    #[arg(long, value_name = "PATH")]
+    svg: Option<PathBuf>,
+    /// Height of the chart in the terminal
+    #[arg(long, value_name = "ROWS", default_value_t = 10)]
+    height: usize,
+    /// Chart todos in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Kind {
+    Down,
+    Up,
+}
+
+impl Run for BurndownOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "burndown", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "burndown", "list [{list}]");
+
+        let todos = ctx.store.todos(&list)?;
+        let scope = match self.todo {
+            Some(id) => {
+                ctx.store.todo_by_id(&list, &id)?;
+                _leaves(todos, &id)
+            }
+            None => todos
+                .iter()
+                .filter(|(_, todo)| {
+                    todo.children.as_ref().is_none_or(|ch| ch.is_empty())
+                })
+                .map(|(_, todo)| todo)
+                .collect(),
+        };
+        let scope: Vec<_> = scope
+            .into_iter()
+            .filter(|todo| todo.status() != Status::Cancelled)
+            .collect();
+        debug!(target: "burndown", "{} todo(s) in scope", scope.len());
+
+        // from the first todo created until today if DATE_OPTION is omitted
+        let tz = ctx.config.timezone();
+        let today = tz.today();
+        let range = date_range(&self.rel_date, &self.abs_date, today)
+            .map_err(|err| anyhow!(err))?
+            .or_else(|| {
+                let start = scope.iter().map(|t| tz.date(&t.create_at())).min();
+                Some(DateRange {
+                    start: start.unwrap_or(today).min(today),
+                    end: today,
+                })
+            })
+            .ok_or_else(|| anyhow!("invalid date range"))?;
+        debug!(target: "burndown", "date range: {range:?}");
+
+        let (total, done) = _series(&scope, &range, tz);
+        let remaining: Vec<_> =
+            total.iter().zip(&done).map(|(t, d)| t - d).collect();
+
+        // straight line from the whole scope at the start to zero
+        let first = total.first().copied().unwrap_or(0) as f64;
+        let steps = remaining.len().saturating_sub(1).max(1) as f64;
+        let ideal: Vec<_> = (0..remaining.len())
+            .map(|i| first * (1.0 - i as f64 / steps))
+            .collect();
+
+        let title = match self.todo {
+            Some(id) => format!("{list}#{id}"),
+            None => list.to_owned(),
+        };
+        let max = total.iter().copied().max().unwrap_or(0);
+        let rows = match self.kind {
+            Kind::Down => {
+                columns(&remaining, None, Some(&ideal), max, self.height)
+            }
+            Kind::Up => columns(&done, Some(&total), None, max, self.height),
+        };
+
+        println!("{title} {} ~ {}", range.start, range.end);
+        for (i, row) in rows.iter().enumerate() {
+            let label = match i {
+                0 => max.to_string(),
+                _ if i + 1 == rows.len() => "0".to_owned(),
+                _ => String::new(),
+            };
+            println!("{label:>4} ┤{row}");
+        }
+        println!("     └{}", "─".repeat(remaining.len()));
+        match self.kind {
+            Kind::Down => println!(
+                "      remaining {} of {}, █ remaining · ideal",
+                remaining.last().unwrap_or(&0),
+                total.last().unwrap_or(&0)
+            ),
+            Kind::Up => println!(
+                "      done {} of {}, █ done ░ scope",
+                done.last().unwrap_or(&0),
+                total.last().unwrap_or(&0)
+            ),
+        }
+
+        if let Some(path) = self.svg {
+            let to_f64 = |v: &[usize]| -> Vec<f64> {
+                v.iter().map(|n| *n as f64).collect()
+            };
+            let (remaining, done, total) =
+                (to_f64(&remaining), to_f64(&done), to_f64(&total));
+            let lines = match self.kind {
+                Kind::Down => vec![
+                    Line {
+                        label: "remaining",
+                        color: "#d62728",
+                        values: &remaining,
+                        dashed: false,
+                    },
+                    Line {
+                        label: "ideal",
+                        color: "#999999",
+                        values: &ideal,
+                        dashed: true,
+                    },
+                ],
+                Kind::Up => vec![
+                    Line {
+                        label: "done",
+                        color: "#2ca02c",
+                        values: &done,
+                        dashed: false,
+                    },
+                    Line {
+                        label: "scope",
+                        color: "#1f77b4",
+                        values: &total,
+                        dashed: true,
+                    },
+                ],
+            };
+            let svg = svg_chart(
+                &title,
+                &range.start.to_string(),
+                &range.end.to_string(),
+                &lines,
+            );
+            fs::write(&path, svg)?;
+            debug!(target: "burndown", "svg written to {path:?}");
+            println!("Saved to {}", path.display());
+        }
+
+        Ok(())
+    }
+}
+
+/// Collects the todos without children in the subtree of todo `id`, or the
+/// todo itself if it has no children.
+fn _leaves<'a>(todos: &'a TodoMap, id: &u32) -> Vec<&'a Todo> {
+    let mut leaves = Vec::new();
+    let mut stack = vec![*id];
+    while let Some(id) = stack.pop() {
+        let Some(todo) = todos.get(&id) else {
+            continue;
+        };
+        match &todo.children {
+            Some(ch_ids) if !ch_ids.is_empty() => stack.extend(ch_ids),
+            _ => leaves.push(todo),
+        }
+    }
+    leaves
+}
+
+/// Counts the todos of `scope` created, and those of them completed, by
+/// each day of `range`. A todo completed before it was created (e.g. an
+/// imported one) counts as done once it is in scope.
+fn _series(
+    scope: &[&Todo],
+    range: &DateRange,
+    tz: Tz,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut total = Vec::new();
+    let mut done = Vec::new();
+    for date in range.days() {
+        let in_scope: Vec<_> = scope
+            .iter()
+            .filter(|t| tz.date(&t.create_at()) <= date)
+            .collect();
+        total.push(in_scope.len());
+        done.push(
+            in_scope
+                .iter()
+                .filter(|t| {
+                    t.complete_at().is_some_and(|c| tz.date(&c) <= date)
+                })
+                .count(),
+        );
+    }
+    (total, done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, NaiveDate};
+
+    #[test]
+    fn completed_before_created() {
+        let tz = Tz::Fixed(FixedOffset::east_opt(0).unwrap());
+        let date = |d: u32| NaiveDate::from_ymd_opt(2026, 9, d).unwrap();
+        let at = |d: u32| tz.start_of_day(date(d)).unwrap();
+        // imported on the 3rd, completed on the 1st
+        let old =
+            Todo::new("Old".to_owned(), None, None, None, at(3), Some(at(1)));
+        let open = Todo::new("Open".to_owned(), None, None, None, at(1), None);
+        let range = DateRange {
+            start: date(1),
+            end: date(4),
+        };
+        let (total, done) = _series(&[&old, &open], &range, tz);
+        assert_eq!(total, [1, 1, 2, 2]);
+        assert_eq!(done, [0, 0, 1, 1]);
+    }
+}
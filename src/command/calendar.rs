@@ -0,0 +1,112 @@
+use crate::{
+    command::Run,
+    context::Context,
+    date::{AbsDateOptions, date_range},
+    model::Todo,
+    ui::calendar::{CalendarUI, DayCount, grid},
+    util::SyncBlock,
+};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use clap::Args;
+use log::{debug, trace};
+use std::collections::HashMap;
+
+#[derive(Debug, Args)]
+pub struct CalendarOptions {
+    #[command(flatten)]
+    abs_date: Option<AbsDateOptions>,
+    /// Print the month grid as plain text instead of an interactive view
+    #[arg(long)]
+    plain: bool,
+    /// Only count todos in specified list, all lists by default
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl Run for CalendarOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "calendar", "{self:#?}");
+
+        // the current month if DATE_OPTION is omitted
+        let tz = ctx.config.timezone();
+        let today = tz.today();
+        let cursor = match date_range(&None, &self.abs_date, today)
+            .map_err(|err| anyhow!(err))?
+        {
+            Some(range) if !range.contains(today) => range.start,
+            _ => today,
+        };
+        debug!(target: "calendar", "cursor at {cursor}");
+
+        let lists: Vec<_> = match &self.list {
+            Some(list) => vec![(list, ctx.store.todos(list)?)],
+            None => ctx.store.lists().iter().collect(),
+        };
+
+        let mut counts: HashMap<NaiveDate, DayCount> = HashMap::new();
+        for todo in lists.iter().flat_map(|(_, todos)| todos.values()) {
+            if let Some(date) = _due(todo) {
+                counts.entry(date).or_default().due += 1;
+            }
+            counts
+                .entry(tz.date(&todo.create_at()))
+                .or_default()
+                .created += 1;
+            if let Some(complete_at) = todo.complete_at() {
+                counts.entry(tz.date(&complete_at)).or_default().completed += 1;
+            }
+        }
+
+        if self.plain {
+            for row in grid(cursor, None, today, &counts) {
+                println!("{}", row.to_string().trim_end());
+            }
+            return Ok(());
+        }
+
+        let rt = SyncBlock::new()?;
+        let Some(day) = rt.block_on(CalendarUI::run(cursor, today, counts))?
+        else {
+            return Ok(());
+        };
+        debug!(target: "calendar", "picked {day}");
+
+        println!("{day}");
+        let mut lists = lists;
+        lists.sort_by_key(|(list, _)| *list);
+        for (list, todos) in lists {
+            let mut ids: Vec<_> = todos.keys().collect();
+            ids.sort();
+            for id in ids {
+                let todo = &todos[id];
+                let mut events = Vec::new();
+                if _due(todo) == Some(day) {
+                    events.push("due");
+                }
+                if tz.date(&todo.create_at()) == day {
+                    events.push("created");
+                }
+                if todo.complete_at().is_some_and(|c| tz.date(&c) == day) {
+                    events.push("completed");
+                }
+                if events.is_empty() {
+                    continue;
+                }
+                println!(
+                    "  {}{list}#{id} {} ({})",
+                    todo.status().marker(),
+                    todo.desc,
+                    events.join(", ")
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Planned date of `todo`, as long as it is still due.
+fn _due(todo: &Todo) -> Option<NaiveDate> {
+    todo.assign_at.filter(|_| todo.status().is_open())
+}
@@ -0,0 +1,33 @@
+use crate::{
+    command::{RunMut, done::set_status},
+    context::Context,
+    model::Status,
+    util::get_list,
+};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct CancelOptions {
+    #[arg(required = true, value_delimiter = ',')]
+    todo_id: Vec<u32>,
+    /// Cancel todo(s) in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for CancelOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "cancel", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "cancel", "list [{list}]");
+        debug!(target: "cancel", "todo [{:?}]", self.todo_id);
+
+        let changed = set_status(ctx, &list, self.todo_id, Status::Cancelled)?;
+        changed.print("Cancelled");
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
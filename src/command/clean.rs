@@ -1,12 +1,14 @@
 use crate::{
     command::RunMut,
     context::Context,
-    util::{check_key, get_list},
+    date::{AbsDateOptions, DateRange, RelDateOptions, date_range},
+    model::Status,
+    util::{get_list, get_query},
 };
-use anyhow::Result;
-use chrono::Local;
+use anyhow::{Result, anyhow, bail};
+use chrono::NaiveDate;
 use clap::Args;
-use log::{debug, trace, warn};
+use log::{debug, trace};
 
 #[derive(Debug, Args)]
 pub struct CleanOptions {
@@ -14,6 +16,18 @@ pub struct CleanOptions {
     rel_date: Option<RelDateOptions>,
     #[command(flatten)]
     abs_date: Option<AbsDateOptions>,
+    /// Clean up todos in specified status(es)
+    #[arg(
+        short,
+        long,
+        value_name = "STATUS",
+        value_delimiter = ',',
+        default_value = "done"
+    )]
+    status: Vec<Status>,
+    /// Only clean up todos matching QUERY, e.g. `status:open and tag:bug`
+    #[arg(short, long, value_name = "QUERY")]
+    query: Option<String>,
     /// Clean up todos in specified list
     #[arg(short, long, value_name = "LIST_NAME")]
     list: Option<String>,
@@ -26,42 +40,72 @@ impl RunMut for CleanOptions {
         let list = get_list(self.list, ctx);
         debug!(target: "clean", "list [{list}]");
 
-        let before = Local::now().date_naive();
-        debug!(target: "clean", "before date: {before}");
+        // only done todos carry a date to filter on
+        let tz = ctx.config.timezone();
+        let today = tz.today();
+        let range = date_range(&self.rel_date, &self.abs_date, today)
+            .map_err(|err| anyhow!(err))?;
+        if range.is_some() && self.status.iter().any(|s| *s != Status::Done) {
+            bail!("DATE_OPTION can only be used with `--status done`");
+        }
+        // everything completed until today if DATE_OPTION is omitted
+        let range = range.unwrap_or(DateRange {
+            start: NaiveDate::MIN,
+            end: today,
+        });
+        debug!(target: "clean", "date range: {range:?}");
 
+        let query = get_query(self.query.as_deref(), ctx)?;
         let todos = ctx.store.todos(&list)?;
         let mut clean_id = Vec::new();
 
         for (id, todo) in todos.iter() {
-            let Some(complete_at) = todo.complete_at else {
-                debug!(target: "clean", "todo [{id}] to be completed");
+            let status = todo.status();
+            if !self.status.contains(&status) {
+                debug!(target: "clean", "todo [{id}] is {status}");
                 continue;
-            };
-            debug!(target: "clean", "todo [{id}] {complete_at} before {before}: {}", complete_at <= before);
-            if complete_at > before {
+            }
+            if todo.complete_at().is_some_and(|complete_at| {
+                !range.contains(tz.date(&complete_at))
+            }) {
                 debug!(target: "clean", "todo [{id}] is skipped");
                 continue;
             }
-            clean_id.push(id.clone());
+            if query.as_ref().is_some_and(|q| !q.matches(todos, id, tz)) {
+                debug!(target: "clean", "todo [{id}] doesn't match the query");
+                continue;
+            }
+            clean_id.push(*id);
+        }
+
+        // a parent stays as long as one of its children does
+        loop {
+            let kept: Vec<_> = clean_id
+                .iter()
+                .copied()
+                .filter(|id| {
+                    todos[id].children.iter().flatten().any(|ch_id| {
+                        todos.contains_key(ch_id) && !clean_id.contains(ch_id)
+                    })
+                })
+                .collect();
+            if kept.is_empty() {
+                break;
+            }
+            debug!(target: "clean", "todo [{kept:?}] still have children");
+            clean_id.retain(|id| !kept.contains(id));
         }
 
         debug!(target: "clean", "to be cleaned: todo [{clean_id:?}]");
-        if clean_id.len() <= 0 {
+        if clean_id.is_empty() {
             debug!(target: "clean", "nothing be cleaned");
             return Ok(());
         }
 
-        let todos_mut = ctx.store.todos_mut(&list)?;
-        let mut cleaned_todos = Vec::new();
-
-        for id in &clean_id {
-            check_key(todos_mut, id)?;
-            let Some(cleaned) = todos_mut.remove(id) else {
-                warn!(target: "clean", "[{id}] can't remove");
-                continue;
-            };
-            cleaned_todos.push(cleaned);
-        }
+        let now = tz.now();
+        let cleaned_todos =
+            ctx.store.todos_mut(&list)?.remove_todos(&clean_id, now);
+        ctx.store.forget(&list, |id| clean_id.contains(id));
 
         println!("{:#?}", cleaned_todos);
 
@@ -69,72 +113,3 @@ impl RunMut for CleanOptions {
         Ok(())
     }
 }
-
-#[derive(Debug, Args)]
-#[group(multiple = false, conflicts_with = "AbsDateOptions")]
-#[command(next_help_heading = "Relative Date")]
-pub struct RelDateOptions {
-    /// (default: 0) Relative date in DAY. <+OFFSET> indicates future and <-OFFSET> is past
-    #[arg(
-                short = 'd',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0",
-                next_line_help = true
-            )]
-    pub day_rel: Option<i32>,
-    /// (default: 0) Relative date in WEEK. Same as --day_rel
-    #[arg(
-                short = 'w',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0"
-            )]
-    pub week_rel: Option<i32>,
-    /// (default: 0) Relative date in MONTH. Same as --day_rel
-    #[arg(
-                short = 'm',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0"
-            )]
-    pub month_rel: Option<i32>,
-    /// (default: 0) Relative date in YEAR. Same as --day_rel
-    #[arg(
-                short = 'y',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0"
-            )]
-    pub year_rel: Option<i32>,
-}
-
-#[derive(Debug, Args)]
-#[group(multiple = true)]
-#[command(next_help_heading = "Absolute Date")]
-pub struct AbsDateOptions {
-    /// Specify a date
-    #[arg(
-                short = 'D',
-                long,
-                value_name = "YYYY-MM-DD",
-                num_args = 1,
-                conflicts_with_all = ["month", "year"],
-                next_line_help = true
-            )]
-    pub date: Option<String>,
-    /// <N> must be 1~12 indicating the N-th month of a year, and the current year is assumed by default
-    #[arg(short = 'M', long, value_name = "N", num_args = 1)]
-    pub month: Option<u32>,
-    /// (default: current year) <N> must be a positive integer indicating the specific year
-    #[arg(short = 'Y', long, value_name = "N", num_args = 1)]
-    pub year: Option<u32>,
-}
@@ -1,18 +1,25 @@
-use crate::{command::RunMut, context::Context, util::get_list};
-use anyhow::Result;
-use chrono::{Local, NaiveDate};
+use crate::{
+    command::RunMut,
+    context::Context,
+    model::{Status, Todo, TodoRef, effort::Effort},
+    selection::SelectionOptions,
+    util::get_list,
+};
+use anyhow::{Result, bail};
+use chrono::{NaiveDate, TimeDelta};
 use clap::Args;
 use log::{debug, trace, warn};
 use std::collections::HashSet;
 
 #[derive(Debug, Args)]
 pub struct DoneOptions {
-    #[arg(required = true, value_delimiter = ',')]
-    todo_id: Vec<u32>,
+    #[command(flatten)]
+    selection: SelectionOptions,
     /// Undo the completed todo(s)
     #[arg(short, long)]
     undo: bool,
-    /// Mark exist todo(s) and its (or their) children as DONE recursively
+    /// Mark exist todo(s) and its (or their) children as DONE recursively.
+    /// Without it, todo(s) with open children are refused
     #[arg(short, long)]
     recursive: bool,
     /// Mark todo(s) in specified list
@@ -27,27 +34,77 @@ impl RunMut for DoneOptions {
         let list = get_list(self.list, ctx);
         debug!(target: "done", "list [{list}]");
 
+        let action = if self.undo {
+            "reopened"
+        } else {
+            "marked as done"
+        };
+        let Some(mut selected) = self.selection.select(ctx, &list, action)?
+        else {
+            return Ok(());
+        };
+
         let todo_id = if self.recursive {
-            _recursive_todo(&ctx, &list, &self.todo_id)?
+            // children go first, so that parents are completed by roll-up
+            let todos = ctx.store.todos(&list)?;
+            let mut todo_id = _recursive_todo(ctx, &list, &selected)?;
+            todo_id.retain(|id| {
+                selected.contains(id)
+                    || todos
+                        .get(id)
+                        .is_some_and(|t| t.status() != Status::Cancelled)
+            });
+            todo_id.reverse();
+            todo_id
         } else {
-            self.todo_id
+            // children go first, so that selected parents can follow them
+            let todos = ctx.store.todos(&list)?;
+            selected
+                .sort_by_key(|id| std::cmp::Reverse(todos.ancestors(id).len()));
+            selected
         };
         debug!(target: "done", "todo [{todo_id:?}]");
 
-        let (success, skip) = if self.undo {
-            _done(ctx, &list, todo_id, None)?
+        if !self.recursive && !self.undo {
+            let todos = ctx.store.todos(&list)?;
+            let refused: Vec<_> = todo_id
+                .iter()
+                .filter(|id| {
+                    todos[*id].children.iter().flatten().any(|ch_id| {
+                        !todo_id.contains(ch_id)
+                            && todos
+                                .get(ch_id)
+                                .is_some_and(|ch| ch.status().is_open())
+                    })
+                })
+                .collect();
+            if !refused.is_empty() {
+                bail!(
+                    "todo {refused:?} still have open children, use --recursive to mark them as DONE as well"
+                );
+            }
+        }
+
+        let status = if self.undo {
+            Status::Todo
         } else {
-            _done(ctx, &list, todo_id, Some(Local::now().date_naive()))?
+            Status::Done
         };
-        debug!(target: "done", "success todo [{success:?}]");
-        debug!(target: "done", "skip todo [{skip:?}]");
-
-        println!("Done:");
-        println!("{success:?}");
+        let changed = set_status(ctx, &list, todo_id, status)?;
+        changed.print("Done");
 
-        if skip.len() > 0 {
-            println!("Skip:");
-            println!("{skip:?}");
+        if !self.undo {
+            let mut spawned = Vec::new();
+            for id in changed.success.iter().chain(&changed.roll_up) {
+                if let Some(next_id) = _spawn_next(ctx, &list, id)? {
+                    spawned.push(next_id);
+                }
+            }
+            debug!(target: "done", "spawned todo [{spawned:?}]");
+            if !spawned.is_empty() {
+                println!("Next:");
+                println!("{spawned:?}");
+            }
         }
 
         ctx.store.write(&ctx.store_path)?;
@@ -55,31 +112,181 @@ impl RunMut for DoneOptions {
     }
 }
 
-fn _done(
+/// Result of [`set_status`].
+#[derive(Debug, Default)]
+pub(super) struct StatusChanged {
+    /// Todo(s) moved into the requested status.
+    pub success: Vec<u32>,
+    /// Todo(s) already in the requested status (or can't leave `Done`).
+    pub skip: Vec<u32>,
+    /// Parent todo(s) completed or reopened from their children.
+    pub roll_up: Vec<u32>,
+    /// Todo(s) started or completed while their blockers are still open.
+    pub blocked: Vec<(u32, Vec<TodoRef>)>,
+    /// Timer stopped because its todo is no longer in progress.
+    pub stopped: Option<(TodoRef, Effort)>,
+}
+
+impl StatusChanged {
+    pub fn print(&self, title: &str) {
+        debug!(target: "done::print", "{self:?}");
+        println!("{title}:");
+        println!("{:?}", self.success);
+
+        if !self.skip.is_empty() {
+            println!("Skip:");
+            println!("{:?}", self.skip);
+        }
+        if !self.roll_up.is_empty() {
+            println!("Roll up:");
+            println!("{:?}", self.roll_up);
+        }
+        for (id, blockers) in &self.blocked {
+            let blockers: Vec<_> =
+                blockers.iter().map(|r| r.to_string()).collect();
+            println!(
+                "Warning: todo [{id}] is still blocked by {}",
+                blockers.join(",")
+            );
+        }
+        if let Some((todo, effort)) = &self.stopped {
+            println!("Timer stopped:");
+            println!("{todo} {effort}");
+        }
+    }
+}
+
+/// Moves todo(s) into `status`, and rolls the change up to their parents.
+pub(super) fn set_status(
     ctx: &mut Context,
     list: &String,
     todo_id: Vec<u32>,
-    complete_at: Option<NaiveDate>,
-) -> Result<(Vec<u32>, Vec<u32>)> {
-    trace!(target: "done::_done", "{todo_id:?}");
-    debug!(target: "done::_done", "complete at: {complete_at:?}");
+    status: Status,
+) -> Result<StatusChanged> {
+    trace!(target: "done::set_status", "{todo_id:?}");
+    debug!(target: "done::set_status", "status: {status}");
 
-    let mut result = Vec::new();
-    let mut skip = Vec::new();
+    let now = ctx.config.timezone().now();
+    let mut changed = StatusChanged::default();
 
     for id in todo_id.into_iter() {
-        debug!(target: "done::_done", "todo [{id}]");
+        debug!(target: "done::set_status", "todo [{id}]");
         let todo = ctx.store.todo_by_id_mut(list, &id)?;
-        if complete_at != None && todo.complete_at != None {
-            debug!(target: "done::_done", "skip todo [{id}]");
-            skip.push(id);
+        if !todo.set_status(status, now) {
+            if !changed.roll_up.contains(&id) {
+                debug!(target: "done::set_status", "skip todo [{id}]");
+                changed.skip.push(id);
+            }
             continue;
         }
-        todo.complete_at = complete_at;
-        result.push(id);
+        changed.success.push(id);
+        let pa_id = todo.parent;
+
+        if matches!(status, Status::Progress | Status::Done) {
+            let blockers = ctx.store.open_blockers(list, &id)?;
+            if !blockers.is_empty() {
+                debug!(target: "done::set_status", "todo [{id}] is blocked by {blockers:?}");
+                changed.blocked.push((id, blockers));
+            }
+        }
+
+        if let Some(pa_id) = pa_id {
+            let rolled = ctx.store.todos_mut(list)?.roll_up(pa_id, now);
+            debug!(target: "done::set_status", "roll up todo [{rolled:?}]");
+            changed.roll_up.retain(|id| !rolled.contains(id));
+            changed.roll_up.extend(rolled);
+        }
+    }
+
+    // the timer only runs while its todo is in progress
+    let paused = ctx.store.timer().is_some_and(|timer| {
+        timer.todo.list_or(list) == list
+            && ctx
+                .store
+                .todo_by_id(list, &timer.todo.id)
+                .is_ok_and(|todo| todo.status() != Status::Progress)
+    });
+    if paused {
+        changed.stopped = ctx.store.stop_timer(now);
+        debug!(target: "done::set_status", "stop timer {:?}", changed.stopped);
+    }
+
+    Ok(changed)
+}
+
+/// Spawns the next occurrence of todo `id` if it is a completed recurring
+/// todo, its children are copied as well. The recurrence rule moves to the new
+/// todo, the completed one is kept as a record.
+fn _spawn_next(
+    ctx: &mut Context,
+    list: &String,
+    id: &u32,
+) -> Result<Option<u32>> {
+    let tz = ctx.config.timezone();
+    let todo = ctx.store.todo_by_id_mut(list, id)?;
+    let Some(complete_at) = todo.complete_at() else {
+        return Ok(None);
+    };
+    let Some(recur) = todo.recur.take() else {
+        return Ok(None);
+    };
+    let scheduled = todo.assign_at.unwrap_or(tz.date(&todo.create_at()));
+    let assign_at = recur.next(scheduled, tz.date(&complete_at));
+    debug!(target: "done::_spawn_next", "todo [{id}] {recur}: {scheduled} -> {assign_at}");
+
+    let parent = todo.parent;
+    let next_id = _copy_subtree(ctx, list, id, parent, assign_at - scheduled)?;
+    let next_todo = ctx.store.todo_by_id_mut(list, &next_id)?;
+    next_todo.assign_at = Some(assign_at);
+    next_todo.recur = Some(recur);
+
+    if let Some(pa_id) = parent {
+        let pa_todo = ctx.store.todo_by_id_mut(list, &pa_id)?;
+        pa_todo.children.get_or_insert_with(Vec::new).push(next_id);
+        let rolled = ctx.store.todos_mut(list)?.roll_up(pa_id, tz.now());
+        debug!(target: "done::_spawn_next", "roll up todo [{rolled:?}]");
+    }
+
+    Ok(Some(next_id))
+}
+
+/// Copies todo `id` and its children as new open todos under `parent`,
+/// planned dates are moved by `shift`. Returns the id of the copy.
+fn _copy_subtree(
+    ctx: &mut Context,
+    list: &String,
+    id: &u32,
+    parent: Option<u32>,
+    shift: TimeDelta,
+) -> Result<u32> {
+    let todo = ctx.store.todo_by_id(list, id)?.clone();
+    let new_id = ctx.store.generate_id(list)?;
+    debug!(target: "done::_copy_subtree", "todo [{id}] -> [{new_id}]");
+
+    let mut copy = Todo::new(
+        todo.desc,
+        todo.link,
+        None,
+        parent,
+        ctx.config.timezone().now(),
+        None,
+    );
+    copy.assign_at = todo.assign_at.map(|d: NaiveDate| d + shift);
+    copy.tags = todo.tags;
+    copy.priority = todo.priority;
+    copy.estimate = todo.estimate;
+    copy.notes = todo.notes;
+    ctx.store.todos_mut(list)?.insert(new_id, copy);
+
+    let mut children = Vec::new();
+    for ch_id in todo.children.iter().flatten() {
+        children.push(_copy_subtree(ctx, list, ch_id, Some(new_id), shift)?);
+    }
+    if !children.is_empty() {
+        ctx.store.todo_by_id_mut(list, &new_id)?.children = Some(children);
     }
 
-    Ok((result, skip))
+    Ok(new_id)
 }
 
 fn _recursive_todo(
@@ -104,7 +311,7 @@ fn _recursive_todo(
             warn!(target: "done::recursive_todo", "todo [{id}] is not exist");
             continue;
         };
-        visited.insert(id.clone());
+        visited.insert(*id);
         debug!(target: "done::recursive_todo", "todo [{id}] is visited");
         if let Some(ch_ids) = &todo.children {
             for ch_id in ch_ids {
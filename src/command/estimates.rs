@@ -0,0 +1,159 @@
+use crate::{
+    command::Run,
+    context::Context,
+    date::{AbsDateOptions, RelDateOptions, date_range},
+    model::{
+        Status,
+        effort::{Effort, Estimate},
+    },
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct EstimatesOptions {
+    #[command(flatten)]
+    rel_date: Option<RelDateOptions>,
+    #[command(flatten)]
+    abs_date: Option<AbsDateOptions>,
+    /// Only report todos in specified list, all lists by default
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+/// Estimated and actual effort of completed todos.
+#[derive(Debug, Default)]
+struct Summary {
+    /// Estimated time and the time actually spent on those todos.
+    estimated: Effort,
+    estimated_actual: Effort,
+    /// Story points and the time actually spent on those todos.
+    points: u32,
+    points_actual: Effort,
+}
+
+impl Summary {
+    fn add(&mut self, estimate: Estimate, actual: Effort) {
+        // a todo estimated in both is counted as estimated in time
+        if estimate.time.is_zero() {
+            self.points += estimate.points;
+            self.points_actual += actual;
+        } else {
+            self.estimated += estimate.time;
+            self.estimated_actual += actual;
+        }
+    }
+
+    fn print(&self, indent: &str) {
+        if !self.estimated.is_zero() {
+            println!(
+                "{indent}time:   {} estimated, {} spent ({})",
+                self.estimated,
+                self.estimated_actual,
+                _ratio(self.estimated_actual, self.estimated)
+            );
+        }
+        if self.points > 0 {
+            let per_point = Effort::from_secs(
+                self.points_actual.as_secs() / u64::from(self.points),
+            );
+            println!(
+                "{indent}points: {}pt in {} ({per_point} per point)",
+                self.points, self.points_actual
+            );
+        }
+    }
+}
+
+impl Run for EstimatesOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "estimates", "{self:#?}");
+
+        // everything completed if DATE_OPTION is omitted
+        let tz = ctx.config.timezone();
+        let range = date_range(&self.rel_date, &self.abs_date, tz.today())
+            .map_err(|err| anyhow!(err))?;
+        debug!(target: "estimates", "date range: {range:?}");
+
+        let mut lists: Vec<_> = match &self.list {
+            Some(list) => vec![(list, ctx.store.todos(list)?)],
+            None => ctx.store.lists().iter().collect(),
+        };
+        lists.sort_by_key(|(list, _)| *list);
+
+        let mut total = Summary::default();
+        let mut reported = 0;
+        for (list, todos) in lists {
+            let mut rows: Vec<_> = todos
+                .iter()
+                .filter(|(_, todo)| todo.status() == Status::Done)
+                .filter(|(_, todo)| {
+                    todo.complete_at().is_some_and(|complete_at| {
+                        range.is_none_or(|r| r.contains(tz.date(&complete_at)))
+                    })
+                })
+                .map(|(id, todo)| (id, todo, todos.total_estimate(id)))
+                .filter(|(.., estimate)| !estimate.is_zero())
+                .collect();
+            debug!(target: "estimates", "list [{list}]: {} todo(s)", rows.len());
+            if rows.is_empty() {
+                continue;
+            }
+            rows.sort_by_key(|(id, ..)| *id);
+
+            println!("{list}");
+            println!(
+                "  {:>12}  {:>12}  {:>6}  Todo",
+                "Estimate", "Spent", "Ratio"
+            );
+            let ids: Vec<_> = rows.iter().map(|(id, ..)| **id).collect();
+            let mut summary = Summary::default();
+            for (id, todo, estimate) in rows {
+                // estimates and time summed over the subtree, as in the tree
+                let actual = todos.total_spent(id);
+                let ratio = if estimate.time.is_zero() {
+                    String::new()
+                } else {
+                    _ratio(actual, estimate.time)
+                };
+                println!(
+                    "  {:>12}  {:>12}  {ratio:>6}  #{id} {}",
+                    estimate.to_string(),
+                    actual.to_string(),
+                    todo.desc
+                );
+                // a subtree is counted once, with its reported ancestor
+                if todos.ancestors(id).iter().any(|pa_id| ids.contains(pa_id)) {
+                    continue;
+                }
+                summary.add(estimate, actual);
+                total.add(estimate, actual);
+            }
+            summary.print("  ");
+            reported += 1;
+        }
+
+        match reported {
+            0 => println!("No completed todo with an estimate"),
+            1 => {}
+            _ => {
+                println!("Total");
+                total.print("  ");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// `actual` in percent of `estimated`.
+fn _ratio(actual: Effort, estimated: Effort) -> String {
+    if estimated.is_zero() {
+        return String::new();
+    }
+    format!(
+        "{}%",
+        u128::from(actual.as_secs()) * 100 / u128::from(estimated.as_secs())
+    )
+}
@@ -0,0 +1,97 @@
+use crate::{
+    command::Run,
+    context::Context,
+    format::{
+        ExportedList, Format, Report, csv, ical, markdown, org, print_report,
+        taskwarrior, todotxt,
+    },
+    selection::Selector,
+    util::{get_list, get_query},
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use log::{debug, trace};
+use std::{fs, path::PathBuf};
+
+#[derive(Debug, Args)]
+pub struct ExportOptions {
+    /// Format of the exported todos, guessed from the extension of --output
+    /// if missing
+    #[arg(short, long, value_name = "FORMAT")]
+    format: Option<Format>,
+    /// Write to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Only export todos matching QUERY, e.g. `status:open and tag:bug`
+    #[arg(short, long, value_name = "QUERY")]
+    query: Option<String>,
+    /// Only export PARENT_TODO_ID and its subtodos
+    #[arg(short, long, value_name = "PARENT_TODO_ID")]
+    todo: Option<u32>,
+    /// Export todos of all lists
+    #[arg(short, long, conflicts_with_all = ["list", "todo"])]
+    all: bool,
+    /// Export todos in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl Run for ExportOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "export", "{self:#?}");
+
+        let format = self
+            .format
+            .or_else(|| self.output.as_deref().and_then(Format::from_path))
+            .ok_or_else(|| {
+                anyhow!("the format can't be guessed, give it with --format")
+            })?;
+        debug!(target: "export", "format: {format:?}");
+
+        let mut lists: Vec<_> = if self.all {
+            ctx.store.lists().keys().cloned().collect()
+        } else {
+            vec![get_list(self.list, ctx)]
+        };
+        lists.sort();
+        let query = get_query(self.query.as_deref(), ctx)?;
+        let tz = ctx.config.timezone();
+
+        let mut exported = Vec::new();
+        for list in &lists {
+            let todos = ctx.store.todos(list)?;
+            let mut ids = match &query {
+                Some(query) => query.select(todos, tz),
+                None => todos.keys().copied().collect(),
+            };
+            if let Some(id) = self.todo {
+                let mut subtree = Selector::Descendants(id).select(todos)?;
+                subtree.push(id);
+                ids.retain(|id| subtree.contains(id));
+            }
+            ids.sort();
+            debug!(target: "export", "list [{list}]: {} todo(s)", ids.len());
+            exported.push(ExportedList { list, todos, ids });
+        }
+
+        let (output, report) = match format {
+            Format::Todotxt => (todotxt::export(&exported, tz), Report::new()),
+            Format::Markdown => {
+                (markdown::export(&exported, tz), Report::new())
+            }
+            Format::Ical => (ical::export(&exported, tz), Report::new()),
+            Format::Taskwarrior => taskwarrior::export(&exported, tz),
+            Format::Csv => csv::export(&exported),
+            Format::Org => (org::export(&exported, tz), Report::new()),
+        };
+        print_report(&report);
+        match self.output {
+            Some(path) => {
+                fs::write(&path, output)?;
+                debug!(target: "export", "written to {path:?}");
+            }
+            None => print!("{output}"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,379 @@
+use crate::{
+    command::Run,
+    context::Context,
+    model::{Status, TodoMap},
+    util::get_list,
+};
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use log::{debug, trace};
+use std::{collections::HashMap, fs, path::PathBuf};
+
+#[derive(Debug, Args)]
+pub struct GraphOptions {
+    /// Text format of the graph
+    #[arg(short, long, value_name = "FORMAT", default_value = "dot")]
+    format: GraphFormat,
+    /// Write to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// Graph the subtree of PARENT_TODO_ID instead of the whole list
+    #[arg(short, long, value_name = "PARENT_TODO_ID")]
+    todo: Option<u32>,
+    /// Only graph todos up to DEPTH levels below the top ones (or
+    /// PARENT_TODO_ID)
+    #[arg(short, long, value_name = "DEPTH")]
+    depth: Option<usize>,
+    /// Graph todos of all lists
+    #[arg(short, long, conflicts_with_all = ["list", "todo"])]
+    all: bool,
+    /// Graph todos in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum GraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+/// A todo drawn as a node, named by its position in the graph.
+struct Node<'a> {
+    name: String,
+    id: u32,
+    todos: &'a TodoMap,
+}
+
+/// Edges between node names, `true` for a blocker.
+type Edges = Vec<(String, String, bool)>;
+
+impl Run for GraphOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "graph", "{self:#?}");
+
+        let mut lists: Vec<_> = if self.all {
+            ctx.store.lists().keys().cloned().collect()
+        } else {
+            vec![get_list(self.list, ctx)]
+        };
+        lists.sort();
+
+        let mut todos = Vec::new();
+        for list in &lists {
+            if let Some(id) = self.todo {
+                ctx.store.todo_by_id(list, &id)?;
+            }
+            todos.push((list.as_str(), ctx.store.todos(list)?));
+        }
+        let (clusters, edges) = _graph(&todos, self.todo, self.depth);
+
+        let output = match self.format {
+            GraphFormat::Dot => _dot(&clusters, &edges),
+            GraphFormat::Mermaid => _mermaid(&clusters, &edges),
+        };
+        match self.output {
+            Some(path) => {
+                fs::write(&path, output)?;
+                debug!(target: "graph", "written to {path:?}");
+            }
+            None => print!("{output}"),
+        }
+        Ok(())
+    }
+}
+
+/// Collects the nodes of `todo` (or the top todos) of each list down to
+/// `depth`, parents before their children, and the edges between them.
+fn _graph<'a>(
+    lists: &[(&'a str, &'a TodoMap)],
+    todo: Option<u32>,
+    depth: Option<usize>,
+) -> (Vec<(&'a str, Vec<Node<'a>>)>, Edges) {
+    let mut clusters = Vec::new();
+    let mut names = HashMap::new();
+    for (list, todos) in lists {
+        let roots = match todo {
+            Some(id) => vec![id],
+            None => {
+                let mut roots: Vec<_> = todos
+                    .iter()
+                    .filter(|(_, todo)| todo.parent.is_none())
+                    .map(|(id, _)| *id)
+                    .collect();
+                roots.sort();
+                roots
+            }
+        };
+        let mut nodes = Vec::new();
+        for id in roots {
+            _walk(todos, id, 0, depth, &mut |id| {
+                let name = format!("t{}", names.len() + 1);
+                names.insert((*list, id), name.clone());
+                nodes.push(Node { name, id, todos });
+            });
+        }
+        debug!(target: "graph", "list [{list}]: {} node(s)", nodes.len());
+        clusters.push((*list, nodes));
+    }
+
+    let mut edges = Edges::new();
+    for (list, nodes) in &clusters {
+        for node in nodes {
+            let todo = &node.todos[&node.id];
+            let parent =
+                todo.parent.and_then(|pa_id| names.get(&(*list, pa_id)));
+            if let Some(parent) = parent {
+                edges.push((parent.clone(), node.name.clone(), false));
+            }
+            for blocker in todo.depends_on.iter().flatten() {
+                let key = (blocker.list_or(list), blocker.id);
+                if let Some(blocker) = names.get(&key) {
+                    edges.push((blocker.clone(), node.name.clone(), true));
+                }
+            }
+        }
+    }
+    debug!(target: "graph", "{} edge(s)", edges.len());
+    (clusters, edges)
+}
+
+/// Visits todo `id` and its children in order, down to `max_depth`.
+fn _walk(
+    todos: &TodoMap,
+    id: u32,
+    depth: usize,
+    max_depth: Option<usize>,
+    visit: &mut impl FnMut(u32),
+) {
+    visit(id);
+    if max_depth.is_some_and(|max| depth >= max) {
+        return;
+    }
+    let children = todos.get(&id).and_then(|todo| todo.children.as_ref());
+    for ch_id in children.into_iter().flatten() {
+        _walk(todos, *ch_id, depth + 1, max_depth, visit);
+    }
+}
+
+/// Returns the lines of the label of `node`, with the completion of a
+/// parent todo.
+fn _label(node: &Node) -> Vec<String> {
+    let todo = &node.todos[&node.id];
+    let mut label = vec![format!("#{} {}", node.id, todo.desc)];
+    if todo.children.as_ref().is_some_and(|ch| !ch.is_empty()) {
+        let completion = node.todos.completion(&node.id) * 100.0;
+        label.push(format!("{completion:.0}%"));
+    }
+    label
+}
+
+fn _dot(clusters: &[(&str, Vec<Node>)], edges: &Edges) -> String {
+    let escape = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut output = String::from("digraph taso {\n");
+    output.push_str(
+        "  node [shape=box, style=\"rounded,filled\", fillcolor=white];\n",
+    );
+    for (i, (list, nodes)) in clusters.iter().enumerate() {
+        output.push_str(&format!("  subgraph cluster_{i} {{\n"));
+        output.push_str(&format!("    label=\"{}\";\n", escape(list)));
+        for node in nodes {
+            let label: Vec<_> =
+                _label(node).iter().map(|line| escape(line)).collect();
+            let style = match node.todos[&node.id].status() {
+                Status::Todo => "",
+                Status::Progress => ", fillcolor=lightyellow",
+                Status::Waiting => ", fillcolor=lightblue",
+                Status::Cancelled => ", fillcolor=lightgray, fontcolor=gray",
+                Status::Done => ", fillcolor=palegreen",
+            };
+            output.push_str(&format!(
+                "    {} [label=\"{}\"{style}];\n",
+                node.name,
+                label.join("\\n")
+            ));
+        }
+        output.push_str("  }\n");
+    }
+    for (from, to, blocker) in edges {
+        let style = if *blocker {
+            " [style=dashed, label=\"blocks\"]"
+        } else {
+            ""
+        };
+        output.push_str(&format!("  {from} -> {to}{style};\n"));
+    }
+    output.push_str("}\n");
+    output
+}
+
+fn _mermaid(clusters: &[(&str, Vec<Node>)], edges: &Edges) -> String {
+    // entity codes keep quotes and `#` from being read as syntax
+    let escape = |text: &str| text.replace('#', "#35;").replace('"', "#quot;");
+    let mut output = String::from("flowchart TD\n");
+    let mut classes: HashMap<String, Vec<&str>> = HashMap::new();
+    for (i, (list, nodes)) in clusters.iter().enumerate() {
+        output.push_str(&format!("  subgraph l{i} [\"{}\"]\n", escape(list)));
+        for node in nodes {
+            let label: Vec<_> =
+                _label(node).iter().map(|line| escape(line)).collect();
+            output.push_str(&format!(
+                "    {}[\"{}\"]\n",
+                node.name,
+                label.join("<br/>")
+            ));
+            let status = node.todos[&node.id].status();
+            if status != Status::Todo {
+                classes
+                    .entry(status.to_string())
+                    .or_default()
+                    .push(&node.name);
+            }
+        }
+        output.push_str("  end\n");
+    }
+    for (from, to, blocker) in edges {
+        let arrow = if *blocker {
+            "-.->|blocks|"
+        } else {
+            "-->"
+        };
+        output.push_str(&format!("  {from} {arrow} {to}\n"));
+    }
+    for (class, style) in [
+        ("progress", "fill:#ffffe0"),
+        ("waiting", "fill:#add8e6"),
+        ("cancelled", "fill:#d3d3d3,color:#808080"),
+        ("done", "fill:#98fb98"),
+    ] {
+        if let Some(names) = classes.get(class) {
+            output.push_str(&format!("  classDef {class} {style}\n"));
+            output.push_str(&format!("  class {} {class}\n", names.join(",")));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Timestamp, Todo, TodoRef};
+
+    fn at(day: u32) -> Timestamp {
+        Timestamp::parse_from_rfc3339(&format!("2025-06-{day:02}T09:00:00Z"))
+            .unwrap()
+    }
+
+    /// 1 `Ship "v2" #1 \ fix` > 2 "Write docs" > 3 "Proofread", and 4
+    /// "Deploy" (done) blocked by 2.
+    fn todos() -> TodoMap {
+        let todo = |desc: &str, children: Option<Vec<u32>>, parent| {
+            Todo::new(desc.to_owned(), None, children, parent, at(1), None)
+        };
+        let mut todos = TodoMap::new();
+        todos.insert(1, todo(r#"Ship "v2" #1 \ fix"#, Some(vec![2]), None));
+        todos.insert(2, todo("Write docs", Some(vec![3]), Some(1)));
+        todos.insert(3, todo("Proofread", None, Some(2)));
+        let mut deploy = Todo::new(
+            "Deploy".to_owned(),
+            None,
+            None,
+            None,
+            at(1),
+            Some(at(2)),
+        );
+        deploy.depends_on = Some(vec![TodoRef { list: None, id: 2 }]);
+        todos.insert(4, deploy);
+        todos
+    }
+
+    fn node_ids(clusters: &[(&str, Vec<Node>)]) -> Vec<u32> {
+        clusters[0].1.iter().map(|node| node.id).collect()
+    }
+
+    fn edge(from: &str, to: &str, blocker: bool) -> (String, String, bool) {
+        (from.to_owned(), to.to_owned(), blocker)
+    }
+
+    #[test]
+    fn hierarchy_and_blockers() {
+        let todos = todos();
+        let (clusters, edges) = _graph(&[("default", &todos)], None, None);
+        assert_eq!(node_ids(&clusters), vec![1, 2, 3, 4]);
+        assert_eq!(
+            edges,
+            vec![
+                edge("t1", "t2", false),
+                edge("t2", "t3", false),
+                edge("t2", "t4", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn depth_and_subtree() {
+        let todos = todos();
+        let (clusters, edges) = _graph(&[("default", &todos)], None, Some(1));
+        assert_eq!(node_ids(&clusters), vec![1, 2, 4]);
+        assert_eq!(
+            edges,
+            vec![edge("t1", "t2", false), edge("t2", "t3", true)]
+        );
+
+        // the blocked todo is outside of the subtree
+        let (clusters, edges) = _graph(&[("default", &todos)], Some(2), None);
+        assert_eq!(node_ids(&clusters), vec![2, 3]);
+        assert_eq!(edges, vec![edge("t1", "t2", false)]);
+
+        let (clusters, edges) =
+            _graph(&[("default", &todos)], Some(2), Some(0));
+        assert_eq!(node_ids(&clusters), vec![2]);
+        assert!(edges.is_empty());
+    }
+
+    #[test]
+    fn dot() {
+        let todos = todos();
+        let (clusters, edges) = _graph(&[("a \"b\"", &todos)], None, None);
+        let output = _dot(&clusters, &edges);
+        assert!(output.starts_with("digraph taso {\n"), "{output}");
+        assert!(output.contains(r#"    label="a \"b\"";"#), "{output}");
+        assert!(
+            output.contains(r##"    t1 [label="#1 Ship \"v2\" #1 \\ fix\n"##),
+            "{output}"
+        );
+        assert!(
+            output.contains(
+                r##"    t4 [label="#4 Deploy", fillcolor=palegreen];"##
+            ),
+            "{output}"
+        );
+        assert!(output.contains("  t1 -> t2;\n"), "{output}");
+        assert!(
+            output.contains(r#"  t2 -> t4 [style=dashed, label="blocks"];"#),
+            "{output}"
+        );
+        assert!(output.ends_with("}\n"), "{output}");
+    }
+
+    #[test]
+    fn mermaid() {
+        let todos = todos();
+        let (clusters, edges) = _graph(&[("a \"b\"", &todos)], None, None);
+        let output = _mermaid(&clusters, &edges);
+        assert!(output.starts_with("flowchart TD\n"), "{output}");
+        assert!(output.contains("  subgraph l0 [\"a #quot;b#quot;\"]\n"));
+        assert!(
+            output.contains(
+                r##"    t1["#35;1 Ship #quot;v2#quot; #35;1 \ fix<br/>"##
+            ),
+            "{output}"
+        );
+        assert!(output.contains("    t4[\"#35;4 Deploy\"]\n"), "{output}");
+        assert!(output.contains("  t1 --> t2\n"), "{output}");
+        assert!(output.contains("  t2 -.->|blocks| t4\n"), "{output}");
+        assert!(output.contains("  class t4 done\n"), "{output}");
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    command::RunMut,
+    context::Context,
+    format::{
+        self, Format, Report, csv, ical, markdown, org, print_report,
+        taskwarrior, todotxt,
+    },
+    util::get_list,
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use log::{debug, trace};
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+};
+
+#[derive(Debug, Args)]
+pub struct ImportOptions {
+    /// File to import, `-` reads it from stdin
+    #[arg(value_name = "FILE", default_value = "-")]
+    file: PathBuf,
+    /// Format of the file, guessed from its extension if missing
+    #[arg(short, long, value_name = "FORMAT")]
+    format: Option<Format>,
+    /// Add new todos into specified list, instead of the lists named in the
+    /// file (or the default one)
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for ImportOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "import", "{self:#?}");
+
+        let format = self
+            .format
+            .or_else(|| Format::from_path(&self.file))
+            .ok_or_else(|| {
+                anyhow!("the format can't be guessed, give it with --format")
+            })?;
+        debug!(target: "import", "format: {format:?}");
+
+        let input = if self.file.as_os_str() == "-" {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            input
+        } else {
+            fs::read_to_string(&self.file)?
+        };
+
+        let tz = ctx.config.timezone();
+        let no_report = |imported| (imported, Report::new());
+        let (mut imported, report) = match format {
+            Format::Todotxt => todotxt::import(&input, tz).map(no_report),
+            Format::Markdown => markdown::import(&input, tz).map(no_report),
+            Format::Ical => ical::import(&input, tz),
+            Format::Taskwarrior => taskwarrior::import(&input, tz),
+            Format::Csv => csv::import(&input, tz),
+            Format::Org => org::import(&input, tz).map(no_report),
+        }
+        .map_err(|err| anyhow!("{:?}: {err}", self.file))?;
+        if self.list.is_some() {
+            for imp in imported.iter_mut() {
+                imp.list = None;
+            }
+        }
+
+        let list = get_list(self.list, ctx);
+        if format == Format::Csv {
+            format::check_parents(&ctx.store, &list, &imported)?;
+        }
+        let inserted =
+            format::insert(&mut ctx.store, &list, imported, tz.now())?;
+        let updated = inserted.iter().filter(|(_, updated)| *updated).count();
+        println!(
+            "Imported: {} todo(s) added, {updated} updated",
+            inserted.len() - updated
+        );
+        for (todo_ref, updated) in &inserted {
+            let todo = ctx
+                .store
+                .todo_by_ref(&list, todo_ref)
+                .ok_or_else(|| anyhow!("todo {todo_ref} is not imported"))?;
+            let updated = if *updated {
+                " (updated)"
+            } else {
+                ""
+            };
+            println!(
+                "{}{todo_ref} {}{updated}",
+                todo.status().marker(),
+                todo.desc
+            );
+        }
+        print_report(&report);
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{config::Config, context::Context, store::Store};
+use crate::{
+    config::Config,
+    context::Context,
+    store::{Store, check_list_name},
+};
 use anyhow::{Result, bail};
 use clap::Args;
 use log::{debug, trace};
@@ -35,6 +39,7 @@ impl InitOptions {
             .default_list
             .clone()
             .unwrap_or_else(|| config.default_list().to_owned());
+        check_list_name(&default_list)?;
         let store = Store::create(default_list, Vec::new());
         debug!(target: "init", "initialized: {store:#?}");
 
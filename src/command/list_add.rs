@@ -1,4 +1,6 @@
-use crate::{command::RunMut, context::Context, model::TodoMap};
+use crate::{
+    command::RunMut, context::Context, model::TodoMap, store::check_list_name,
+};
 use anyhow::Result;
 use clap::Args;
 use log::debug;
@@ -13,6 +15,7 @@ impl RunMut for ListAddOptions {
         debug!(target: "list_add", "{ctx:#?}");
         let lists_mut = ctx.store.lists_mut();
         for new_list in self.new_lists {
+            check_list_name(&new_list)?;
             let list_map = TodoMap::new();
             lists_mut.insert(new_list, list_map);
         }
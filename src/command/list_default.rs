@@ -1,4 +1,4 @@
-use crate::{command::RunMut, context::Context, model::TodoMap};
+use crate::{command::RunMut, context::Context};
 use anyhow::{Result, bail};
 use clap::Args;
 use log::debug;
@@ -18,9 +18,7 @@ impl RunMut for ListDefaultOptions {
         if !lists_mut.contains_key(&self.new_default_lists) && !self.new {
             bail!("list [{}] does not exist", self.new_default_lists);
         }
-        lists_mut
-            .entry(self.new_default_lists.clone())
-            .or_insert_with(TodoMap::new);
+        lists_mut.entry(self.new_default_lists.clone()).or_default();
 
         ctx.store.set_default_list(self.new_default_lists);
 
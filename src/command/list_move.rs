@@ -0,0 +1,111 @@
+use crate::{
+    command::RunMut,
+    context::Context,
+    selection::{SelectionOptions, Selector},
+    util::get_list,
+};
+use anyhow::{Result, bail};
+use clap::Args;
+use log::{debug, trace};
+use std::collections::HashMap;
+
+#[derive(Debug, Args)]
+pub struct ListMoveOptions {
+    #[command(flatten)]
+    selection: SelectionOptions,
+    /// Move todo(s) to list NEW_LIST_NAME, which must exist
+    #[arg(long, value_name = "NEW_LIST_NAME")]
+    to: String,
+    /// Move todo(s) from specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for ListMoveOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "list_move", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        let to = self.to;
+        debug!(target: "list_move", "list [{list}] -> [{to}]");
+        ctx.store.todos(&to)?;
+        if list == to {
+            bail!("todo(s) are already in list [{to}]");
+        }
+
+        let Some(todo_id) =
+            self.selection
+                .select(ctx, &list, &format!("moved to [{to}]"))?
+        else {
+            return Ok(());
+        };
+
+        // subtodos go along with their parents
+        let todos = ctx.store.todos(&list)?;
+        let mut moving = Vec::new();
+        for id in todo_id {
+            for id in [id]
+                .into_iter()
+                .chain(Selector::Descendants(id).select(todos)?)
+            {
+                if !moving.contains(&id) {
+                    moving.push(id);
+                }
+            }
+        }
+        let first_id = ctx.store.generate_id(&to)?;
+        let moved: HashMap<u32, u32> =
+            moving.iter().copied().zip(first_id..).collect();
+        debug!(target: "list_move", "moved {moved:?}");
+
+        let now = ctx.config.timezone().now();
+        let mut detached = Vec::new();
+        for id in &moving {
+            let Some(mut todo) = ctx.store.todos_mut(&list)?.remove(id) else {
+                continue;
+            };
+            // a parent left behind loses the todo
+            if let Some(pa_id) =
+                todo.parent.filter(|pa| !moved.contains_key(pa))
+            {
+                let pa_todo = ctx.store.todo_by_id_mut(&list, &pa_id)?;
+                if let Some(ch_ids) = pa_todo.children.as_mut() {
+                    ch_ids.retain(|ch_id| ch_id != id);
+                }
+                if pa_todo.children.as_ref().is_some_and(|ch| ch.is_empty()) {
+                    pa_todo.children = None;
+                }
+                detached.push(pa_id);
+            }
+            todo.parent = todo.parent.and_then(|pa| moved.get(&pa).copied());
+            if let Some(ch_ids) = todo.children.as_mut() {
+                ch_ids.retain_mut(|ch_id| match moved.get(ch_id) {
+                    Some(new_id) => {
+                        *ch_id = *new_id;
+                        true
+                    }
+                    None => false,
+                });
+            }
+            if let Some(refs) = todo.depends_on.as_mut() {
+                for r in refs.iter_mut() {
+                    *r = r.clone().resolve(&list);
+                }
+            }
+            ctx.store.todos_mut(&to)?.insert(moved[id], todo);
+        }
+        ctx.store.retarget(&list, &to, &moved);
+
+        for pa_id in detached {
+            let rolled = ctx.store.todos_mut(&list)?.roll_up(pa_id, now);
+            debug!(target: "list_move", "roll up todo [{rolled:?}]");
+        }
+
+        println!("Moved:");
+        for id in &moving {
+            println!("{list}#{id} -> {to}#{}", moved[id]);
+        }
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
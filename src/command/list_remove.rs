@@ -1,7 +1,10 @@
-use crate::{command::RunMut, context::Context, model::Todo, util::check_key};
+use crate::{
+    command::RunMut, context::Context, model::TodoMap, util::check_key,
+};
 use anyhow::Result;
 use clap::Args;
 use log::{debug, warn};
+use std::collections::HashMap;
 
 #[derive(Debug, Args)]
 pub struct ListRemoveOptions {
@@ -15,7 +18,7 @@ impl RunMut for ListRemoveOptions {
         debug!(target: "list_remove", "{ctx:#?}");
         let mut remove_lists = Vec::new();
         let mut remove_failed = Vec::new();
-        let mut migrate_todos: Vec<(u32, Todo)> = Vec::new();
+        let mut migrate_todos: Vec<(String, TodoMap)> = Vec::new();
 
         let default_list = ctx.store.default_list().clone();
         debug!(target: "list_remove", "default list [{default_list}]");
@@ -29,28 +32,59 @@ impl RunMut for ListRemoveOptions {
             }
             check_key(lists, &list)?;
             let todos = lists.get(&list).unwrap();
-            if todos.len() > 0 {
+            if !todos.is_empty() {
                 if !self.force {
                     warn!("list [{list}] still have todo, can't be removed!");
                     remove_failed.push(list);
                     continue;
                 }
                 let todos = lists.remove(&list).unwrap();
-                migrate_todos.extend(todos.into_iter());
+                migrate_todos.push((list.clone(), todos));
             }
             remove_lists.push(list);
         }
 
         let lists_mut = ctx.store.lists_mut();
-        for list in remove_lists {
+        for list in &remove_lists {
             debug!(target: "list_remove", "list [{list}] is removed");
-            lists_mut.remove(&list);
+            lists_mut.remove(list);
         }
 
-        let default_todos = ctx.store.todos_mut(&default_list)?;
-        default_todos.extend(migrate_todos.into_iter());
+        // todos move to the default list under new ids, as with list-move
+        for (list, todos) in migrate_todos {
+            let mut ids: Vec<_> = todos.keys().copied().collect();
+            ids.sort();
+            let first_id = ctx.store.generate_id(&default_list)?;
+            let moved: HashMap<u32, u32> =
+                ids.iter().copied().zip(first_id..).collect();
+            debug!(target: "list_remove", "list [{list}] moved {moved:?}");
+            for (id, mut todo) in todos {
+                todo.parent =
+                    todo.parent.and_then(|pa| moved.get(&pa).copied());
+                if let Some(ch_ids) = todo.children.as_mut() {
+                    ch_ids.retain_mut(|ch_id| match moved.get(ch_id) {
+                        Some(new_id) => {
+                            *ch_id = *new_id;
+                            true
+                        }
+                        None => false,
+                    });
+                }
+                if let Some(refs) = todo.depends_on.as_mut() {
+                    for r in refs.iter_mut() {
+                        *r = r.clone().resolve(&list);
+                    }
+                }
+                ctx.store.todos_mut(&default_list)?.insert(moved[&id], todo);
+            }
+            ctx.store.retarget(&list, &default_list, &moved);
+        }
+        // references left point into a list which no longer exists
+        for list in &remove_lists {
+            ctx.store.forget(list, |_| true);
+        }
 
-        if remove_failed.len() > 0 {
+        if !remove_failed.is_empty() {
             println!("Failed: {remove_failed:?}");
         }
 
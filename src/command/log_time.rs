@@ -0,0 +1,61 @@
+use crate::{
+    command::RunMut,
+    context::Context,
+    date::DateExpr,
+    model::effort::{Effort, TimeEntry},
+    util::get_list,
+};
+use anyhow::{Result, anyhow, bail};
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct LogTimeOptions {
+    todo_id: u32,
+    /// Time spent, e.g. 1h30m, 45m, 90 (minutes)
+    effort: Effort,
+    /// Day the time was spent on, e.g. 2025-06-01, yesterday, last fri
+    #[arg(short = 'D', long, value_name = "DATE", value_parser = DateExpr::parse)]
+    date: Option<DateExpr>,
+    /// Log time on todo in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for LogTimeOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "log_time", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "log_time", "list [{list}]");
+        debug!(target: "log_time", "todo [{}]", self.todo_id);
+
+        if self.effort.is_zero() {
+            bail!("nothing to log, the duration is zero");
+        }
+
+        // a past day is logged at its start, in the configured timezone
+        let tz = ctx.config.timezone();
+        let start = match self.date {
+            Some(date) => {
+                let date =
+                    date.resolve(tz.today()).map_err(|err| anyhow!(err))?;
+                tz.start_of_day(date)
+                    .ok_or_else(|| anyhow!("invalid date [{date}]"))?
+            }
+            None => tz.now(),
+        };
+        debug!(target: "log_time", "{} at {start}", self.effort);
+
+        let todo = ctx.store.todo_by_id_mut(&list, &self.todo_id)?;
+        todo.time_log.get_or_insert_with(Vec::new).push(TimeEntry {
+            start,
+            effort: self.effort,
+        });
+
+        println!("Logged:");
+        println!("#{} {} (total {})", self.todo_id, self.effort, todo.spent());
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
     command::RunMut,
     context::Context,
+    date::DateExpr,
+    model::{Priority, effort::Estimate, recur::Recur},
     ui::form::ModifyUI,
     util::{SyncBlock, get_list},
 };
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use clap::Args;
 use log::{debug, trace};
 
@@ -14,6 +16,32 @@ pub struct ModifyOptions {
     todo_id: u32,
     #[command(flatten)]
     todo_from_args: Option<ModifyArgs>,
+    /// Planned date of todo, e.g. 2025-06-01, tomorrow, next friday, in 3 days
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = DateExpr::parse,
+        help_heading = "Todo"
+    )]
+    assign: Option<DateExpr>,
+    /// Recurrence rule, e.g. `daily`, `every 2 weeks on mon,thu` or `after 3 days`
+    #[arg(long, value_name = "RULE", help_heading = "Todo")]
+    recur: Option<Recur>,
+    /// Stop the recurrence of todo
+    #[arg(long, conflicts_with = "recur", help_heading = "Todo")]
+    no_recur: bool,
+    /// Planned effort, e.g. 2h, 3d (working days) or 5pt (story points)
+    #[arg(long, value_name = "ESTIMATE", help_heading = "Todo")]
+    estimate: Option<Estimate>,
+    /// Remove the estimate of todo
+    #[arg(long, conflicts_with = "estimate", help_heading = "Todo")]
+    no_estimate: bool,
+    /// Priority of todo, from A (highest) to Z
+    #[arg(long, value_name = "PRIORITY", help_heading = "Todo")]
+    priority: Option<Priority>,
+    /// Remove the priority of todo
+    #[arg(long, conflicts_with = "priority", help_heading = "Todo")]
+    no_priority: bool,
     /// Modify todo in specified list
     #[arg(short, long, value_name = "LIST_NAME")]
     list: Option<String>,
@@ -42,8 +70,22 @@ impl RunMut for ModifyOptions {
         debug!(target: "modify", "list [{list}]");
 
         debug!(target: "modify", "todo [{:?}]", self.todo_id);
+        let tz = ctx.config.timezone();
+        let assign_at = self
+            .assign
+            .as_ref()
+            .map(|assign| assign.resolve(tz.today()))
+            .transpose()
+            .map_err(|err| anyhow!(err))?;
         let todo = ctx.store.todo_by_id_mut(&list, &self.todo_id)?;
 
+        let scheduled = self.assign.is_some()
+            || self.recur.is_some()
+            || self.no_recur
+            || self.estimate.is_some()
+            || self.no_estimate
+            || self.priority.is_some()
+            || self.no_priority;
         let (desc, link) = match self.todo_from_args {
             Some(ModifyArgs { desc, link }) => {
                 debug!(target: "modify", "desc from arg: {desc:?}");
@@ -51,6 +93,7 @@ impl RunMut for ModifyOptions {
 
                 (desc, link)
             }
+            None if scheduled => (None, None),
             None => {
                 let rt = SyncBlock::new()?;
                 rt.block_on(ModifyUI::run(&todo.desc, &todo.link))?
@@ -65,6 +108,35 @@ impl RunMut for ModifyOptions {
             debug!(target: "modify", "change link to: {link:?}");
             todo.link = link;
         }
+        if let Some(assign) = assign_at {
+            debug!(target: "modify", "change assign date to: {assign}");
+            todo.assign_at = Some(assign);
+        }
+        if let Some(recur) = self.recur {
+            debug!(target: "modify", "change recurrence to: {recur}");
+            todo.assign_at.get_or_insert(tz.date(&todo.create_at()));
+            todo.recur = Some(recur);
+        }
+        if self.no_recur {
+            debug!(target: "modify", "remove recurrence");
+            todo.recur = None;
+        }
+        if let Some(estimate) = self.estimate {
+            debug!(target: "modify", "change estimate to: {estimate}");
+            todo.estimate = Some(estimate);
+        }
+        if self.no_estimate {
+            debug!(target: "modify", "remove estimate");
+            todo.estimate = None;
+        }
+        if let Some(priority) = self.priority {
+            debug!(target: "modify", "change priority to: {priority}");
+            todo.priority = Some(priority);
+        }
+        if self.no_priority {
+            debug!(target: "modify", "remove priority");
+            todo.priority = None;
+        }
 
         println!("{}", todo);
 
@@ -1,11 +1,15 @@
-use crate::{command::RunMut, context::Context, model::Todo, util::get_list};
-use anyhow::Result;
+use crate::{
+    command::RunMut, context::Context, model::Todo,
+    selection::SelectionOptions, util::get_list,
+};
+use anyhow::{Result, bail};
 use clap::Args;
 use log::{debug, trace, warn};
 
 #[derive(Debug, Args)]
 pub struct MoveOptions {
-    todo_id: u32,
+    #[command(flatten)]
+    selection: SelectionOptions,
     #[command(flatten)]
     todo_move_target: TodoMoveTarget,
     /// Move todo in specified list
@@ -17,8 +21,8 @@ pub struct MoveOptions {
 #[group(required = true, multiple = false)]
 struct TodoMoveTarget {
     /// Move into todo <PARENT_TODO_ID> as a child todo.
-    #[arg(long, value_name = "PARENT_TODO_ID", default_value = "")]
-    parent: u32,
+    #[arg(long, value_name = "PARENT_TODO_ID")]
+    parent: Option<u32>,
     #[arg(long)]
     top: bool,
 }
@@ -30,17 +34,47 @@ impl RunMut for MoveOptions {
         let list = get_list(self.list, ctx);
         debug!(target: "move", "list [{list}]");
 
-        if self.todo_move_target.top {
-            debug!(target: "move", "todo [{}] move to top level", self.todo_id);
-            _move_to_top(ctx, &list, &self.todo_id)?;
-        } else {
-            debug!(target: "move", "todo [{}] will have new parent todo [{}]", self.todo_id, self.todo_move_target.parent);
-            _move_to_children(
-                ctx,
-                &list,
-                &self.todo_id,
-                &self.todo_move_target.parent,
-            )?;
+        let Some(todo_id) = self.selection.select(ctx, &list, "moved")? else {
+            return Ok(());
+        };
+
+        // a todo can't be moved into its own subtree
+        let target = self.todo_move_target.parent;
+        if let Some(target) = target {
+            ctx.store.todo_by_id(&list, &target)?;
+            let todos = ctx.store.todos(&list)?;
+            let mut path = todos.ancestors(&target);
+            path.push(target);
+            let refused: Vec<_> =
+                todo_id.iter().filter(|id| path.contains(id)).collect();
+            if !refused.is_empty() {
+                bail!("todo {refused:?} can't be moved into its own subtree");
+            }
+        }
+
+        let now = ctx.config.timezone().now();
+        for id in &todo_id {
+            let pre_pa_id = ctx.store.todo_by_id(&list, id)?.parent;
+            debug!(target: "move", "todo [{id}] pre-parent-todo [{pre_pa_id:?}]");
+
+            match target {
+                None => {
+                    debug!(target: "move", "todo [{id}] move to top level");
+                    _move_to_top(ctx, &list, id)?;
+                }
+                Some(target) => {
+                    debug!(target: "move", "todo [{id}] will have new parent todo [{target}]");
+                    _move_to_children(ctx, &list, id, &target)?;
+                }
+            }
+
+            // both parents may be completed or reopened by the move
+            let todos_mut = ctx.store.todos_mut(&list)?;
+            let new_pa_id = todos_mut.get(id).and_then(|t| t.parent);
+            for pa_id in [pre_pa_id, new_pa_id].into_iter().flatten() {
+                let rolled = todos_mut.roll_up(pa_id, now);
+                debug!(target: "move", "roll up todo [{rolled:?}]");
+            }
         }
 
         ctx.store.write(&ctx.store_path)
@@ -48,8 +82,8 @@ impl RunMut for MoveOptions {
 }
 
 fn _move_to_top(ctx: &mut Context, list: &String, ch_id: &u32) -> Result<()> {
-    let ch_todo = ctx.store.todo_by_id_mut(&list, &ch_id)?;
-    match ch_todo.parent.clone() {
+    let ch_todo = ctx.store.todo_by_id_mut(list, ch_id)?;
+    match ch_todo.parent {
         Some(pa_id) => {
             debug!(target: "move::_move_to_top", "target todo [{ch_id}] has parent todo [{pa_id}]");
             ch_todo.parent = None;
@@ -70,24 +104,24 @@ fn _move_to_children(
     ch_id: &u32,
     target_pa_id: &u32,
 ) -> Result<()> {
-    let ch_todo = ctx.store.todo_by_id_mut(&list, &ch_id)?;
+    let ch_todo = ctx.store.todo_by_id_mut(list, ch_id)?;
 
-    match ch_todo.parent.clone() {
+    match ch_todo.parent {
         Some(pa_id) => {
             debug!(target: "move::_move_to_children", "target todo [{ch_id}] has parent todo [{pa_id}]");
-            ch_todo.parent = Some(target_pa_id.clone());
+            ch_todo.parent = Some(*target_pa_id);
             debug!(target: "move::_move_to_children", "target todo [{ch_id}] has new parent todo [{target_pa_id}]");
 
             _remove_child_id(ctx, list, &pa_id, ch_id)?;
         }
         None => {
             debug!(target: "move::_move_to_children", "target todo [{ch_id}] had no parent todo before");
-            ch_todo.parent = Some(target_pa_id.clone());
+            ch_todo.parent = Some(*target_pa_id);
             debug!(target: "move::_move_to_children", "target todo [{ch_id}] has new parent todo [{target_pa_id}]");
         }
     };
 
-    let pa_todo = ctx.store.todo_by_id_mut(&list, &target_pa_id)?;
+    let pa_todo = ctx.store.todo_by_id_mut(list, target_pa_id)?;
     pa_todo.children.get_or_insert_with(Vec::new).push(*ch_id);
     debug!(target: "move::_move_to_children", "target todo [{target_pa_id}] has new child todo [{ch_id}]");
     Ok(())
@@ -100,7 +134,7 @@ fn _remove_child_id(
     ch_id: &u32,
 ) -> Result<()> {
     debug!(target: "move::_remove_child_id", "target pre-parent-todo [{pa_id}]");
-    let pa_todo = ctx.store.todo_by_id_mut(&list, &pa_id)?;
+    let pa_todo = ctx.store.todo_by_id_mut(list, pa_id)?;
     match &mut pa_todo.children {
         Some(pa_todo_ch_ids) => {
             debug!(target: "move::_remove_child_id", "target pre-parent-todo [{pa_id}] has children todos [{pa_todo_ch_ids:?}]");
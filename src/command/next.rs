@@ -0,0 +1,61 @@
+use crate::{command::Run, context::Context, model::Status, util::get_list};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct NextOptions {
+    /// Show todos of all lists
+    #[arg(short, long, conflicts_with = "list")]
+    all: bool,
+    /// Show todos in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl Run for NextOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "next", "{self:#?}");
+
+        let mut lists: Vec<String> = if self.all {
+            ctx.store.lists().keys().cloned().collect()
+        } else {
+            vec![get_list(self.list, ctx)]
+        };
+        lists.sort();
+        debug!(target: "next", "lists {lists:?}");
+
+        for list in lists {
+            let todos = ctx.store.todos(&list)?;
+            let mut ready = Vec::new();
+            for (id, todo) in todos.iter() {
+                let status = todo.status();
+                // waiting todos are blocked by something outside of taso
+                if !status.is_open() || status == Status::Waiting {
+                    continue;
+                }
+                if todos.progress(id).is_some_and(|(done, total)| done < total)
+                {
+                    debug!(target: "next", "todo [{id}] has open children");
+                    continue;
+                }
+                let blockers = ctx.store.open_blockers(&list, id)?;
+                if !blockers.is_empty() {
+                    debug!(target: "next", "todo [{id}] is blocked by {blockers:?}");
+                    continue;
+                }
+                ready.push((id, todo));
+            }
+
+            // todos in progress first
+            ready.sort_by_key(|(id, todo)| {
+                (todo.status() != Status::Progress, **id)
+            });
+            for (id, todo) in ready {
+                println!("{}{list}#{id} {}", todo.status().marker(), todo.desc);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{command::RunMut, context::Context, model::Note, util::get_list};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct NoteOptions {
+    #[arg(required = true)]
+    todo_id: u32,
+    /// Text of the note. It is recommended to wrap it in quotes
+    #[arg(
+        required = true,
+        value_parser = clap::builder::NonEmptyStringValueParser::new()
+    )]
+    text: String,
+    /// Annotate todo in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for NoteOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "note", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "note", "list [{list}]");
+
+        let now = ctx.config.timezone().now();
+        let todo = ctx.store.todo_by_id_mut(&list, &self.todo_id)?;
+        todo.notes.get_or_insert_with(Vec::new).push(Note {
+            at: now,
+            text: self.text,
+        });
+        debug!(target: "note", "todo [{}] notes: {:?}", self.todo_id, todo.notes);
+
+        println!("{}", todo);
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
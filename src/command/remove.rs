@@ -1,16 +1,17 @@
 use crate::{
     command::RunMut,
     context::Context,
+    selection::{SelectionOptions, Selector},
     util::{check_key, get_list},
 };
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::Args;
-use log::{debug, trace, warn};
+use log::{debug, trace};
 
 #[derive(Debug, Args)]
 pub struct RemoveOptions {
-    #[arg(required = true, value_delimiter = ',')]
-    todo_id: Vec<u32>,
+    #[command(flatten)]
+    selection: SelectionOptions,
     /// Remove exist todo(s) and its (or their) children recursively
     #[arg(short, long)]
     recursive: bool,
@@ -25,19 +26,51 @@ impl RunMut for RemoveOptions {
 
         let list = get_list(self.list, ctx);
         debug!(target: "remove", "list [{list}]");
-        debug!(target: "remove", "to be removed: todo [{:?}]", self.todo_id);
+        let Some(todo_id) = self.selection.select(ctx, &list, "removed")?
+        else {
+            return Ok(());
+        };
+        let todos = ctx.store.todos(&list)?;
+        let todo_id = if self.recursive {
+            let mut ids = Vec::new();
+            for id in todo_id {
+                for id in std::iter::once(id)
+                    .chain(Selector::Descendants(id).select(todos)?)
+                {
+                    if !ids.contains(&id) {
+                        ids.push(id);
+                    }
+                }
+            }
+            ids
+        } else {
+            let refused: Vec<_> = todo_id
+                .iter()
+                .filter(|id| {
+                    todos.get(id).is_some_and(|todo| {
+                        todo.children
+                            .iter()
+                            .flatten()
+                            .any(|ch_id| !todo_id.contains(ch_id))
+                    })
+                })
+                .collect();
+            if !refused.is_empty() {
+                bail!(
+                    "todo {refused:?} still have children, use --recursive to remove them as well"
+                );
+            }
+            todo_id
+        };
+        debug!(target: "remove", "to be removed: todo [{todo_id:?}]");
 
-        let todos_mut = ctx.store.todos_mut(&list)?;
-        let mut removed_todos = Vec::new();
-
-        for id in self.todo_id.iter() {
-            check_key(todos_mut, id)?;
-            let Some(removed) = todos_mut.remove(id) else {
-                warn!(target: "remove", "[{id}] can't remove");
-                continue;
-            };
-            removed_todos.push(removed);
+        for id in &todo_id {
+            check_key(todos, id)?;
         }
+        let now = ctx.config.timezone().now();
+        let removed_todos =
+            ctx.store.todos_mut(&list)?.remove_todos(&todo_id, now);
+        ctx.store.forget(&list, |id| todo_id.contains(id));
 
         println!("{:#?}", removed_todos);
 
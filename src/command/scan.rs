@@ -0,0 +1,326 @@
+use crate::{
+    command::{RunMut, done},
+    context::Context,
+    model::{Status, Todo, TodoRef},
+    scan::{self, Comment},
+    util::get_list,
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use ignore::WalkBuilder;
+use log::{debug, trace};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Prefix of the tag of a synced todo, followed by the keyword in lowercase.
+const TAG_PREFIX: &str = "scan:";
+
+#[derive(Debug, Args)]
+pub struct ScanOptions {
+    /// Directory or file to scan, files ignored by .gitignore are skipped
+    #[arg(value_name = "PATH", default_value = ".")]
+    path: PathBuf,
+    /// Keywords starting the comments to collect
+    #[arg(
+        short,
+        long,
+        value_name = "KEYWORD",
+        value_delimiter = ',',
+        default_value = "TODO,FIXME,HACK"
+    )]
+    keywords: Vec<String>,
+    /// Sync comments into specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+/// A todo synced with a comment.
+#[derive(Debug)]
+enum Change {
+    Added,
+    Moved,
+    Updated,
+    Completed,
+}
+
+impl RunMut for ScanOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "scan", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "scan", "list [{list}]");
+        ctx.store.todos(&list)?;
+
+        // links are relative to the directory of the store when they can be
+        let base = ctx
+            .store_path
+            .parent()
+            .and_then(|dir| dir.canonicalize().ok())
+            .unwrap_or_default();
+        let root = self
+            .path
+            .canonicalize()
+            .map_err(|err| anyhow!("{:?}: {err}", self.path))?;
+        debug!(target: "scan", "scan {root:?} from {base:?}");
+
+        // (file, comment) found in the tree
+        let mut found: Vec<(String, Comment)> = Vec::new();
+        let mut files = 0;
+        let walker = WalkBuilder::new(&root)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+        for entry in walker {
+            let entry = entry?;
+            let path = entry.path();
+            let Some(markers) = scan::markers(path).filter(|_| path.is_file())
+            else {
+                continue;
+            };
+            // binary files aren't UTF-8
+            let Ok(content) = fs::read_to_string(path) else {
+                debug!(target: "scan", "skip {path:?}");
+                continue;
+            };
+            files += 1;
+            let file = _link_file(&base, path);
+            for comment in scan::comments(&content, markers, &self.keywords) {
+                trace!(target: "scan", "{file}:{} {comment:?}", comment.line);
+                found.push((file.clone(), comment));
+            }
+        }
+        debug!(target: "scan", "{} comment(s) in {files} file(s)", found.len());
+
+        // open todos of earlier scans: tagged `scan:KEYWORD`, linked to a
+        // line of a scanned file
+        let tags: Vec<_> = self.keywords.iter().map(|k| _tag(k)).collect();
+        let mut synced: Vec<_> = ctx
+            .store
+            .todos(&list)?
+            .iter()
+            .filter(|(_, todo)| {
+                todo.status().is_open()
+                    && todo.tags.iter().flatten().any(|tag| tags.contains(tag))
+            })
+            .filter_map(|(id, todo)| {
+                let (file, line) = _split_link(todo.link.as_deref()?)?;
+                base.join(file)
+                    .starts_with(&root)
+                    .then(|| (*id, file.to_owned(), line, todo.desc.clone()))
+            })
+            .collect();
+        synced.sort();
+        debug!(target: "scan", "{} synced todo(s)", synced.len());
+
+        // a comment keeps its todo if it is unchanged, preferably the closest
+        // one, or if it is still on the same line
+        let mut matched: Vec<Option<u32>> = vec![None; found.len()];
+        let mut used = vec![false; synced.len()];
+        for (k, (file, comment)) in found.iter().enumerate() {
+            let desc = _desc(comment);
+            let closest = synced
+                .iter()
+                .enumerate()
+                .filter(|(i, (_, f, _, d))| {
+                    !used[*i] && f == file && *d == desc
+                })
+                .min_by_key(|(_, (_, _, line, _))| line.abs_diff(comment.line));
+            if let Some((i, (id, ..))) = closest {
+                used[i] = true;
+                matched[k] = Some(*id);
+            }
+        }
+        for (k, (file, comment)) in found.iter().enumerate() {
+            if matched[k].is_some() {
+                continue;
+            }
+            let same_line =
+                synced.iter().enumerate().find(|(i, (_, f, l, _))| {
+                    !used[*i] && f == file && *l == comment.line
+                });
+            if let Some((i, (id, ..))) = same_line {
+                used[i] = true;
+                matched[k] = Some(*id);
+            }
+        }
+
+        let now = ctx.config.timezone().now();
+        let mut changes = Vec::new();
+        for ((file, comment), id) in found.iter().zip(matched) {
+            let link = format!("{file}:{}", comment.line);
+            let desc = _desc(comment);
+            let tag = _tag(&comment.keyword);
+            let Some(id) = id else {
+                let id = ctx.store.generate_id(&list)?;
+                let mut todo =
+                    Todo::new(desc, Some(link), None, None, now, None);
+                todo.tags = Some(vec![tag]);
+                ctx.store.todos_mut(&list)?.insert(id, todo);
+                changes.push((id, Change::Added));
+                continue;
+            };
+            let todo = ctx.store.todo_by_id_mut(&list, &id)?;
+            let change = if todo.desc != desc {
+                Some(Change::Updated)
+            } else if todo.link.as_ref() != Some(&link) {
+                Some(Change::Moved)
+            } else {
+                None
+            };
+            todo.desc = desc;
+            todo.link = Some(link);
+            let todo_tags = todo.tags.get_or_insert_with(Vec::new);
+            todo_tags.retain(|t| !tags.contains(t));
+            todo_tags.push(tag);
+            changes.extend(change.map(|change| (id, change)));
+        }
+
+        // comments which are gone are done
+        let gone: Vec<_> = synced
+            .iter()
+            .zip(&used)
+            .filter(|(_, used)| !**used)
+            .map(|((id, ..), _)| *id)
+            .collect();
+        let completed = done::set_status(ctx, &list, gone, Status::Done)?;
+        changes.extend(
+            completed.success.iter().map(|id| (*id, Change::Completed)),
+        );
+        debug!(target: "scan", "{changes:?}");
+
+        let count = |kind: fn(&Change) -> bool| {
+            changes.iter().filter(|(_, change)| kind(change)).count()
+        };
+        println!(
+            "Scan: {} comment(s) in {files} file(s), {} added, {} updated, {} completed",
+            found.len(),
+            count(|change| matches!(change, Change::Added)),
+            count(|change| matches!(change, Change::Moved | Change::Updated)),
+            count(|change| matches!(change, Change::Completed)),
+        );
+        for (id, change) in &changes {
+            let todo = ctx.store.todo_by_id(&list, id)?;
+            let todo_ref = TodoRef {
+                list: Some(list.clone()),
+                id: *id,
+            };
+            let change = format!("{change:?}").to_lowercase();
+            println!(
+                "{}{todo_ref} {} ({change})",
+                todo.status().marker(),
+                todo.desc
+            );
+        }
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
+
+/// Returns `path` relative to `base` if it is inside, absolute otherwise.
+fn _link_file(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Splits a link `FILE:LINE`.
+fn _split_link(link: &str) -> Option<(&str, usize)> {
+    let (file, line) = link.rsplit_once(':')?;
+    Some((file, line.parse().ok()?))
+}
+
+/// Tag of the todos synced with comments starting with `keyword`.
+fn _tag(keyword: &str) -> String {
+    format!("{TAG_PREFIX}{}", keyword.to_lowercase())
+}
+
+/// Description of the todo of `comment`, the keyword if it has no text.
+fn _desc(comment: &Comment) -> String {
+    if comment.text.is_empty() {
+        comment.keyword.clone()
+    } else {
+        comment.text.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Config, store::Store};
+
+    fn scan(ctx: &mut Context, root: &Path) {
+        let options = ScanOptions {
+            path: root.to_owned(),
+            keywords: ["TODO", "FIXME"].map(str::to_owned).to_vec(),
+            list: None,
+        };
+        options.run_mut(ctx).unwrap();
+    }
+
+    fn todo<'a>(ctx: &'a Context, desc: &str) -> &'a Todo {
+        let todos = ctx.store.todos(&"default".to_owned()).unwrap();
+        todos.values().find(|todo| todo.desc == desc).unwrap()
+    }
+
+    #[test]
+    fn rescan() {
+        let root = std::env::temp_dir()
+            .join(format!("taso-scan-{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        let root = root.canonicalize().unwrap();
+        let file = root.join("src/main.rs");
+        let store = Store::create("default".to_owned(), Vec::new());
+        let mut ctx =
+            Context::new(Config::default(), root.join(".todo"), store);
+        // a todo of the user which looks like a synced one
+        let now = ctx.config.timezone().now();
+        let mut proxy = Todo::new(
+            "Proxy".to_owned(),
+            Some("http://proxy.local:8080".to_owned()),
+            None,
+            None,
+            now,
+            None,
+        );
+        proxy.tags = Some(vec!["todo".to_owned()]);
+        ctx.store
+            .todos_mut(&"default".to_owned())
+            .unwrap()
+            .insert(1, proxy);
+
+        fs::write(
+            &file,
+            "fn main() {\n    // TODO: args\n    // FIXME: exit\n}\n",
+        )
+        .unwrap();
+        scan(&mut ctx, &root);
+        assert_eq!(todo(&ctx, "args").link.as_deref(), Some("src/main.rs:2"));
+        assert_eq!(
+            todo(&ctx, "exit").tags,
+            Some(vec!["scan:fixme".to_owned()])
+        );
+
+        // moved, and gone
+        fs::write(&file, "\n\nfn main() {\n    // TODO: args\n}\n").unwrap();
+        scan(&mut ctx, &root);
+        assert_eq!(todo(&ctx, "args").link.as_deref(), Some("src/main.rs:4"));
+        assert_eq!(todo(&ctx, "args").status(), Status::Todo);
+        assert_eq!(todo(&ctx, "exit").status(), Status::Done);
+        assert_eq!(todo(&ctx, "Proxy").status(), Status::Todo);
+
+        // changed on the same line
+        fs::write(&file, "\n\nfn main() {\n    // TODO: parse args\n}\n")
+            .unwrap();
+        scan(&mut ctx, &root);
+        let todos = ctx.store.todos(&"default".to_owned()).unwrap();
+        assert_eq!(todos.len(), 3);
+        assert_eq!(
+            todo(&ctx, "parse args").link.as_deref(),
+            Some("src/main.rs:4")
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{
+    command::Run,
+    context::Context,
+    model::{Todo, TodoMap},
+    store::Store,
+    util::{get_list, get_query},
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use log::{debug, trace};
+use regex::{Regex, RegexBuilder};
+
+#[derive(Debug, Args)]
+pub struct SearchOptions {
+    /// Text to look for in the description, the link, the tags and the notes
+    /// of todos
+    #[arg(required_unless_present = "query")]
+    pattern: Option<String>,
+    /// Ignore case when matching PATTERN
+    #[arg(short, long)]
+    ignore_case: bool,
+    /// Read PATTERN as a regular expression
+    #[arg(short, long)]
+    regex: bool,
+    /// Search todos of all lists
+    #[arg(short, long, conflicts_with = "list")]
+    all: bool,
+    /// Also search the global store, in all lists unless --list is given
+    #[arg(short = 'G', long)]
+    with_global: bool,
+    /// Only list todos matching QUERY, e.g. `status:open and tag:bug`
+    #[arg(short, long, value_name = "QUERY")]
+    query: Option<String>,
+    /// Search todos in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+enum Matcher {
+    Text { pattern: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Text {
+                pattern,
+                ignore_case: false,
+            } => text.contains(pattern.as_str()),
+            Self::Text {
+                pattern,
+                ignore_case: true,
+            } => text.to_lowercase().contains(pattern.as_str()),
+            Self::Regex(re) => re.is_match(text),
+        }
+    }
+}
+
+impl Run for SearchOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "search", "{self:#?}");
+
+        let matcher = match &self.pattern {
+            None => None,
+            Some(pattern) if self.regex => Some(Matcher::Regex(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(self.ignore_case)
+                    .build()
+                    .map_err(|err| anyhow!("invalid regex: {err}"))?,
+            )),
+            Some(pattern) => Some(Matcher::Text {
+                pattern: if self.ignore_case {
+                    pattern.to_lowercase()
+                } else {
+                    pattern.clone()
+                },
+                ignore_case: self.ignore_case,
+            }),
+        };
+        let query = get_query(self.query.as_deref(), ctx)?;
+        let tz = ctx.config.timezone();
+
+        let mut stores = vec![(None, &ctx.store)];
+        let global;
+        if self.with_global {
+            let global_path =
+                ctx.config.global_store().join(ctx.config.data_file_name());
+            if global_path != ctx.store_path && global_path.exists() {
+                global = Store::read(&global_path, &ctx.config)?;
+                stores.push((Some("global"), &global));
+            } else {
+                debug!(target: "search", "no other global store at {global_path:?}");
+            }
+        }
+        let several = stores.len() > 1;
+
+        // every list of each store, unless a list is asked for or implied
+        let wanted = match self.list {
+            Some(list) => Some(list),
+            None if self.all || several => None,
+            None => Some(get_list(None, ctx)),
+        };
+        debug!(target: "search", "list {wanted:?}");
+
+        let mut found = 0;
+        for (label, store) in stores {
+            let mut lists: Vec<&String> = match &wanted {
+                // a list only needs to exist in one of several stores
+                Some(list) if !several => {
+                    store.todos(list)?;
+                    vec![list]
+                }
+                Some(list) => {
+                    store.lists().keys().filter(|l| *l == list).collect()
+                }
+                None => store.lists().keys().collect(),
+            };
+            lists.sort();
+            debug!(target: "search", "store {label:?} lists {lists:?}");
+
+            let prefix = label.map_or(String::new(), |l| format!("({l}) "));
+            for list in lists {
+                let todos = store.todos(list)?;
+                let mut ids: Vec<_> = todos
+                    .iter()
+                    .filter(|(id, todo)| {
+                        matcher.as_ref().is_none_or(|m| _is_match(m, todo))
+                            && query
+                                .as_ref()
+                                .is_none_or(|q| q.matches(todos, id, tz))
+                    })
+                    .map(|(id, _)| *id)
+                    .collect();
+                ids.sort();
+                for id in ids {
+                    found += 1;
+                    let todo = store.todo_by_id(list, &id)?;
+                    println!(
+                        "{}{prefix}{list}#{id} {}",
+                        todo.status().marker(),
+                        _breadcrumbs(todos, &id, todo)
+                    );
+                    if let Some(link) = todo.link.as_ref().filter(|link| {
+                        matcher.as_ref().is_some_and(|m| m.is_match(link))
+                    }) {
+                        println!("    {link}");
+                    }
+                    for note in todo.notes.iter().flatten().filter(|note| {
+                        matcher.as_ref().is_some_and(|m| m.is_match(&note.text))
+                    }) {
+                        println!("    {}", note.text);
+                    }
+                }
+            }
+        }
+        debug!(target: "search", "{found} todo(s) found");
+
+        Ok(())
+    }
+}
+
+fn _is_match(matcher: &Matcher, todo: &Todo) -> bool {
+    matcher.is_match(&todo.desc)
+        || todo
+            .link
+            .as_ref()
+            .is_some_and(|link| matcher.is_match(link))
+        || todo.tags.iter().flatten().any(|tag| matcher.is_match(tag))
+        || todo
+            .notes
+            .iter()
+            .flatten()
+            .any(|n| matcher.is_match(&n.text))
+}
+
+/// Description of todo `id` after the ones of its ancestors.
+fn _breadcrumbs(todos: &TodoMap, id: &u32, todo: &Todo) -> String {
+    todos
+        .ancestors(id)
+        .iter()
+        .filter_map(|pa_id| todos.get(pa_id))
+        .map(|pa| pa.desc.as_str())
+        .chain([todo.desc.as_str()])
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
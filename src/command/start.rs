@@ -0,0 +1,70 @@
+use crate::{
+    command::{RunMut, done::set_status},
+    context::Context,
+    model::{Status, TodoRef, local_time},
+    util::get_list,
+};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct StartOptions {
+    #[arg(required = true, value_delimiter = ',')]
+    todo_id: Vec<u32>,
+    /// Don't start the timer
+    #[arg(long)]
+    no_timer: bool,
+    /// Start todo(s) in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for StartOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "start", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "start", "list [{list}]");
+        debug!(target: "start", "todo [{:?}]", self.todo_id);
+
+        // the timer follows a single todo only
+        let timed = match self.todo_id.as_slice() {
+            [id] if !self.no_timer => Some(*id),
+            _ => None,
+        };
+
+        let changed = set_status(ctx, &list, self.todo_id, Status::Progress)?;
+        changed.print("In Progress");
+
+        if let Some(id) = timed {
+            _start_timer(ctx, &list, id)?;
+        }
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
+
+fn _start_timer(ctx: &mut Context, list: &String, id: u32) -> Result<()> {
+    if ctx.store.todo_by_id(list, &id)?.status() != Status::Progress {
+        debug!(target: "start::_start_timer", "todo [{id}] is not in progress");
+        return Ok(());
+    }
+    let todo = TodoRef {
+        list: Some(list.to_owned()),
+        id,
+    };
+    if let Some(timer) = ctx.store.timer().filter(|t| t.todo == todo) {
+        println!("Timer running since {}", local_time(&timer.start));
+        return Ok(());
+    }
+
+    let now = ctx.config.timezone().now();
+    if let Some((stopped, effort)) = ctx.store.start_timer(todo.clone(), now) {
+        println!("Timer stopped:");
+        println!("{stopped} {effort}");
+    }
+    println!("Timer started:");
+    println!("{todo}");
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use crate::{
+    command::Run,
+    context::Context,
+    date::{AbsDateOptions, DateRange, RelDateOptions, Tz, date_range},
+    model::{Status, TodoMap},
+    ui::chart::{bar, sparkline},
+};
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use clap::Args;
+use log::{debug, trace};
+use serde::Serialize;
+
+#[derive(Debug, Args)]
+pub struct StatsOptions {
+    #[command(flatten)]
+    rel_date: Option<RelDateOptions>,
+    #[command(flatten)]
+    abs_date: Option<AbsDateOptions>,
+    /// Print the statistics as JSON
+    #[arg(long)]
+    json: bool,
+    /// Only count todos in specified list, all lists by default
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+/// Statistics of all lists in a date range, as printed by `--json`.
+#[derive(Debug, Serialize)]
+struct Report {
+    start: NaiveDate,
+    end: NaiveDate,
+    lists: Vec<Stats>,
+    /// All lists together, `None` if only one list is counted.
+    total: Option<Stats>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Stats {
+    list: String,
+    /// Todos created in the range.
+    created: usize,
+    /// Todos completed in the range.
+    completed: usize,
+    /// Todos still open at the end of the range.
+    open: usize,
+    /// Average age in days of the open todos at the end of the range.
+    open_age_days: Option<f64>,
+    /// Average days from creation to completion of the completed todos.
+    lead_time_days: Option<f64>,
+    /// Completions per weekday, Monday first.
+    completed_by_weekday: [usize; 7],
+    daily: Vec<Daily>,
+    #[serde(skip)]
+    open_age_sum: f64,
+    #[serde(skip)]
+    lead_time_sum: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Daily {
+    date: NaiveDate,
+    created: usize,
+    completed: usize,
+}
+
+impl Run for StatsOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "stats", "{self:#?}");
+
+        // the last 30 days if DATE_OPTION is omitted
+        let tz = ctx.config.timezone();
+        let today = tz.today();
+        let range = date_range(&self.rel_date, &self.abs_date, today)
+            .map_err(|err| anyhow!(err))?
+            .or_else(|| {
+                Some(DateRange {
+                    start: today.checked_sub_days(Days::new(29))?,
+                    end: today,
+                })
+            })
+            .ok_or_else(|| anyhow!("invalid date range"))?;
+        debug!(target: "stats", "date range: {range:?}");
+
+        let mut lists: Vec<_> = match &self.list {
+            Some(list) => vec![(list, ctx.store.todos(list)?)],
+            None => ctx.store.lists().iter().collect(),
+        };
+        lists.sort_by_key(|(list, _)| *list);
+
+        let stats: Vec<_> = lists
+            .iter()
+            .map(|(list, todos)| _stats(list, [*todos], &range, &tz, today))
+            .collect();
+        let total = (stats.len() > 1).then(|| {
+            _stats(
+                "(all)",
+                lists.iter().map(|(_, todos)| *todos),
+                &range,
+                &tz,
+                today,
+            )
+        });
+        let report = Report {
+            start: range.start,
+            end: range.end,
+            lists: stats,
+            total,
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(());
+        }
+
+        println!("{} ~ {}", report.start, report.end);
+        for stats in report.lists.iter().chain(&report.total) {
+            println!();
+            _print(stats);
+        }
+
+        Ok(())
+    }
+}
+
+fn _stats<'a>(
+    list: &str,
+    todo_maps: impl IntoIterator<Item = &'a TodoMap>,
+    range: &DateRange,
+    tz: &Tz,
+    today: NaiveDate,
+) -> Stats {
+    // open todos are counted as of the end of the range, or today
+    let until = range.end.min(today);
+    let mut stats = Stats {
+        list: list.to_owned(),
+        daily: range
+            .days()
+            .map(|date| Daily {
+                date,
+                created: 0,
+                completed: 0,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    for todo in todo_maps.into_iter().flat_map(|todos| todos.values()) {
+        let create_at = tz.date(&todo.create_at());
+        let complete_at = todo.complete_at().map(|t| tz.date(&t));
+
+        if range.contains(create_at) {
+            stats.created += 1;
+            stats.daily[_day_index(range, create_at)].created += 1;
+        }
+        if let Some(complete_at) = complete_at.filter(|d| range.contains(*d)) {
+            stats.completed += 1;
+            stats.daily[_day_index(range, complete_at)].completed += 1;
+            stats.completed_by_weekday
+                [complete_at.weekday().num_days_from_monday() as usize] += 1;
+            stats.lead_time_sum += _days(
+                todo.complete_at().unwrap_or(todo.create_at())
+                    - todo.create_at(),
+            );
+        }
+
+        let open = match todo.status() {
+            Status::Cancelled => false,
+            Status::Done => complete_at.is_some_and(|d| d > until),
+            _ => true,
+        };
+        if open && create_at <= until {
+            stats.open += 1;
+            stats.open_age_sum += (until - create_at).num_days() as f64;
+        }
+    }
+
+    stats.open_age_days =
+        (stats.open > 0).then(|| stats.open_age_sum / stats.open as f64);
+    stats.lead_time_days = (stats.completed > 0)
+        .then(|| stats.lead_time_sum / stats.completed as f64);
+    stats
+}
+
+fn _day_index(range: &DateRange, date: NaiveDate) -> usize {
+    (date - range.start).num_days() as usize
+}
+
+fn _days(delta: chrono::TimeDelta) -> f64 {
+    delta.num_seconds() as f64 / 86400.0
+}
+
+fn _print(stats: &Stats) {
+    let days =
+        |d: Option<f64>| d.map_or("-".to_owned(), |d| format!("{d:.1}d"));
+    println!("{}", stats.list);
+    println!(
+        "  created {}  completed {}  open {}",
+        stats.created, stats.completed, stats.open
+    );
+    println!(
+        "  open age {}  lead time {}",
+        days(stats.open_age_days),
+        days(stats.lead_time_days)
+    );
+
+    let created: Vec<_> = stats.daily.iter().map(|d| d.created).collect();
+    let completed: Vec<_> = stats.daily.iter().map(|d| d.completed).collect();
+    println!("  created   |{}|", sparkline(&created));
+    println!("  completed |{}|", sparkline(&completed));
+
+    let max = stats
+        .completed_by_weekday
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    let mut weekday = Weekday::Mon;
+    for count in stats.completed_by_weekday {
+        let line = format!("  {weekday} {count:>4} {}", bar(count, max, 20));
+        println!("{}", line.trim_end());
+        weekday = weekday.succ();
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{command::RunMut, context::Context};
+use anyhow::{Result, bail};
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct StopOptions;
+
+impl RunMut for StopOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "stop", "{self:#?}");
+
+        let now = ctx.config.timezone().now();
+        let Some((todo, effort)) = ctx.store.stop_timer(now) else {
+            bail!("no timer is running");
+        };
+        debug!(target: "stop", "timer on {todo}: {effort}");
+
+        println!("Timer stopped:");
+        println!("{todo} {effort}");
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
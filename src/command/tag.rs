@@ -0,0 +1,67 @@
+use crate::{
+    command::RunMut, context::Context, selection::SelectionOptions,
+    util::get_list,
+};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct TagOptions {
+    #[command(flatten)]
+    selection: SelectionOptions,
+    #[command(flatten)]
+    change: TagChange,
+    /// Tag todo(s) in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[group(required = true, multiple = true)]
+struct TagChange {
+    /// Tag(s) to add, e.g. bug,infra
+    #[arg(long, value_name = "TAG", value_delimiter = ',')]
+    add: Vec<String>,
+    /// Tag(s) to remove
+    #[arg(long, value_name = "TAG", value_delimiter = ',')]
+    remove: Vec<String>,
+}
+
+impl RunMut for TagOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "tag", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "tag", "list [{list}]");
+
+        let Some(todo_id) = self.selection.select(ctx, &list, "tagged")? else {
+            return Ok(());
+        };
+
+        let mut changed = Vec::new();
+        for id in todo_id {
+            let todo = ctx.store.todo_by_id_mut(&list, &id)?;
+            let before = todo.tags.clone();
+            let tags = todo.tags.get_or_insert_with(Vec::new);
+            tags.retain(|tag| !self.change.remove.contains(tag));
+            for tag in &self.change.add {
+                if !tags.contains(tag) {
+                    tags.push(tag.to_owned());
+                }
+            }
+            if tags.is_empty() {
+                todo.tags = None;
+            }
+            if todo.tags != before {
+                debug!(target: "tag", "todo [{id}] tags {:?}", todo.tags);
+                changed.push(id);
+            }
+        }
+
+        println!("Tagged:");
+        println!("{changed:?}");
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
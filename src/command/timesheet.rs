@@ -0,0 +1,124 @@
+use crate::{
+    command::Run,
+    context::Context,
+    date::{AbsDateOptions, DateRange, Period, RelDateOptions, date_range},
+    model::effort::Effort,
+};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use clap::{Args, ValueEnum};
+use log::{debug, trace};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Args)]
+pub struct TimesheetOptions {
+    #[command(flatten)]
+    rel_date: Option<RelDateOptions>,
+    #[command(flatten)]
+    abs_date: Option<AbsDateOptions>,
+    /// Sum up logged time by day or by week
+    #[arg(short, long, value_name = "PERIOD", default_value = "day")]
+    by: Group,
+    /// Only report todos in specified list, all lists by default
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Group {
+    Day,
+    Week,
+}
+
+/// period -> list -> todo -> logged time
+type Sheet = BTreeMap<NaiveDate, BTreeMap<String, BTreeMap<u32, Effort>>>;
+
+impl Run for TimesheetOptions {
+    fn run(self, ctx: &Context) -> Result<()> {
+        trace!(target: "timesheet", "{self:#?}");
+
+        // the current week if DATE_OPTION is omitted
+        let tz = ctx.config.timezone();
+        let today = tz.today();
+        let range = date_range(&self.rel_date, &self.abs_date, today)
+            .map_err(|err| anyhow!(err))?
+            .or_else(|| DateRange::period(today, Period::Week))
+            .ok_or_else(|| anyhow!("invalid date range"))?;
+        debug!(target: "timesheet", "date range: {range:?}");
+
+        let lists: Vec<_> = match &self.list {
+            Some(list) => vec![(list, ctx.store.todos(list)?)],
+            None => ctx.store.lists().iter().collect(),
+        };
+
+        let mut sheet = Sheet::new();
+        for (list, todos) in lists {
+            for (id, todo) in todos.iter() {
+                for entry in todo.time_log.iter().flatten() {
+                    let date = tz.date(&entry.start);
+                    if !range.contains(date) {
+                        continue;
+                    }
+                    *sheet
+                        .entry(_period_start(date, self.by))
+                        .or_default()
+                        .entry(list.to_owned())
+                        .or_default()
+                        .entry(*id)
+                        .or_default() += entry.effort;
+                }
+            }
+        }
+        debug!(target: "timesheet", "{} period(s)", sheet.len());
+
+        if sheet.is_empty() {
+            println!("No time logged in {} ~ {}", range.start, range.end);
+            return Ok(());
+        }
+
+        let mut total = Effort::default();
+        for (start, lists) in &sheet {
+            let label = match self.by {
+                Group::Day => start.format("%Y-%m-%d %a").to_string(),
+                Group::Week => format!(
+                    "{start} ~ {}",
+                    _period_end(*start).unwrap_or(*start)
+                ),
+            };
+            let period_total: Effort = lists
+                .values()
+                .flat_map(|todos| todos.values())
+                .copied()
+                .sum();
+            println!("{label}  {period_total}");
+
+            for (list, todos) in lists {
+                let list_total: Effort = todos.values().copied().sum();
+                println!("  {list}  {list_total}");
+                for (id, effort) in todos {
+                    let desc = ctx
+                        .store
+                        .todo_by_id(list, id)
+                        .map_or("", |todo| todo.desc.as_str());
+                    println!("    {:>8}  #{id} {desc}", effort.to_string());
+                }
+            }
+            total += period_total;
+        }
+        println!("Total: {total}");
+
+        Ok(())
+    }
+}
+
+fn _period_start(date: NaiveDate, by: Group) -> NaiveDate {
+    match by {
+        Group::Day => date,
+        Group::Week => DateRange::period(date, Period::Week)
+            .map_or(date, |week| week.start),
+    }
+}
+
+fn _period_end(week_start: NaiveDate) -> Option<NaiveDate> {
+    DateRange::period(week_start, Period::Week).map(|week| week.end)
+}
@@ -1,4 +1,9 @@
-use crate::{command::Run, context::Context, util::get_list};
+use crate::{
+    command::Run,
+    context::Context,
+    model::{TodoRef, effort::Effort, local_time},
+    util::get_list,
+};
 use anyhow::Result;
 use clap::Args;
 use log::{debug, trace};
@@ -22,6 +27,23 @@ impl Run for TrackOptions {
         let todo = ctx.store.todo_by_id(&list, &self.todo_id)?;
         println!("{}", todo);
 
+        if todo.children.is_some() {
+            let total = ctx.store.todos(&list)?.total_spent(&self.todo_id);
+            println!("Time Spent (with children): {total}");
+        }
+        let timed = TodoRef {
+            list: Some(list.to_owned()),
+            id: self.todo_id,
+        };
+        if let Some(timer) = ctx.store.timer().filter(|t| t.todo == timed) {
+            let now = ctx.config.timezone().now();
+            println!(
+                "Timer: running since {} ({})",
+                local_time(&timer.start),
+                Effort::between(&timer.start, &now)
+            );
+        }
+
         Ok(())
     }
 }
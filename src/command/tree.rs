@@ -1,12 +1,15 @@
 use crate::{
     command::Run,
     context::Context,
+    date::{AbsDateOptions, RelDateOptions},
+    model::Status,
     ui::tree::TreeUI,
-    util::{SyncBlock, get_list},
+    util::{SyncBlock, get_list, get_query},
 };
 use anyhow::Result;
 use clap::Args;
 use log::{debug, trace};
+use std::collections::HashSet;
 
 #[derive(Debug, Args)]
 pub struct TreeOptions {
@@ -20,6 +23,18 @@ pub struct TreeOptions {
     /// Filter completed todos
     #[arg(long)]
     done: bool,
+    /// Filter todos in specified status(es), cancelled todos are hidden by default
+    #[arg(
+        short,
+        long,
+        value_name = "STATUS",
+        value_delimiter = ',',
+        conflicts_with_all = ["todo", "done"]
+    )]
+    status: Vec<Status>,
+    /// Filter todos matching QUERY, e.g. `status:open and tag:bug`
+    #[arg(short, long, value_name = "QUERY")]
+    query: Option<String>,
     /// Reverse sort order
     #[arg(short = 'R', long)]
     reverse: bool,
@@ -37,6 +52,36 @@ impl Run for TreeOptions {
 
         let todos = ctx.store.todos(&list)?;
 
+        let status = if self.todo {
+            Status::OPEN.to_vec()
+        } else if self.done {
+            vec![Status::Done]
+        } else if self.status.is_empty() {
+            [Status::OPEN.as_slice(), &[Status::Done]].concat()
+        } else {
+            self.status
+        };
+        debug!(target: "tree", "status {status:?}");
+
+        let query = get_query(self.query.as_deref(), ctx)?;
+        let tz = ctx.config.timezone();
+        let shown: HashSet<_> = todos
+            .iter()
+            .filter(|(id, todo)| {
+                status.contains(&todo.status())
+                    && query.as_ref().is_none_or(|q| q.matches(todos, id, tz))
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut blocked = HashSet::new();
+        for id in todos.keys() {
+            if !ctx.store.open_blockers(&list, id)?.is_empty() {
+                blocked.insert(*id);
+            }
+        }
+        debug!(target: "tree", "blocked todo [{blocked:?}]");
+
         // TODO: TUI
         trace!(target: "tree", "TODO [tui]");
 
@@ -45,6 +90,8 @@ impl Run for TreeOptions {
             &String::from(ctx.store_path.to_string_lossy()),
             &list,
             todos,
+            &shown,
+            &blocked,
             ctx.config.tree_line(),
         ))?;
 
@@ -90,72 +137,3 @@ impl Run for TreeOptions {
         Ok(())
     }
 }
-
-#[derive(Debug, Args)]
-#[group(multiple = false, conflicts_with = "AbsDateOptions")]
-#[command(next_help_heading = "Relative Date")]
-pub struct RelDateOptions {
-    /// (default: 0) Relative date in DAY. <+OFFSET> indicates future and <-OFFSET> is past
-    #[arg(
-                short = 'd',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0",
-                next_line_help = true
-            )]
-    pub day_rel: Option<i32>,
-    /// (default: 0) Relative date in WEEK. Same as --day_rel
-    #[arg(
-                short = 'w',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0"
-            )]
-    pub week_rel: Option<i32>,
-    /// (default: 0) Relative date in MONTH. Same as --day_rel
-    #[arg(
-                short = 'm',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0"
-            )]
-    pub month_rel: Option<i32>,
-    /// (default: 0) Relative date in YEAR. Same as --day_rel
-    #[arg(
-                short = 'y',
-                long,
-                value_name = "OFFSET",
-                allow_negative_numbers = true,
-                num_args = 0..=1,
-                default_missing_value = "0"
-            )]
-    pub year_rel: Option<i32>,
-}
-
-#[derive(Debug, Args)]
-#[group(multiple = true)]
-#[command(next_help_heading = "Absolute Date")]
-pub struct AbsDateOptions {
-    /// Specify a date
-    #[arg(
-                short = 'D',
-                long,
-                value_name = "YYYY-MM-DD",
-                num_args = 1,
-                conflicts_with_all = ["month", "year"],
-                next_line_help = true
-            )]
-    pub date: Option<String>,
-    /// <N> must be 1~12 indicating the N-th month of a year, and the current year is assumed by default
-    #[arg(short = 'M', long, value_name = "N", num_args = 1)]
-    pub month: Option<u32>,
-    /// (default: current year) <N> must be a positive integer indicating the specific year
-    #[arg(short = 'Y', long, value_name = "N", num_args = 1)]
-    pub year: Option<u32>,
-}
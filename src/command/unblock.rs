@@ -0,0 +1,44 @@
+use crate::{
+    command::RunMut, context::Context, model::TodoRef, util::get_list,
+};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct UnblockOptions {
+    todo_id: u32,
+    /// Todo(s) no longer blocking <TODO_ID>, all blockers are removed if omitted
+    #[arg(short, long, value_name = "TODO_ID", value_delimiter = ',')]
+    by: Vec<TodoRef>,
+    /// Unblock todo in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for UnblockOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "unblock", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "unblock", "list [{list}]");
+
+        let blockers: Vec<_> =
+            self.by.into_iter().map(|r| r.resolve(&list)).collect();
+        debug!(target: "unblock", "blockers {blockers:?}");
+
+        let todo = ctx.store.todo_by_id_mut(&list, &self.todo_id)?;
+        if blockers.is_empty() {
+            todo.depends_on = None;
+        } else if let Some(depends_on) = &mut todo.depends_on {
+            depends_on.retain(|r| !blockers.contains(r));
+            if depends_on.is_empty() {
+                todo.depends_on = None;
+            }
+        }
+
+        println!("{}", todo);
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::{
+    command::{RunMut, done::set_status},
+    context::Context,
+    model::Status,
+    util::get_list,
+};
+use anyhow::Result;
+use clap::Args;
+use log::{debug, trace};
+
+#[derive(Debug, Args)]
+pub struct WaitOptions {
+    #[arg(required = true, value_delimiter = ',')]
+    todo_id: Vec<u32>,
+    /// Mark todo(s) in specified list
+    #[arg(short, long, value_name = "LIST_NAME")]
+    list: Option<String>,
+}
+
+impl RunMut for WaitOptions {
+    fn run_mut(self, ctx: &mut Context) -> Result<()> {
+        trace!(target: "wait", "{self:#?}");
+
+        let list = get_list(self.list, ctx);
+        debug!(target: "wait", "list [{list}]");
+        debug!(target: "wait", "todo [{:?}]", self.todo_id);
+
+        let changed = set_status(ctx, &list, self.todo_id, Status::Waiting)?;
+        changed.print("Waiting");
+
+        ctx.store.write(&ctx.store_path)
+    }
+}
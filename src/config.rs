@@ -1,8 +1,8 @@
-use crate::util::expand_tilde;
+use crate::{date::Tz, util::expand_tilde};
 use anyhow::Result;
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
-use std::{env, fs, path::PathBuf};
+use std::{collections::HashMap, env, fs, path::PathBuf};
 
 const CONFIG_FILE: &str = "taso/config";
 
@@ -12,11 +12,17 @@ const D_DATA_FILE_NAME: &str = ".todo";
 const D_TREE_LINE: u8 = 10;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     default_list: String,
     global_store: PathBuf,
     data_file_name: String,
     tree_line: u8,
+    /// `local` or a UTC offset such as `+09:00`, used to tell the calendar day
+    /// of timestamps
+    timezone: Tz,
+    /// Named queries, used as `@NAME` in query expressions
+    queries: HashMap<String, String>,
 }
 
 impl Default for Config {
@@ -26,6 +32,8 @@ impl Default for Config {
             global_store: expand_tilde(PathBuf::from(D_GLOBAL_STORE)),
             data_file_name: D_DATA_FILE_NAME.to_owned(),
             tree_line: D_TREE_LINE,
+            timezone: Tz::default(),
+            queries: HashMap::new(),
         }
     }
 }
@@ -68,4 +76,12 @@ impl Config {
     pub fn tree_line(&self) -> u8 {
         self.tree_line
     }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    pub fn queries(&self) -> &HashMap<String, String> {
+        &self.queries
+    }
 }
@@ -0,0 +1,748 @@
+use crate::model::Timestamp;
+use chrono::{
+    Datelike, Days, FixedOffset, Local, Months, NaiveDate, NaiveDateTime,
+    NaiveTime, Utc, Weekday,
+};
+use clap::Args;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// Hint appended to parse errors.
+const EXPECTED: &str = "expected YYYY-MM-DD, today, tomorrow, yesterday, a weekday (e.g. fri, next friday, last mon), in 3 days, 2 weeks ago, +2w, -1m, next month, end of week or start of year";
+
+/// Parses a date expression relative to `today`.
+///
+/// Accepted expressions (case insensitive):
+///
+/// | Expression                               | Date
+/// | :--------------------------------------- | :-------------------------------
+/// | `2025-06-01`                             | the given date
+/// | `today`, `tomorrow`, `yesterday`         |
+/// | `fri`, `friday`, `next friday`           | the first friday after today
+/// | `last friday`                            | the last friday before today
+/// | `in 3 days`, `3 days ago`                | also with weeks, months, years
+/// | `3d`, `+2w`, `-1m`, `+1y`, `+3`, `-3`    | offset, in days without unit
+/// | `next week`, `last month`, `this year`   | first day of the period
+/// | `end of month`, `start of week`          | also `eow`, `som`, `eoy`, ...
+///
+/// Weeks start on Monday.
+pub fn parse_date(input: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let expr = input.trim().to_lowercase();
+    debug!(target: "date::parse_date", "expr [{expr}] at {today}");
+    let words: Vec<_> = expr.split_whitespace().collect();
+
+    let date = match words.as_slice() {
+        [] => return Err(format!("empty date, {EXPECTED}")),
+        ["today"] | ["now"] => Some(today),
+        ["tomorrow"] => today.succ_opt(),
+        ["yesterday"] => today.pred_opt(),
+        [word] if is_iso_date(word) => {
+            return NaiveDate::parse_from_str(word, "%Y-%m-%d")
+                .map_err(|err| format!("invalid date [{input}]: {err}"));
+        }
+        [word] if word.parse::<Weekday>().is_ok() => {
+            Some(next_weekday(today, word.parse().unwrap()))
+        }
+        [word] => match parse_period_edge(word) {
+            Some((edge, period)) => period_edge(today, period, edge),
+            None => {
+                let (n, period) = parse_offset(word).ok_or_else(|| {
+                    format!("unknown date [{input}], {EXPECTED}")
+                })?;
+                shift(today, period, n)
+            }
+        },
+        ["next", word] | ["last", word] | ["this", word] => {
+            let n = match words[0] {
+                "next" => 1,
+                "last" => -1,
+                _ => 0,
+            };
+            if let Ok(weekday) = word.parse::<Weekday>() {
+                match n {
+                    1 => Some(next_weekday(today, weekday)),
+                    -1 => Some(last_weekday(today, weekday)),
+                    _ => Some(
+                        week_start(today)
+                            + Days::new(weekday.num_days_from_monday().into()),
+                    ),
+                }
+            } else {
+                let period = parse_period(word).ok_or_else(|| {
+                    format!("unknown period [{word}] in [{input}], {EXPECTED}")
+                })?;
+                shift(today, period, n)
+                    .and_then(|d| period_edge(d, period, Edge::Start))
+            }
+        }
+        ["in", n, unit] => {
+            let n = parse_count(n, input)?;
+            let period = parse_period(unit).ok_or_else(|| {
+                format!("unknown unit [{unit}] in [{input}], {EXPECTED}")
+            })?;
+            shift(today, period, n)
+        }
+        [n, unit, "ago"] => {
+            let n = parse_count(n, input)?;
+            let period = parse_period(unit).ok_or_else(|| {
+                format!("unknown unit [{unit}] in [{input}], {EXPECTED}")
+            })?;
+            shift(today, period, -n)
+        }
+        [edge, "of", period] | [edge, "of", "the", period] => {
+            let edge = match *edge {
+                "start" | "beginning" => Edge::Start,
+                "end" => Edge::End,
+                _ => {
+                    return Err(format!(
+                        "unknown date [{input}], expected start of or end of a period"
+                    ));
+                }
+            };
+            let period = parse_period(period).ok_or_else(|| {
+                format!("unknown period [{period}] in [{input}], {EXPECTED}")
+            })?;
+            period_edge(today, period, edge)
+        }
+        _ => return Err(format!("unknown date [{input}], {EXPECTED}")),
+    };
+
+    date.ok_or_else(|| format!("date [{input}] is out of range"))
+}
+
+/// A date expression given on the command line. Its syntax is checked when
+/// the arguments are parsed, but it is resolved only once the timezone of the
+/// configuration tells which day today is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DateExpr(String);
+
+impl DateExpr {
+    /// Parses a date expression, to be used as a clap `value_parser`.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        // any day will do to check the syntax
+        parse_date(input, Local::now().date_naive())?;
+        Ok(Self(input.to_owned()))
+    }
+
+    /// Parses the offset of a relative date option, a number of periods or a
+    /// date expression, to be used as a clap `value_parser`.
+    pub fn parse_offset(input: &str) -> Result<Self, String> {
+        match input.trim().parse::<i32>() {
+            Ok(_) => Ok(Self(input.to_owned())),
+            Err(_) => Self::parse(input),
+        }
+    }
+
+    /// Resolves the expression relative to `today`.
+    pub fn resolve(&self, today: NaiveDate) -> Result<NaiveDate, String> {
+        parse_date(&self.0, today)
+    }
+
+    /// Resolves the expression relative to `today`, a number without unit is
+    /// an offset in `period`.
+    fn resolve_in(
+        &self,
+        today: NaiveDate,
+        period: Period,
+    ) -> Result<NaiveDate, String> {
+        match self.0.trim().parse::<i32>() {
+            Ok(n) => shift(today, period, n.into())
+                .ok_or_else(|| format!("date [{}] is out of range", self.0)),
+            Err(_) => self.resolve(today),
+        }
+    }
+}
+
+impl Display for DateExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Timezone in which timestamps are split into calendar days, either the
+/// local one of the system or a fixed UTC offset such as `+09:00`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub enum Tz {
+    #[default]
+    Local,
+    Fixed(FixedOffset),
+}
+
+impl Tz {
+    pub fn now(&self) -> Timestamp {
+        match self {
+            Tz::Local => Local::now().fixed_offset(),
+            Tz::Fixed(offset) => Utc::now().with_timezone(offset),
+        }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    /// Returns the start of `date` in the timezone.
+    pub fn start_of_day(&self, date: NaiveDate) -> Option<Timestamp> {
+        self.local(date.and_time(NaiveTime::MIN))
+    }
+
+    /// Returns the timestamp of the wall clock `datetime` in the timezone.
+    pub fn local(&self, datetime: NaiveDateTime) -> Option<Timestamp> {
+        match self {
+            Tz::Local => datetime
+                .and_local_timezone(Local)
+                .earliest()
+                .map(|t| t.fixed_offset()),
+            Tz::Fixed(offset) => datetime.and_local_timezone(*offset).single(),
+        }
+    }
+
+    /// Returns the calendar day of `timestamp` in this timezone.
+    pub fn date(&self, timestamp: &Timestamp) -> NaiveDate {
+        self.datetime(timestamp).date()
+    }
+
+    /// Returns the wall clock time of `timestamp` in this timezone.
+    pub fn datetime(&self, timestamp: &Timestamp) -> NaiveDateTime {
+        match self {
+            Tz::Local => timestamp.with_timezone(&Local).naive_local(),
+            Tz::Fixed(offset) => timestamp.with_timezone(offset).naive_local(),
+        }
+    }
+}
+
+impl FromStr for Tz {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "local" => Ok(Tz::Local),
+            "utc" | "z" => Ok(Tz::Fixed(FixedOffset::east_opt(0).unwrap())),
+            offset => offset
+                .parse::<FixedOffset>()
+                .map(Tz::Fixed)
+                .map_err(|_| {
+                    format!(
+                        "invalid timezone [{s}], expected local, utc or an offset like +09:00"
+                    )
+                }),
+        }
+    }
+}
+
+impl TryFrom<String> for Tz {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Tz> for String {
+    fn from(value: Tz) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Tz {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tz::Local => write!(f, "local"),
+            Tz::Fixed(offset) => write!(f, "{offset}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Edge {
+    Start,
+    End,
+}
+
+fn is_iso_date(word: &str) -> bool {
+    word.len() >= 8
+        && word.chars().next().is_some_and(|c| c.is_ascii_digit())
+        && word.contains('-')
+}
+
+fn parse_count(n: &str, input: &str) -> Result<i64, String> {
+    match n {
+        "a" | "an" | "one" => Ok(1),
+        _ => n
+            .parse::<u32>()
+            .map(i64::from)
+            .map_err(|_| format!("invalid number [{n}] in [{input}]")),
+    }
+}
+
+fn parse_period(word: &str) -> Option<Period> {
+    match word {
+        "d" | "day" | "days" => Some(Period::Day),
+        "w" | "week" | "weeks" => Some(Period::Week),
+        "m" | "month" | "months" => Some(Period::Month),
+        "y" | "year" | "years" => Some(Period::Year),
+        _ => None,
+    }
+}
+
+/// Parses `eow`, `som`, ... into the edge of a period.
+fn parse_period_edge(word: &str) -> Option<(Edge, Period)> {
+    let edge = match word.get(..2)? {
+        "eo" => Edge::End,
+        "so" => Edge::Start,
+        _ => return None,
+    };
+    let period = match word.get(2..)? {
+        "d" => Period::Day,
+        "w" => Period::Week,
+        "m" => Period::Month,
+        "y" => Period::Year,
+        _ => return None,
+    };
+    Some((edge, period))
+}
+
+/// Parses `3d`, `+2w`, `-1m`, `+3` into a signed offset.
+fn parse_offset(word: &str) -> Option<(i64, Period)> {
+    let (sign, rest) = match word.as_bytes().first()? {
+        b'+' => (1, &word[1..]),
+        b'-' => (-1, &word[1..]),
+        _ => (1, word),
+    };
+    let split = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    let (n, unit) = rest.split_at(split);
+    let n = n.parse::<u32>().ok()?;
+    let period = match unit {
+        // a bare number is only an offset with an explicit sign
+        "" if rest.len() < word.len() => Period::Day,
+        "" => return None,
+        unit => parse_period(unit)?,
+    };
+    Some((sign * i64::from(n), period))
+}
+
+/// Moves `date` by `n` periods, months are clamped to their last day.
+pub fn shift(date: NaiveDate, period: Period, n: i64) -> Option<NaiveDate> {
+    let days = |n: i64| {
+        if n >= 0 {
+            date.checked_add_days(Days::new(n.unsigned_abs()))
+        } else {
+            date.checked_sub_days(Days::new(n.unsigned_abs()))
+        }
+    };
+    let months = |n: i64| {
+        let months = Months::new(u32::try_from(n.unsigned_abs()).ok()?);
+        if n >= 0 {
+            date.checked_add_months(months)
+        } else {
+            date.checked_sub_months(months)
+        }
+    };
+    match period {
+        Period::Day => days(n),
+        Period::Week => days(n.checked_mul(7)?),
+        Period::Month => months(n),
+        Period::Year => months(n.checked_mul(12)?),
+    }
+}
+
+fn period_edge(
+    date: NaiveDate,
+    period: Period,
+    edge: Edge,
+) -> Option<NaiveDate> {
+    let start = match period {
+        Period::Day => date,
+        Period::Week => week_start(date),
+        Period::Month => date.with_day(1)?,
+        Period::Year => date.with_ordinal(1)?,
+    };
+    match edge {
+        Edge::Start => Some(start),
+        Edge::End => shift(start, period, 1)?.pred_opt(),
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday().into())
+}
+
+/// The first `weekday` after `date`.
+fn next_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (7 + weekday.num_days_from_monday()
+        - date.weekday().num_days_from_monday())
+        % 7;
+    date + Days::new(if ahead == 0 {
+        7
+    } else {
+        ahead.into()
+    })
+}
+
+/// The last `weekday` before `date`.
+fn last_weekday(date: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let behind = (7 + date.weekday().num_days_from_monday()
+        - weekday.num_days_from_monday())
+        % 7;
+    date - Days::new(if behind == 0 {
+        7
+    } else {
+        behind.into()
+    })
+}
+
+/// An inclusive range of dates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    /// The whole period containing `date`.
+    pub fn period(date: NaiveDate, period: Period) -> Option<Self> {
+        Some(Self {
+            start: period_edge(date, period, Edge::Start)?,
+            end: period_edge(date, period, Edge::End)?,
+        })
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    /// Iterates over all days of the range.
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let end = self.end;
+        self.start.iter_days().take_while(move |d| *d <= end)
+    }
+}
+
+/*
+*
+* Clap options
+*
+*/
+
+#[derive(Debug, Args)]
+#[group(multiple = false, conflicts_with = "AbsDateOptions")]
+#[command(next_help_heading = "Relative Date")]
+pub struct RelDateOptions {
+    /// (default: 0) Relative date in DAY. <+OFFSET> indicates future and <-OFFSET> is past, a date expression picks the day containing it, e.g. yesterday
+    #[arg(
+                short = 'd',
+                long,
+                value_name = "OFFSET",
+                value_parser = DateExpr::parse_offset,
+                allow_negative_numbers = true,
+                num_args = 0..=1,
+                default_missing_value = "0",
+                next_line_help = true
+            )]
+    pub day_rel: Option<DateExpr>,
+    /// (default: 0) Relative date in WEEK. Same as --day_rel
+    #[arg(
+                short = 'w',
+                long,
+                value_name = "OFFSET",
+                value_parser = DateExpr::parse_offset,
+                allow_negative_numbers = true,
+                num_args = 0..=1,
+                default_missing_value = "0"
+            )]
+    pub week_rel: Option<DateExpr>,
+    /// (default: 0) Relative date in MONTH. Same as --day_rel
+    #[arg(
+                short = 'm',
+                long,
+                value_name = "OFFSET",
+                value_parser = DateExpr::parse_offset,
+                allow_negative_numbers = true,
+                num_args = 0..=1,
+                default_missing_value = "0"
+            )]
+    pub month_rel: Option<DateExpr>,
+    /// (default: 0) Relative date in YEAR. Same as --day_rel
+    #[arg(
+                short = 'y',
+                long,
+                value_name = "OFFSET",
+                value_parser = DateExpr::parse_offset,
+                allow_negative_numbers = true,
+                num_args = 0..=1,
+                default_missing_value = "0"
+            )]
+    pub year_rel: Option<DateExpr>,
+}
+
+impl RelDateOptions {
+    /// Resolves the period relative to `today`.
+    pub fn range(&self, today: NaiveDate) -> Result<DateRange, String> {
+        let (expr, period) = match self {
+            Self {
+                day_rel: Some(e), ..
+            } => (Some(e), Period::Day),
+            Self {
+                week_rel: Some(e), ..
+            } => (Some(e), Period::Week),
+            Self {
+                month_rel: Some(e), ..
+            } => (Some(e), Period::Month),
+            Self {
+                year_rel: Some(e), ..
+            } => (Some(e), Period::Year),
+            _ => (None, Period::Day),
+        };
+        let date = match expr {
+            Some(expr) => expr.resolve_in(today, period)?,
+            None => today,
+        };
+        DateRange::period(date, period)
+            .ok_or_else(|| format!("date [{date}] is out of range"))
+    }
+}
+
+#[derive(Debug, Args)]
+#[group(multiple = true)]
+#[command(next_help_heading = "Absolute Date")]
+pub struct AbsDateOptions {
+    /// Specify a date, e.g. 2025-06-01, today, next friday, in 3 days, -1w
+    #[arg(
+                short = 'D',
+                long,
+                value_name = "DATE",
+                num_args = 1,
+                value_parser = DateExpr::parse,
+                conflicts_with_all = ["month", "year"],
+                next_line_help = true
+            )]
+    pub date: Option<DateExpr>,
+    /// <N> must be 1~12 indicating the N-th month of a year, and the current year is assumed by default
+    #[arg(
+        short = 'M',
+        long,
+        value_name = "N",
+        num_args = 1,
+        value_parser = clap::value_parser!(u32).range(1..=12)
+    )]
+    pub month: Option<u32>,
+    /// (default: current year) <N> must be a positive integer indicating the specific year
+    #[arg(short = 'Y', long, value_name = "N", num_args = 1)]
+    pub year: Option<u32>,
+}
+
+impl AbsDateOptions {
+    /// Resolves the period, the current year is assumed if it is missing.
+    pub fn range(&self, today: NaiveDate) -> Result<DateRange, String> {
+        let out_of_range = || "date is out of range".to_owned();
+        if let Some(date) = &self.date {
+            return DateRange::period(date.resolve(today)?, Period::Day)
+                .ok_or_else(out_of_range);
+        }
+        let year = match self.year {
+            Some(year) => i32::try_from(year).map_err(|_| out_of_range())?,
+            None => today.year(),
+        };
+        let start = match self.month {
+            Some(month) => NaiveDate::from_ymd_opt(year, month, 1)
+                .map(|date| (date, Period::Month)),
+            None => {
+                NaiveDate::from_ymd_opt(year, 1, 1).map(|d| (d, Period::Year))
+            }
+        };
+        start
+            .and_then(|(date, period)| DateRange::period(date, period))
+            .ok_or_else(out_of_range)
+    }
+}
+
+/// Resolves the date options of a command, `None` if both are omitted.
+pub fn date_range(
+    rel_date: &Option<RelDateOptions>,
+    abs_date: &Option<AbsDateOptions>,
+    today: NaiveDate,
+) -> Result<Option<DateRange>, String> {
+    match (rel_date, abs_date) {
+        (Some(rel), _) => rel.range(today).map(Some),
+        (None, Some(abs)) => abs.range(today).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 11).unwrap()
+    }
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn parse(input: &str) -> NaiveDate {
+        parse_date(input, today()).unwrap()
+    }
+
+    #[test]
+    fn keywords() {
+        assert_eq!(parse("today"), ymd(2025, 6, 11));
+        assert_eq!(parse(" Tomorrow "), ymd(2025, 6, 12));
+        assert_eq!(parse("yesterday"), ymd(2025, 6, 10));
+    }
+
+    #[test]
+    fn iso_dates() {
+        assert_eq!(parse("2024-02-29"), ymd(2024, 2, 29));
+        let err = parse_date("2025-02-30", today()).unwrap_err();
+        assert!(err.starts_with("invalid date [2025-02-30]"), "{err}");
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!(parse("fri"), ymd(2025, 6, 13));
+        assert_eq!(parse("friday"), ymd(2025, 6, 13));
+        assert_eq!(parse("next friday"), ymd(2025, 6, 13));
+        // the same weekday is a week later
+        assert_eq!(parse("wednesday"), ymd(2025, 6, 18));
+        assert_eq!(parse("last wed"), ymd(2025, 6, 4));
+        assert_eq!(parse("last friday"), ymd(2025, 6, 6));
+        assert_eq!(parse("this monday"), ymd(2025, 6, 9));
+    }
+
+    #[test]
+    fn relative_expressions() {
+        assert_eq!(parse("in 3 days"), ymd(2025, 6, 14));
+        assert_eq!(parse("in a week"), ymd(2025, 6, 18));
+        assert_eq!(parse("2 weeks ago"), ymd(2025, 5, 28));
+        assert_eq!(parse("in 1 month"), ymd(2025, 7, 11));
+        assert_eq!(parse("in 2 years"), ymd(2027, 6, 11));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse("2w"), ymd(2025, 6, 25));
+        assert_eq!(parse("+3d"), ymd(2025, 6, 14));
+        assert_eq!(parse("-1m"), ymd(2025, 5, 11));
+        assert_eq!(parse("+1y"), ymd(2026, 6, 11));
+        assert_eq!(parse("+2"), ymd(2025, 6, 13));
+        assert_eq!(parse("-2"), ymd(2025, 6, 9));
+        assert!(parse_date("2", today()).is_err());
+    }
+
+    #[test]
+    fn months_are_clamped() {
+        let jan_31 = ymd(2025, 1, 31);
+        assert_eq!(parse_date("+1m", jan_31).unwrap(), ymd(2025, 2, 28));
+    }
+
+    #[test]
+    fn periods() {
+        assert_eq!(parse("next week"), ymd(2025, 6, 16));
+        assert_eq!(parse("last week"), ymd(2025, 6, 2));
+        assert_eq!(parse("next month"), ymd(2025, 7, 1));
+        assert_eq!(parse("this year"), ymd(2025, 1, 1));
+        assert_eq!(parse("end of month"), ymd(2025, 6, 30));
+        assert_eq!(parse("end of the week"), ymd(2025, 6, 15));
+        assert_eq!(parse("start of year"), ymd(2025, 1, 1));
+        assert_eq!(parse("beginning of week"), ymd(2025, 6, 9));
+        assert_eq!(parse("eom"), ymd(2025, 6, 30));
+        assert_eq!(parse("eoy"), ymd(2025, 12, 31));
+        assert_eq!(parse("sow"), ymd(2025, 6, 9));
+    }
+
+    #[test]
+    fn errors() {
+        let err = parse_date("someday", today()).unwrap_err();
+        assert!(err.starts_with("unknown date [someday], expected"), "{err}");
+        let err = parse_date("in x days", today()).unwrap_err();
+        assert_eq!(err, "invalid number [x] in [in x days]");
+        let err = parse_date("in 3 fortnights", today()).unwrap_err();
+        assert!(err.starts_with("unknown unit [fortnights]"), "{err}");
+        let err = parse_date("middle of month", today()).unwrap_err();
+        assert!(err.contains("start of or end of"), "{err}");
+        assert!(parse_date("", today()).is_err());
+    }
+
+    #[test]
+    fn timezones() {
+        let tz: Tz = "+09:00".parse().unwrap();
+        let timestamp =
+            Timestamp::parse_from_rfc3339("2025-06-11T20:30:00+00:00").unwrap();
+        assert_eq!(tz.date(&timestamp), ymd(2025, 6, 12));
+        let tz: Tz = "UTC".parse().unwrap();
+        assert_eq!(tz.date(&timestamp), ymd(2025, 6, 11));
+        assert_eq!("local".parse::<Tz>(), Ok(Tz::Local));
+        assert!("Mars/Olympus".parse::<Tz>().is_err());
+    }
+
+    #[test]
+    fn ranges() {
+        let rel = RelDateOptions {
+            day_rel: None,
+            week_rel: Some(DateExpr::parse_offset("-1").unwrap()),
+            month_rel: None,
+            year_rel: None,
+        };
+        assert_eq!(
+            rel.range(today()),
+            Ok(DateRange {
+                start: ymd(2025, 6, 2),
+                end: ymd(2025, 6, 8)
+            })
+        );
+        // a date expression picks the period containing it
+        let rel = RelDateOptions {
+            day_rel: None,
+            week_rel: None,
+            month_rel: Some(DateExpr::parse_offset("in 3 weeks").unwrap()),
+            year_rel: None,
+        };
+        assert_eq!(rel.range(today()).unwrap().start, ymd(2025, 7, 1));
+        let abs = AbsDateOptions {
+            date: None,
+            month: Some(2),
+            year: Some(2024),
+        };
+        assert_eq!(
+            abs.range(today()),
+            Ok(DateRange {
+                start: ymd(2024, 2, 1),
+                end: ymd(2024, 2, 29)
+            })
+        );
+        let abs = AbsDateOptions {
+            date: None,
+            month: None,
+            year: None,
+        };
+        assert_eq!(abs.range(today()).unwrap().end, ymd(2025, 12, 31));
+    }
+
+    #[test]
+    fn expressions_are_resolved_late() {
+        let expr = DateExpr::parse("tomorrow").unwrap();
+        assert_eq!(expr.resolve(today()), Ok(ymd(2025, 6, 12)));
+        assert_eq!(expr.resolve(ymd(2025, 6, 12)), Ok(ymd(2025, 6, 13)));
+        assert!(DateExpr::parse("someday").is_err());
+        // a number without unit is only an offset of a relative date option
+        assert!(DateExpr::parse("2").is_err());
+        assert!(DateExpr::parse_offset("2").is_ok());
+        assert!(DateExpr::parse_offset("someday").is_err());
+    }
+}
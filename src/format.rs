@@ -0,0 +1,468 @@
+pub mod csv;
+pub mod ical;
+pub mod markdown;
+pub mod org;
+pub mod taskwarrior;
+pub mod todotxt;
+
+use crate::{
+    model::{Status, Timestamp, Todo, TodoMap, TodoRef},
+    store::Store,
+};
+use anyhow::{Result, bail};
+use chrono::NaiveTime;
+use clap::ValueEnum;
+use log::{debug, warn};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+/// File format of other tools that todos are exported to or imported from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// todo.txt, one todo per line
+    Todotxt,
+    /// Nested checklists of GitHub-flavored Markdown
+    Markdown,
+    /// iCalendar to-dos
+    Ical,
+    /// JSON of Taskwarrior
+    Taskwarrior,
+    /// Comma-separated values, one todo per row
+    Csv,
+    /// Outlines of Emacs Org mode
+    Org,
+}
+
+impl Format {
+    /// Guesses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "txt" => Some(Format::Todotxt),
+            "md" | "markdown" => Some(Format::Markdown),
+            "ics" | "ical" => Some(Format::Ical),
+            "json" => Some(Format::Taskwarrior),
+            "csv" => Some(Format::Csv),
+            "org" => Some(Format::Org),
+            _ => None,
+        }
+    }
+}
+
+/// Fields which couldn't be mapped, with the todos they were found on.
+pub type Report = BTreeMap<String, Vec<String>>;
+
+/// Prints `report` to stderr, so that it doesn't mix with an export.
+pub fn print_report(report: &Report) {
+    if report.is_empty() {
+        return;
+    }
+    eprintln!("Not mapped:");
+    for (field, todos) in report {
+        eprintln!("  {field}: {}", todos.join(", "));
+    }
+}
+
+/// Todos of a list picked for export.
+#[derive(Debug)]
+pub struct ExportedList<'a> {
+    pub list: &'a str,
+    pub todos: &'a TodoMap,
+    /// Ids of the exported todos in ascending order.
+    pub ids: Vec<u32>,
+}
+
+/// A todo read from a file, not yet in the store.
+///
+/// Keys only identify todos within the file they are read from, they are
+/// replaced by new ids once imported.
+#[derive(Debug)]
+pub struct Imported {
+    pub key: String,
+    /// Todo of the store the file was exported from, it is updated instead
+    /// of adding a new one if it still exists. A todo of the target list
+    /// with the same `uid` is updated as well.
+    pub existing: Option<TodoRef>,
+    /// Creation time given in the file, if any. Ids are reused once todos
+    /// are removed, so `existing` is only updated if it was created then.
+    pub create_at: Option<Timestamp>,
+    /// List named in the file, if any.
+    pub list: Option<String>,
+    /// Key of the parent todo. A key not found in the file is read as a
+    /// reference to a todo already in the store.
+    pub parent: Option<String>,
+    /// Keys of the blocking todos. Keys not found in the file are read as
+    /// references to todos already in the store.
+    pub depends_on: Vec<String>,
+    /// Status given in the file. `None` if it gives none, an updated todo
+    /// keeps its own then.
+    pub status: Option<Status>,
+    /// The todo itself, its `parent`, `children` and `depends_on` are
+    /// filled in from the keys.
+    pub todo: Todo,
+}
+
+/// Inserts `imported` todos with new ids into the list each of them names,
+/// or into `list` when they don't name one. Todos whose `existing` one is
+/// still in the store update it instead, unless it was created at another
+/// time than the file gives. Parents and blockers read from the
+/// same file are linked to the new ids, an updated todo follows the parent
+/// given in the file.
+///
+/// Returns the references to the todos, with whether they were updated, in
+/// the order of `imported`.
+pub fn insert(
+    store: &mut Store,
+    list: &str,
+    imported: Vec<Imported>,
+    now: Timestamp,
+) -> Result<Vec<(TodoRef, bool)>> {
+    let mut next_ids: HashMap<String, u32> = HashMap::new();
+    let mut refs: HashMap<String, (TodoRef, bool)> = HashMap::new();
+    for imp in &imported {
+        let existing = _existing(store, list, imp);
+        let reused = imp.existing.as_ref().filter(|r| {
+            existing.is_none() && store.todo_by_ref(list, r).is_some()
+        });
+        if let Some(other) = reused {
+            warn!(target: "format::insert", "[{}] is not {other} of the store", imp.key);
+            eprintln!(
+                "Warning: {other} was created at another time than [{}], it is added as a new todo",
+                imp.key
+            );
+        }
+        let todo_ref = match &existing {
+            Some(existing) => existing.clone().resolve(list),
+            None => {
+                let target = imp.list.as_deref().unwrap_or(list).to_owned();
+                if !store.lists().contains_key(&target) {
+                    bail!(
+                        "list [{target}] of [{}] doesn't exist, add it first or import into another list with --list",
+                        imp.todo.desc
+                    );
+                }
+                let id = match next_ids.get(&target) {
+                    Some(id) => *id,
+                    None => store.generate_id(&target)?,
+                };
+                next_ids.insert(target.clone(), id + 1);
+                TodoRef {
+                    list: Some(target),
+                    id,
+                }
+            }
+        };
+        if refs
+            .insert(imp.key.clone(), (todo_ref, existing.is_some()))
+            .is_some()
+        {
+            bail!("todo [{}] is found twice", imp.key);
+        }
+    }
+
+    let mut inserted = Vec::new();
+    let mut parents = Vec::new();
+    for mut imp in imported {
+        let (todo_ref, updated) = refs[&imp.key].clone();
+        let target = todo_ref.list_or(list).to_owned();
+        let parent = imp.parent.as_ref().and_then(|key| {
+            let pa_ref = match refs.get(key) {
+                Some((r, _)) => Some(r.clone()),
+                None => _stored(store, &target, key),
+            }
+            .filter(|r| r.list == todo_ref.list);
+            if pa_ref.is_none() {
+                warn!(target: "format::insert", "parent [{key}] of [{}] is not in list [{target}]", imp.key);
+            }
+            pa_ref.map(|r| r.id)
+        });
+        let depends_on: Vec<_> = imp
+            .depends_on
+            .iter()
+            .filter_map(|key| match refs.get(key) {
+                Some((r, _)) => Some(r.clone()),
+                None => key
+                    .parse::<TodoRef>()
+                    .map(|r| r.resolve(&target))
+                    .inspect_err(|err| warn!(target: "format::insert", "blocker skipped: {err}"))
+                    .ok(),
+            })
+            .collect();
+        imp.todo.depends_on = Some(depends_on).filter(|d| !d.is_empty());
+        debug!(target: "format::insert", "[{}] -> {todo_ref} (updated: {updated})", imp.key);
+
+        let todos = store.todos_mut(&target)?;
+        if updated {
+            let todo = todos.get_mut(&todo_ref.id).unwrap();
+            _update(todo, imp.todo, imp.status, now);
+        } else {
+            imp.todo.parent = None;
+            imp.todo.children = None;
+            todos.insert(todo_ref.id, imp.todo);
+        }
+        parents.push((target, todo_ref.id, parent));
+        inserted.push((todo_ref, updated));
+    }
+
+    // todos are linked once all of them are in, children in the order of
+    // the file
+    for (list, id, parent) in parents {
+        let Some(pa_id) = parent else {
+            continue;
+        };
+        let todos = store.todos_mut(&list)?;
+        let old_pa_id = todos[&id].parent;
+        if old_pa_id != Some(pa_id) {
+            if pa_id == id || todos.ancestors(&pa_id).contains(&id) {
+                warn!(target: "format::insert", "todo [{id}] can't be moved into its own subtree");
+                continue;
+            }
+            if let Some(old_pa) =
+                old_pa_id.and_then(|old_pa_id| todos.get_mut(&old_pa_id))
+            {
+                old_pa
+                    .children
+                    .iter_mut()
+                    .for_each(|ch| ch.retain(|ch_id| *ch_id != id));
+                if old_pa.children.as_ref().is_some_and(|ch| ch.is_empty()) {
+                    old_pa.children = None;
+                }
+            }
+            todos.get_mut(&id).unwrap().parent = Some(pa_id);
+        }
+        let pa = todos.get_mut(&pa_id).unwrap();
+        let children = pa.children.get_or_insert_with(Vec::new);
+        if !children.contains(&id) {
+            children.push(id);
+        }
+        if let Some(old_pa_id) = old_pa_id.filter(|old| *old != pa_id) {
+            let rolled = todos.roll_up(old_pa_id, now);
+            debug!(target: "format::insert", "roll up todo [{rolled:?}]");
+        }
+    }
+
+    // parents follow the status of the imported children
+    for (todo_ref, _) in &inserted {
+        let todos = store.todos_mut(&todo_ref.list_or(list).to_owned())?;
+        let rolled = todos.roll_up(todo_ref.id, now);
+        debug!(target: "format::insert", "roll up todo [{rolled:?}]");
+    }
+    Ok(inserted)
+}
+
+/// Checks that the parent of each `imported` todo is either read from the
+/// same file or already in the store, in the list the todo goes to.
+pub fn check_parents(
+    store: &Store,
+    list: &str,
+    imported: &[Imported],
+) -> Result<()> {
+    let keys: HashMap<_, _> =
+        imported.iter().map(|imp| (&imp.key, imp)).collect();
+    let target_of = |imp: &Imported| -> String {
+        match _existing(store, list, imp).as_ref() {
+            Some(existing) => existing.list_or(list).to_owned(),
+            None => imp.list.as_deref().unwrap_or(list).to_owned(),
+        }
+    };
+    let mut errors = Vec::new();
+    for imp in imported {
+        let Some(key) = &imp.parent else {
+            continue;
+        };
+        let target = target_of(imp);
+        let pa_list = match keys.get(key) {
+            Some(pa) => Some(target_of(pa)),
+            None => _stored(store, &target, key).and_then(|r| r.list),
+        };
+        match pa_list {
+            Some(pa_list) if pa_list == target => (),
+            Some(pa_list) => errors.push(format!(
+                "{}: parent [{key}] is in list [{pa_list}], not in [{target}]",
+                imp.key
+            )),
+            None => errors.push(format!(
+                "{}: parent [{key}] is neither in the file nor in list [{target}]",
+                imp.key
+            )),
+        }
+    }
+    if !errors.is_empty() {
+        bail!("{}", errors.join("\n"));
+    }
+    Ok(())
+}
+
+/// The `existing` todo of `imp` if it is still in the store, and was
+/// created at the time the file gives. Times are compared to the minute,
+/// or to the day when the file gives the start of a day, as todo.txt only
+/// keeps the date. Otherwise the todo of the target list with the `uid` of
+/// `imp`, if any.
+fn _existing(store: &Store, list: &str, imp: &Imported) -> Option<TodoRef> {
+    let by_uid = || {
+        let uid = imp.todo.uid.as_ref()?;
+        let target = imp.list.as_deref().unwrap_or(list);
+        let todos = store.lists().get(target)?;
+        let id = todos
+            .iter()
+            .filter(|(_, todo)| todo.uid.as_ref() == Some(uid))
+            .map(|(id, _)| *id)
+            .min()?;
+        Some(TodoRef {
+            list: Some(target.to_owned()),
+            id,
+        })
+    };
+    let existing = imp.existing.as_ref().filter(|r| {
+        store.todo_by_ref(list, r).is_some_and(|todo| {
+            imp.create_at.is_none_or(|at| {
+                let create_at = todo.create_at().with_timezone(at.offset());
+                if at.time() == NaiveTime::MIN {
+                    create_at.date_naive() == at.date_naive()
+                } else {
+                    create_at.timestamp().div_euclid(60)
+                        == at.timestamp().div_euclid(60)
+                }
+            })
+        })
+    });
+    existing.cloned().or_else(by_uid)
+}
+
+/// Creation time of an imported todo: the one `given` by the file, or `now`
+/// if it gives none. A done todo wasn't created after its completion.
+fn create_at(
+    given: Option<Timestamp>,
+    complete_at: Option<Timestamp>,
+    now: Timestamp,
+) -> Timestamp {
+    given.unwrap_or_else(|| complete_at.map_or(now, |at| at.min(now)))
+}
+
+/// Reads `key` as a reference to a todo of the store, relative to `list`.
+fn _stored(store: &Store, list: &str, key: &str) -> Option<TodoRef> {
+    key.parse::<TodoRef>()
+        .ok()
+        .map(|r| r.resolve(list))
+        .filter(|r| store.todo_by_ref(list, r).is_some())
+}
+
+/// Updates `todo` with the fields of `imported` a file can set: the
+/// description always, the status and the others when they are given.
+fn _update(
+    todo: &mut Todo,
+    imported: Todo,
+    status: Option<Status>,
+    now: Timestamp,
+) {
+    todo.desc = imported.desc.clone();
+    if let Some(status) = status.filter(|status| *status != todo.status()) {
+        // only undo can reopen a todo
+        if todo.status() == Status::Done {
+            todo.set_status(Status::Todo, now);
+        }
+        todo.set_status(status, imported.complete_at().unwrap_or(now));
+    }
+    if imported.link.is_some() {
+        todo.link = imported.link;
+    }
+    if imported.assign_at.is_some() {
+        todo.assign_at = imported.assign_at;
+    }
+    if imported.recur.is_some() {
+        todo.recur = imported.recur;
+    }
+    if imported.tags.is_some() {
+        todo.tags = imported.tags;
+    }
+    if imported.priority.is_some() {
+        todo.priority = imported.priority;
+    }
+    if imported.estimate.is_some() {
+        todo.estimate = imported.estimate;
+    }
+    if imported.notes.is_some() {
+        todo.notes = imported.notes;
+    }
+    if imported.depends_on.is_some() {
+        todo.depends_on = imported.depends_on;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::Tz;
+    use chrono::{DateTime, FixedOffset};
+
+    /// Time zone the format tests read and write dates in.
+    pub fn tz() -> Tz {
+        Tz::Fixed(FixedOffset::east_opt(0).unwrap())
+    }
+
+    /// Inserts `imported` into a new store of list `dev` and exports all of
+    /// its todos again with `export`.
+    pub fn insert_and_export<T>(
+        imported: Vec<Imported>,
+        export: impl Fn(&[ExportedList]) -> T,
+    ) -> (Store, T) {
+        let list = "dev".to_owned();
+        let mut store = Store::create(list.clone(), Vec::new());
+        insert(&mut store, &list, imported, tz().now()).unwrap();
+        let todos = store.todos(&list).unwrap();
+        let mut ids: Vec<_> = todos.keys().copied().collect();
+        ids.sort();
+        let exported = ExportedList {
+            list: &list,
+            todos,
+            ids,
+        };
+        let output = export(&[exported]);
+        (store, output)
+    }
+
+    #[test]
+    fn reused_id() {
+        let list = "default".to_owned();
+        let mut store = Store::create(list.clone(), Vec::new());
+        let created =
+            DateTime::parse_from_rfc3339("2025-06-01T09:00:00Z").unwrap();
+        let todo =
+            Todo::new("Other".to_owned(), None, None, None, created, None);
+        store.todos_mut(&list).unwrap().insert(1, todo);
+
+        let input = "- [x] Old ✅ 2025-05-20 <!-- taso:default#1 2025-05-10T09:00:00+00:00 -->\n\
+                     - [/] Same <!-- taso:default#1 2025-06-01T09:00:30+00:00 -->\n";
+        let imported = markdown::import(input, tz()).unwrap();
+        let inserted = insert(&mut store, &list, imported, tz().now()).unwrap();
+        assert_eq!(
+            inserted,
+            [
+                ("default#2".parse().unwrap(), false),
+                ("default#1".parse().unwrap(), true),
+            ]
+        );
+        let todos = store.todos(&list).unwrap();
+        assert_eq!(todos[&1].desc, "Same");
+        assert_eq!(todos[&2].desc, "Old");
+        assert_eq!(todos[&2].status(), Status::Done);
+    }
+
+    #[test]
+    fn foreign_uid() {
+        let list = "default".to_owned();
+        let mut store = Store::create(list.clone(), Vec::new());
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:abc\r\n\
+                     SUMMARY:Plan\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        for desc in ["Plan", "Plan again"] {
+            let input = input.replace("Plan", desc);
+            let (imported, _) = ical::import(&input, tz()).unwrap();
+            insert(&mut store, &list, imported, tz().now()).unwrap();
+        }
+        let todos = store.todos(&list).unwrap();
+        assert_eq!(todos.len(), 1);
+        assert_eq!(todos[&1].desc, "Plan again");
+        assert_eq!(todos[&1].uid.as_deref(), Some("abc"));
+    }
+}
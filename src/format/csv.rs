@@ -0,0 +1,491 @@
+//! Comma-separated values, for spreadsheets.
+//!
+//! The first row names the columns, then a todo is written per row in the
+//! order of the tree: `list`, `id`, `parent`, `depth`, `status`, `desc`,
+//! `link`, `created`, `completed`, `assign`, `recur`, `estimate`, `spent`,
+//! `priority`, `tags` and `depends_on`. Fields are quoted as in RFC 4180,
+//! tags and blockers are separated by commas within their field.
+//!
+//! Only `desc` is needed for import, and the columns may come in any order.
+//! A row updates the todo its `list` and `id` name if it exists, `parent`
+//! is the id of a todo of the same list, in the file or in the store.
+//! `depth` and `spent` are only written, and empty fields leave the todo as
+//! it is.
+
+use crate::{
+    date::Tz,
+    format::{ExportedList, Imported, Report},
+    model::{Status, Timestamp, Todo, TodoRef},
+};
+use chrono::{DateTime, NaiveDate, SecondsFormat};
+use log::debug;
+use std::collections::HashMap;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const COLUMNS: [&str; 16] = [
+    "list",
+    "id",
+    "parent",
+    "depth",
+    "status",
+    "desc",
+    "link",
+    "created",
+    "completed",
+    "assign",
+    "recur",
+    "estimate",
+    "spent",
+    "priority",
+    "tags",
+    "depends_on",
+];
+
+/// Writes the todos of `lists`, parents before their children. Reports the
+/// notes, which have no column.
+pub fn export(lists: &[ExportedList]) -> (String, Report) {
+    let mut output = _row(COLUMNS.iter().map(|c| c.to_string()));
+    let mut report = Report::new();
+    for exported in lists {
+        let roots = exported.ids.iter().filter(|id| {
+            exported.todos[id]
+                .parent
+                .is_none_or(|pa_id| !exported.ids.contains(&pa_id))
+        });
+        for id in roots {
+            _todo_rows(exported, *id, &mut output, &mut report);
+        }
+    }
+    (output, report)
+}
+
+fn _todo_rows(
+    exported: &ExportedList,
+    id: u32,
+    output: &mut String,
+    report: &mut Report,
+) {
+    let todo = &exported.todos[&id];
+    let todo_ref = TodoRef {
+        list: Some(exported.list.to_owned()),
+        id,
+    };
+    if todo.notes.is_some() {
+        report
+            .entry("notes".to_owned())
+            .or_default()
+            .push(todo_ref.to_string());
+    }
+    let timestamp = |timestamp: Timestamp| {
+        timestamp.to_rfc3339_opts(SecondsFormat::Secs, false)
+    };
+    let joined = |values: Vec<String>| values.join(",");
+    let spent = todo.spent();
+    output.push_str(&_row([
+        exported.list.to_owned(),
+        id.to_string(),
+        todo.parent
+            .map_or_else(String::new, |pa_id| pa_id.to_string()),
+        exported.todos.ancestors(&id).len().to_string(),
+        todo.status().to_string(),
+        todo.desc.clone(),
+        todo.link.clone().unwrap_or_default(),
+        timestamp(todo.create_at()),
+        todo.complete_at().map(timestamp).unwrap_or_default(),
+        todo.assign_at
+            .map(|d| d.format(DATE_FORMAT).to_string())
+            .unwrap_or_default(),
+        todo.recur
+            .as_ref()
+            .map(|r| r.to_string())
+            .unwrap_or_default(),
+        todo.estimate.map(|e| e.to_string()).unwrap_or_default(),
+        if spent.is_zero() {
+            String::new()
+        } else {
+            spent.to_string()
+        },
+        todo.priority.map(|p| p.to_string()).unwrap_or_default(),
+        joined(todo.tags.clone().unwrap_or_default()),
+        joined(
+            todo.depends_on
+                .iter()
+                .flatten()
+                .map(|r| r.to_string())
+                .collect(),
+        ),
+    ]));
+    for ch_id in todo.children.iter().flatten() {
+        if exported.ids.contains(ch_id) {
+            _todo_rows(exported, *ch_id, output, report);
+        }
+    }
+}
+
+/// Writes `fields` as a row, quoting those which need it.
+fn _row(fields: impl IntoIterator<Item = String>) -> String {
+    let fields: Vec<_> = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect();
+    format!("{}\n", fields.join(","))
+}
+
+/// Splits `input` into rows of fields, each with the line it starts on.
+/// Empty lines are skipped.
+fn _records(input: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = input.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    quoted = false;
+                }
+            }
+            '"' if field.is_empty() && !was_quoted => {
+                quoted = true;
+                was_quoted = true;
+            }
+            '"' => return Err(format!("line {line}: stray quote in a field")),
+            '\n' if quoted => {
+                field.push(c);
+                line += 1;
+            }
+            _ if quoted => field.push(c),
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                was_quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                if fields.len() > 1 || !fields[0].is_empty() || was_quoted {
+                    records.push((start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                was_quoted = false;
+                line += 1;
+                start = line;
+            }
+            _ if was_quoted => {
+                return Err(format!("line {line}: text after a quoted field"));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("line {start}: unterminated quoted field"));
+    }
+    if !field.is_empty() || !fields.is_empty() || was_quoted {
+        fields.push(field);
+        records.push((start, fields));
+    }
+    Ok(records)
+}
+
+/// Reads the rows of CSV `input`, keyed by their line number. Columns which
+/// aren't known are reported, errors of all the rows are returned at once.
+pub fn import(input: &str, tz: Tz) -> Result<(Vec<Imported>, Report), String> {
+    let records = _records(input)?;
+    let Some(((header_line, header), records)) = records.split_first() else {
+        return Ok((Vec::new(), Report::new()));
+    };
+    let header: Vec<_> = header
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    if !header.iter().any(|name| name == "desc") {
+        return Err(format!("line {header_line}: missing column [desc]"));
+    }
+    let mut report = Report::new();
+    for name in header
+        .iter()
+        .filter(|name| !COLUMNS.contains(&name.as_str()))
+    {
+        report
+            .entry(format!("column [{name}]"))
+            .or_default()
+            .push(format!("line {header_line}"));
+    }
+
+    let rows: Vec<(usize, HashMap<&str, &str>)> = records
+        .iter()
+        .map(|(n, fields)| {
+            let row = header
+                .iter()
+                .zip(fields)
+                .map(|(name, field)| (name.as_str(), field.trim()))
+                .filter(|(_, field)| !field.is_empty())
+                .collect();
+            (*n, row)
+        })
+        .collect();
+    let mut errors = Vec::new();
+    // line of the row of each todo, to link parents and blockers
+    let mut lines: HashMap<(Option<&str>, u32), usize> = HashMap::new();
+    for ((n, fields), (_, row)) in records.iter().zip(&rows) {
+        if fields.len() > header.len() {
+            errors.push((
+                *n,
+                format!("{} fields for {} columns", fields.len(), header.len()),
+            ));
+        }
+        let Some(id) = row.get("id") else {
+            continue;
+        };
+        match id.parse::<u32>() {
+            Ok(id) => {
+                let list = row.get("list").copied();
+                if let Some(first) = lines.insert((list, id), *n) {
+                    errors.push((
+                        *n,
+                        format!("todo [{id}] is already on line {first}"),
+                    ));
+                }
+            }
+            Err(err) => errors.push((*n, format!("invalid id [{id}]: {err}"))),
+        }
+    }
+
+    let mut imported = Vec::new();
+    for (n, row) in &rows {
+        match _parse_row(row, *n, &lines, tz) {
+            Ok(imp) => {
+                debug!(target: "format::csv", "line {n}: {imp:?}");
+                imported.push(imp);
+            }
+            Err(err) => errors.push((*n, err)),
+        }
+    }
+    if !errors.is_empty() {
+        errors.sort_by_key(|(n, _)| *n);
+        let errors: Vec<_> = errors
+            .into_iter()
+            .map(|(n, err)| format!("line {n}: {err}"))
+            .collect();
+        return Err(errors.join("\n"));
+    }
+    Ok((imported, report))
+}
+
+fn _parse_row(
+    row: &HashMap<&str, &str>,
+    n: usize,
+    lines: &HashMap<(Option<&str>, u32), usize>,
+    tz: Tz,
+) -> Result<Imported, String> {
+    let list = row.get("list").copied();
+    let invalid = |name: &str, err: &dyn std::fmt::Display| {
+        format!("invalid {name} [{}]: {err}", row[name])
+    };
+    let field = |name: &str| row.get(name).copied();
+    let id = field("id").map(|id| id.parse::<u32>().unwrap_or_default());
+    // a todo of the file by its line, a todo of the store by its reference
+    let key = |todo_ref: TodoRef| {
+        let list = todo_ref.list.as_deref().or(list);
+        match lines.get(&(list, todo_ref.id)) {
+            Some(line) => format!("line {line}"),
+            None => todo_ref.to_string(),
+        }
+    };
+
+    let Some(desc) = field("desc") else {
+        return Err("missing description".to_owned());
+    };
+    let parent = field("parent")
+        .map(|pa_id| {
+            pa_id
+                .parse::<u32>()
+                .map(|id| key(TodoRef { list: None, id }))
+                .map_err(|err| invalid("parent", &err))
+        })
+        .transpose()?;
+    let depends_on = field("depends_on")
+        .into_iter()
+        .flat_map(|refs| refs.split(','))
+        .map(|r| r.trim().parse::<TodoRef>().map(key))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| invalid("depends_on", &err))?;
+
+    let timestamp = |name: &str| {
+        field(name)
+            .map(|value| {
+                if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+                    return Ok(timestamp);
+                }
+                let date = NaiveDate::parse_from_str(value, DATE_FORMAT)
+                    .map_err(|err| invalid(name, &err))?;
+                tz.start_of_day(date)
+                    .ok_or_else(|| format!("invalid date [{date}] in {tz:?}"))
+            })
+            .transpose()
+    };
+    let now = tz.now();
+    let create_at = timestamp("created")?;
+    let complete_at = timestamp("completed")?;
+    let status = field("status")
+        .map(|status| {
+            <Status as clap::ValueEnum>::from_str(status, true)
+                .map_err(|err| invalid("status", &err))
+        })
+        .transpose()?;
+    // an empty status leaves an updated todo as it is
+    let given = status.or(complete_at.map(|_| Status::Done));
+    let status = given.unwrap_or(Status::Todo);
+    let complete_at = match status {
+        Status::Done => Some(complete_at.unwrap_or(now)),
+        _ => None,
+    };
+
+    let mut todo = Todo::new(
+        desc.to_owned(),
+        field("link").map(str::to_owned),
+        None,
+        None,
+        super::create_at(create_at, complete_at, now),
+        complete_at,
+    );
+    if complete_at.is_none() {
+        todo.set_status(status, now);
+    }
+    todo.assign_at = field("assign")
+        .map(|date| {
+            NaiveDate::parse_from_str(date, DATE_FORMAT)
+                .map_err(|err| invalid("assign", &err))
+        })
+        .transpose()?;
+    todo.recur = field("recur").map(str::parse).transpose()?;
+    todo.estimate = field("estimate").map(str::parse).transpose()?;
+    todo.priority = field("priority").map(str::parse).transpose()?;
+    todo.tags = field("tags").map(|tags| {
+        tags.split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_owned)
+            .collect()
+    });
+
+    let existing = id.map(|id| TodoRef {
+        list: list.map(str::to_owned),
+        id,
+    });
+    Ok(Imported {
+        key: format!("line {n}"),
+        existing,
+        create_at,
+        list: list.map(str::to_owned),
+        parent,
+        depends_on,
+        status: given,
+        todo,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::tests::{insert_and_export, tz};
+
+    #[test]
+    fn split_records() {
+        let input = "\u{feff}a,\"b,\"\"c\"\"\"\r\n\n\"multi\nline\",\r\nlast";
+        assert_eq!(
+            _records(input).unwrap(),
+            [
+                (1, vec!["a".to_owned(), "b,\"c\"".to_owned()]),
+                (3, vec!["multi\nline".to_owned(), String::new()]),
+                (5, vec!["last".to_owned()]),
+            ]
+        );
+        assert!(_records("a,b\"c").is_err());
+        assert!(_records("\"a\"b").is_err());
+        assert!(_records("a\n\"b").is_err());
+    }
+
+    #[test]
+    fn parse_rows() {
+        let input = "ID,Desc,Parent,Status,Tags,Depends_On,Owner\n\
+                     3,Plan,,,\"ui, ux\",,me\n\
+                     4,Ship,3,progress,,\"3,work#2\",\n\
+                     ,Review,9,,,,\n";
+        let (imported, report) = import(input, tz()).unwrap();
+        assert_eq!(report["column [owner]"], ["line 1"]);
+        let summary: Vec<_> = imported
+            .iter()
+            .map(|imp| {
+                (
+                    imp.key.as_str(),
+                    imp.parent.as_deref(),
+                    imp.todo.desc.as_str(),
+                    imp.todo.status(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("line 2", None, "Plan", Status::Todo),
+                ("line 3", Some("line 2"), "Ship", Status::Progress),
+                ("line 4", Some("#9"), "Review", Status::Todo),
+            ]
+        );
+        assert_eq!(imported[0].existing, "#3".parse().ok());
+        assert_eq!(
+            imported[0].todo.tags,
+            Some(vec!["ui".to_owned(), "ux".to_owned()])
+        );
+        assert_eq!(imported[1].depends_on, ["line 2", "work#2"]);
+        assert_eq!(imported[2].existing, None);
+        assert_eq!(imported[0].status, None);
+        assert_eq!(imported[1].status, Some(Status::Progress));
+    }
+
+    #[test]
+    fn row_errors() {
+        let input = "id,desc,status,assign\n\
+                     1,Plan,,\n\
+                     1,Ship,,\n\
+                     x,Test,,\n\
+                     ,,,\n\
+                     2,Fix,later,\n\
+                     3,Deploy,,friday,extra\n";
+        let err = import(input, tz()).unwrap_err();
+        let lines: Vec<_> = err
+            .lines()
+            .map(|line| line.split(':').next().unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            ["line 3", "line 4", "line 5", "line 6", "line 7", "line 7"]
+        );
+        assert!(import("id,title\n1,Plan\n", tz()).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = "list,id,parent,depth,status,desc,link,created,completed,assign,recur,estimate,spent,priority,tags,depends_on\n\
+                     dev,1,,0,todo,\"Plan, then \"\"ship\"\"\",http://p,2025-06-01T09:00:00+00:00,,2025-06-03,,2h,,A,ui,\n\
+                     dev,2,1,1,done,Ship,,2025-06-01T09:00:00+00:00,2025-06-02T10:30:00+00:00,,,,,,,dev#1\n\
+                     dev,3,1,1,todo,Party,,2025-06-01T09:00:00+00:00,,,,,,,,\n";
+        let (imported, _) = import(input, tz()).unwrap();
+        let (_, (output, report)) = insert_and_export(imported, export);
+        assert_eq!(output, input);
+        assert!(report.is_empty());
+    }
+}
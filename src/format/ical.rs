@@ -0,0 +1,686 @@
+//! iCalendar (RFC 5545) to-dos, `VTODO` components of a `VCALENDAR`.
+//!
+//! A todo exported from taso gets the UID `ID.LIST@taso`, so that calendar
+//! apps update it on a later export and taso updates it on import. Parents
+//! are `RELATED-TO` and blockers `RELATED-TO;RELTYPE=DEPENDS-ON` (RFC 9253)
+//! the UID of the other todo. Fields without a place in the format are `X-`
+//! properties. Other UIDs are kept on the imported todo, so that importing
+//! the same to-do again updates it.
+
+use crate::{
+    date::Tz,
+    format::{ExportedList, Imported, Report},
+    model::{
+        Priority, Status, Timestamp, Todo, TodoRef,
+        recur::{MAX_EVERY, Recur, RecurUnit},
+    },
+};
+use chrono::{NaiveDate, NaiveDateTime, Utc, Weekday};
+use log::debug;
+use std::collections::HashSet;
+
+const UID_DOMAIN: &str = "@taso";
+const DATE_FORMAT: &str = "%Y%m%d";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+/// Lines are folded at 75 octets.
+const LINE_LIMIT: usize = 75;
+/// taso statuses which have no iCalendar equivalent.
+const X_STATUS: &str = "X-TASO-STATUS";
+/// Recurrence rules after completion, which RRULE can't express.
+const X_RECUR: &str = "X-TASO-RECUR";
+
+/// Returns the UID of todo `id` of `list`.
+pub fn uid(list: &str, id: u32) -> String {
+    format!("{id}.{list}{UID_DOMAIN}")
+}
+
+/// Returns the todo of the UID given by [`uid`], `None` for other UIDs.
+fn _todo_ref(uid: &str) -> Option<TodoRef> {
+    let (id, list) = uid.strip_suffix(UID_DOMAIN)?.split_once('.')?;
+    Some(TodoRef {
+        list: Some(list.to_owned()),
+        id: id.parse().ok()?,
+    })
+}
+
+/// Writes the todos of `lists` as a calendar of to-dos.
+pub fn export(lists: &[ExportedList], tz: Tz) -> String {
+    let stamp = _date_time(&tz.now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:-//taso//taso {}//EN", env!("CARGO_PKG_VERSION")),
+    ];
+    for exported in lists {
+        for id in &exported.ids {
+            let todo = &exported.todos[id];
+            lines.extend(_vtodo(exported.list, *id, todo, &stamp));
+        }
+    }
+    lines.push("END:VCALENDAR".to_owned());
+    lines.iter().map(|line| _fold(line)).collect()
+}
+
+fn _vtodo(list: &str, id: u32, todo: &Todo, stamp: &str) -> Vec<String> {
+    let mut lines = vec![
+        "BEGIN:VTODO".to_owned(),
+        format!("UID:{}", uid(list, id)),
+        format!("DTSTAMP:{stamp}"),
+        format!("CREATED:{}", _date_time(&todo.create_at())),
+        format!("SUMMARY:{}", _escape(&todo.desc)),
+    ];
+    lines.extend(todo.link.as_ref().map(|link| format!("URL:{link}")));
+    let status = todo.status();
+    lines.push(format!(
+        "STATUS:{}",
+        match status {
+            Status::Todo | Status::Waiting => "NEEDS-ACTION",
+            Status::Progress => "IN-PROCESS",
+            Status::Cancelled => "CANCELLED",
+            Status::Done => "COMPLETED",
+        }
+    ));
+    if status == Status::Waiting {
+        lines.push(format!("{X_STATUS}:{status}"));
+    }
+    if let Some(complete_at) = todo.complete_at() {
+        lines.push(format!("COMPLETED:{}", _date_time(&complete_at)));
+    }
+    lines
+        .extend(todo.assign_at.map(|date| {
+            format!("DUE;VALUE=DATE:{}", date.format(DATE_FORMAT))
+        }));
+    lines.extend(
+        todo.priority
+            .map(|p| format!("PRIORITY:{}", _ical_priority(p))),
+    );
+    if let Some(tags) = &todo.tags {
+        let tags: Vec<_> = tags.iter().map(|tag| _escape(tag)).collect();
+        lines.push(format!("CATEGORIES:{}", tags.join(",")));
+    }
+    match &todo.recur {
+        Some(recur) if recur.after_completion => {
+            lines.push(format!("{X_RECUR}:{}", _escape(&recur.to_string())));
+        }
+        Some(recur) => lines.push(format!("RRULE:{}", _rrule(recur))),
+        None => {}
+    }
+    lines.extend(
+        todo.parent
+            .map(|pa_id| format!("RELATED-TO:{}", uid(list, pa_id))),
+    );
+    for blocker in todo.depends_on.iter().flatten() {
+        lines.push(format!(
+            "RELATED-TO;RELTYPE=DEPENDS-ON:{}",
+            uid(blocker.list_or(list), blocker.id)
+        ));
+    }
+    lines.push("END:VTODO".to_owned());
+    lines
+}
+
+fn _date_time(timestamp: &Timestamp) -> String {
+    timestamp
+        .with_timezone(&Utc)
+        .format(&format!("{DATE_TIME_FORMAT}Z"))
+        .to_string()
+}
+
+/// Maps `A` to `I` to the priorities 1 (highest) to 9 of iCalendar, lower
+/// priorities are 9 as well.
+fn _ical_priority(priority: Priority) -> u32 {
+    let letter = priority.to_string().chars().next().unwrap_or('A');
+    (u32::from(letter) - u32::from('A') + 1).min(9)
+}
+
+fn _rrule(recur: &Recur) -> String {
+    let freq = match recur.unit {
+        RecurUnit::Day => "DAILY",
+        RecurUnit::Week => "WEEKLY",
+        RecurUnit::Month => "MONTHLY",
+        RecurUnit::Year => "YEARLY",
+    };
+    let mut rule = format!("FREQ={freq}");
+    if recur.every > 1 {
+        rule.push_str(&format!(";INTERVAL={}", recur.every));
+    }
+    if let Some(weekdays) = &recur.weekdays {
+        let days: Vec<_> = weekdays
+            .iter()
+            .map(|w| w.to_string()[..2].to_uppercase())
+            .collect();
+        rule.push_str(&format!(";BYDAY={}", days.join(",")));
+    }
+    rule
+}
+
+/// Escapes the characters of a TEXT value.
+fn _escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn _unescape(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => unescaped.push('\n'),
+                Some(c) => unescaped.push(c),
+                None => {}
+            },
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Splits a list of TEXT values at the commas which are not escaped.
+fn _split_texts(value: &str) -> Vec<String> {
+    let mut texts = Vec::new();
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                text.push(c);
+                text.extend(chars.next());
+            }
+            ',' => texts.push(_unescape(&std::mem::take(&mut text))),
+            c => text.push(c),
+        }
+    }
+    texts.push(_unescape(&text));
+    texts.retain(|text| !text.is_empty());
+    texts
+}
+
+/// Folds `line` into lines of at most 75 octets, ended by CRLF.
+fn _fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > LINE_LIMIT {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+/// A content line, `NAME;PARAM=VALUE:VALUE`.
+#[derive(Debug)]
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Reads the `VTODO` components of iCalendar `input`, other components are
+/// skipped, along with the report of the parts of recurrence rules which
+/// couldn't be read. To-dos are keyed by their UID.
+pub fn import(input: &str, tz: Tz) -> Result<(Vec<Imported>, Report), String> {
+    // (line number, unfolded line)
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((n + 1, line.to_owned())),
+        }
+    }
+
+    let mut report = Report::new();
+    let mut imported = Vec::new();
+    // components the current line is in, and the properties of the to-do
+    let mut components: Vec<String> = Vec::new();
+    let mut vtodo: Option<(usize, Vec<Property>)> = None;
+    for (n, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let prop =
+            _parse_property(&line).map_err(|err| format!("line {n}: {err}"))?;
+        match prop.name.as_str() {
+            "BEGIN" => {
+                let name = prop.value.to_uppercase();
+                if name == "VTODO"
+                    && components.last().is_some_and(|c| c == "VCALENDAR")
+                {
+                    vtodo = Some((n, Vec::new()));
+                }
+                components.push(name);
+            }
+            "END" => {
+                let ended = components.pop();
+                let Some((start, props)) =
+                    vtodo.take_if(|_| ended.as_deref() == Some("VTODO"))
+                else {
+                    continue;
+                };
+                let name = props
+                    .iter()
+                    .find(|prop| prop.name == "SUMMARY")
+                    .map_or_else(
+                        || format!("to-do at line {start}"),
+                        |prop| format!("[{}]", _unescape(&prop.value)),
+                    );
+                let mut unmapped = |field: &str| {
+                    report
+                        .entry(field.to_owned())
+                        .or_default()
+                        .push(name.clone());
+                };
+                let imp = _vtodo_to_imported(props, tz, &mut unmapped)
+                    .map_err(|err| format!("to-do at line {start}: {err}"))?;
+                debug!(target: "format::ical", "line {start}: {imp:?}");
+                imported.push(imp);
+            }
+            _ if components.last().is_some_and(|c| c == "VTODO") => {
+                if let Some((_, props)) = vtodo.as_mut() {
+                    props.push(prop);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // parents and blockers out of the file may still be todos of taso
+    let keys: HashSet<_> = imported.iter().map(|imp| imp.key.clone()).collect();
+    for imp in imported.iter_mut() {
+        for key in imp.parent.iter_mut().chain(imp.depends_on.iter_mut()) {
+            if let Some(todo_ref) =
+                _todo_ref(key).filter(|_| !keys.contains(key))
+            {
+                *key = todo_ref.to_string();
+            }
+        }
+    }
+    Ok((imported, report))
+}
+
+fn _parse_property(line: &str) -> Result<Property, String> {
+    // the value starts at the first colon out of quoted parameter values
+    let mut quoted = false;
+    let colon = line
+        .char_indices()
+        .find(|(_, c)| {
+            if *c == '"' {
+                quoted = !quoted;
+            }
+            *c == ':' && !quoted
+        })
+        .map(|(i, _)| i)
+        .ok_or_else(|| format!("invalid content line [{line}]"))?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next().unwrap_or_default().trim().to_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(param, value)| {
+            (
+                param.trim().to_uppercase(),
+                value.trim_matches('"').to_owned(),
+            )
+        })
+        .collect();
+    Ok(Property {
+        name,
+        params,
+        value: value.to_owned(),
+    })
+}
+
+fn _vtodo_to_imported(
+    props: Vec<Property>,
+    tz: Tz,
+    unmapped: &mut impl FnMut(&str),
+) -> Result<Imported, String> {
+    let mut key = None;
+    let mut desc = None;
+    let mut link = None;
+    let mut create_at = None;
+    let mut complete_at = None;
+    let mut status = Status::Todo;
+    let mut assign_at = None;
+    let mut priority = None;
+    let mut tags = Vec::new();
+    let mut recur = None;
+    let mut parent = None;
+    let mut depends_on = Vec::new();
+    for prop in &props {
+        let invalid = |err: &dyn std::fmt::Display| {
+            format!("invalid {} [{}]: {err}", prop.name, prop.value)
+        };
+        match prop.name.as_str() {
+            "UID" => key = Some(prop.value.clone()),
+            "SUMMARY" => desc = Some(_unescape(&prop.value)),
+            "URL" => link = Some(prop.value.clone()),
+            "CREATED" => create_at = Some(_parse_date_time(prop, tz)?),
+            "COMPLETED" => complete_at = Some(_parse_date_time(prop, tz)?),
+            "STATUS" => {
+                status = match prop.value.to_uppercase().as_str() {
+                    "NEEDS-ACTION" => Status::Todo,
+                    "IN-PROCESS" => Status::Progress,
+                    "CANCELLED" => Status::Cancelled,
+                    "COMPLETED" => Status::Done,
+                    _ => return Err(invalid(&"unknown status")),
+                }
+            }
+            X_STATUS => {
+                status =
+                    <Status as clap::ValueEnum>::from_str(&prop.value, true)
+                        .map_err(|err| invalid(&err))?
+            }
+            "DUE" => {
+                let due = _parse_date_time(prop, tz)?;
+                assign_at = Some(tz.date(&due));
+            }
+            "PRIORITY" => {
+                let value = prop
+                    .value
+                    .trim()
+                    .parse::<u8>()
+                    .map_err(|err| invalid(&err))?;
+                priority = match value {
+                    0 => None,
+                    1..=9 => Some(
+                        char::from(b'A' + value - 1)
+                            .to_string()
+                            .parse::<Priority>()?,
+                    ),
+                    _ => return Err(invalid(&"expected 0 to 9")),
+                }
+            }
+            "CATEGORIES" => tags.extend(_split_texts(&prop.value)),
+            "RRULE" => {
+                recur = Some(
+                    _parse_rrule(&prop.value, unmapped)
+                        .map_err(|err| invalid(&err))?,
+                )
+            }
+            X_RECUR => recur = Some(_unescape(&prop.value).parse::<Recur>()?),
+            "RELATED-TO" => {
+                match prop.param("RELTYPE").map(str::to_uppercase).as_deref() {
+                    None | Some("PARENT") => parent = Some(prop.value.clone()),
+                    Some("DEPENDS-ON") => depends_on.push(prop.value.clone()),
+                    Some(_) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let desc = desc
+        .filter(|desc| !desc.is_empty())
+        .ok_or("missing SUMMARY")?;
+    let key = key.ok_or("missing UID")?;
+    let now = tz.now();
+    let complete_at = match status {
+        Status::Done => Some(complete_at.unwrap_or(now)),
+        _ => None,
+    };
+    let mut todo = Todo::new(
+        desc,
+        link,
+        None,
+        None,
+        super::create_at(create_at, complete_at, now),
+        complete_at,
+    );
+    todo.set_status(status, now);
+    todo.assign_at = assign_at;
+    todo.priority = priority;
+    todo.tags = Some(tags).filter(|tags| !tags.is_empty());
+    todo.recur = recur;
+
+    let existing = _todo_ref(&key);
+    if existing.is_none() {
+        todo.uid = Some(key.clone());
+    }
+    Ok(Imported {
+        list: existing.as_ref().and_then(|r| r.list.clone()),
+        existing,
+        create_at,
+        key,
+        parent,
+        depends_on,
+        status: Some(todo.status()),
+        todo,
+    })
+}
+
+/// Reads a DATE-TIME in UTC, or in the timezone of taso when it is floating
+/// or in another TZID, or a DATE as the start of the day.
+fn _parse_date_time(prop: &Property, tz: Tz) -> Result<Timestamp, String> {
+    let value = prop.value.trim();
+    let invalid = || format!("invalid {} [{value}]", prop.name);
+    if let Some(utc) = value.strip_suffix('Z') {
+        return NaiveDateTime::parse_from_str(utc, DATE_TIME_FORMAT)
+            .map(|t| t.and_utc().fixed_offset())
+            .map_err(|_| invalid());
+    }
+    let (date, time) =
+        match NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT) {
+            Ok(t) => (t.date(), t.time() - chrono::NaiveTime::MIN),
+            Err(_) => (
+                NaiveDate::parse_from_str(value, DATE_FORMAT)
+                    .map_err(|_| invalid())?,
+                chrono::TimeDelta::zero(),
+            ),
+        };
+    tz.start_of_day(date).map(|t| t + time).ok_or_else(invalid)
+}
+
+/// Reads the FREQ, INTERVAL and BYDAY parts of a recurrence rule, the parts
+/// taso can't follow are reported to `unmapped` and dropped.
+fn _parse_rrule(
+    value: &str,
+    unmapped: &mut impl FnMut(&str),
+) -> Result<Recur, String> {
+    let mut recur = Recur {
+        every: 1,
+        unit: RecurUnit::Day,
+        weekdays: None,
+        after_completion: false,
+    };
+    let mut freq = false;
+    // weekdays are only read once the unit is known
+    let mut by_day = None;
+    for part in value.split(';') {
+        let Some((name, value)) = part.split_once('=') else {
+            continue;
+        };
+        match name.to_uppercase().as_str() {
+            "FREQ" => {
+                freq = true;
+                recur.unit = match value.to_uppercase().as_str() {
+                    "DAILY" => RecurUnit::Day,
+                    "WEEKLY" => RecurUnit::Week,
+                    "MONTHLY" => RecurUnit::Month,
+                    "YEARLY" => RecurUnit::Year,
+                    _ => return Err(format!("unsupported FREQ [{value}]")),
+                };
+            }
+            "INTERVAL" => {
+                recur.every = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|n| (1..=MAX_EVERY).contains(n))
+                    .ok_or_else(|| format!("invalid INTERVAL [{value}]"))?;
+            }
+            "BYDAY" => by_day = Some(value),
+            end @ ("COUNT" | "UNTIL") => {
+                unmapped(&format!("RRULE {end} (recurs without end)"));
+            }
+            "WKST" => {}
+            name => unmapped(&format!("RRULE {name} (dropped)")),
+        }
+    }
+    if !freq {
+        return Err("missing FREQ".to_owned());
+    }
+
+    let Some(by_day) = by_day else {
+        return Ok(recur);
+    };
+    let mut weekdays = Vec::new();
+    let mut ordinal = false;
+    for day in by_day.split(',') {
+        let day = day.to_uppercase();
+        // an ordinal such as 1MO, the first monday of the month
+        let name = day.trim_start_matches(|c: char| {
+            c.is_ascii_digit() || c == '+' || c == '-'
+        });
+        ordinal |= name.len() < day.len();
+        weekdays.push(match name {
+            "MO" => Weekday::Mon,
+            "TU" => Weekday::Tue,
+            "WE" => Weekday::Wed,
+            "TH" => Weekday::Thu,
+            "FR" => Weekday::Fri,
+            "SA" => Weekday::Sat,
+            "SU" => Weekday::Sun,
+            _ => return Err(format!("unsupported BYDAY [{day}]")),
+        });
+    }
+    if ordinal || !matches!(recur.unit, RecurUnit::Day | RecurUnit::Week) {
+        unmapped("RRULE BYDAY of a month or year (dropped)");
+    } else if recur.unit == RecurUnit::Day && recur.every > 1 {
+        unmapped("RRULE BYDAY of an INTERVAL of days (dropped)");
+    } else {
+        recur.weekdays = Some(weekdays);
+    }
+    Ok(recur)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::tests::{insert_and_export, tz};
+
+    #[test]
+    fn parse_vtodo() {
+        let input = "BEGIN:VCALENDAR\r\n\
+                     BEGIN:VEVENT\r\nUID:e1\r\nSUMMARY:Event\r\nEND:VEVENT\r\n\
+                     BEGIN:VTODO\r\nUID:p1\r\nSUMMARY:Plan\\, then ship\r\n\
+                     STATUS:IN-PROCESS\r\nDUE;TZID=Europe/Paris:20250601T090000\r\n\
+                     PRIORITY:2\r\nCATEGORIES:web,ops\r\n\
+                     RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH\r\n\
+                     BEGIN:VALARM\r\nACTION:DISPLAY\r\nEND:VALARM\r\nEND:VTODO\r\n\
+                     BEGIN:VTODO\r\nUID:3.dev@taso\r\nSUMMARY:Wri\r\n te\r\n\
+                     URL;VALUE=URI:http://a.b\r\nSTATUS:COMPLETED\r\n\
+                     COMPLETED:20250602T100000Z\r\nRELATED-TO:p1\r\n\
+                     RELATED-TO;RELTYPE=DEPENDS-ON:9.dev@taso\r\nEND:VTODO\r\n\
+                     END:VCALENDAR\r\n";
+        let (imported, report) = import(input, tz()).unwrap();
+        assert_eq!(imported.len(), 2);
+        assert!(report.is_empty());
+
+        let plan = &imported[0].todo;
+        assert_eq!(plan.desc, "Plan, then ship");
+        assert_eq!(plan.status(), Status::Progress);
+        assert_eq!(plan.assign_at.unwrap().to_string(), "2025-06-01");
+        assert_eq!(plan.priority, "B".parse().ok());
+        assert_eq!(plan.tags, Some(vec!["web".to_owned(), "ops".to_owned()]));
+        assert_eq!(
+            plan.recur.as_ref().unwrap().to_string(),
+            "every 2 weeks on mon,thu"
+        );
+        assert_eq!(imported[0].existing, None);
+        assert_eq!(plan.uid.as_deref(), Some("p1"));
+
+        let write = &imported[1];
+        assert_eq!(write.todo.desc, "Write");
+        assert_eq!(write.todo.link.as_deref(), Some("http://a.b"));
+        assert_eq!(write.todo.status(), Status::Done);
+        assert_eq!(write.parent.as_deref(), Some("p1"));
+        assert_eq!(write.depends_on, ["dev#9"]);
+        assert_eq!(write.existing, "dev#3".parse().ok());
+        assert_eq!(write.todo.uid, None);
+        assert_eq!(write.list.as_deref(), Some("dev"));
+
+        assert!(
+            import(
+                "BEGIN:VCALENDAR\nBEGIN:VTODO\nSUMMARY:x\nEND:VTODO\n",
+                tz()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = "BEGIN:VCALENDAR\r\nBEGIN:VTODO\r\nUID:1.dev@taso\r\n\
+                     SUMMARY:Plan\r\nEND:VTODO\r\nBEGIN:VTODO\r\n\
+                     UID:2.dev@taso\r\nSUMMARY:A very long description that \
+                     needs to be folded\\; twice or more\r\nSTATUS:NEEDS-ACTION\r\n\
+                     X-TASO-STATUS:waiting\r\nX-TASO-RECUR:after 3 days\r\n\
+                     RELATED-TO:1.dev@taso\r\nEND:VTODO\r\nEND:VCALENDAR\r\n";
+        let (imported, _) = import(input, tz()).unwrap();
+        let (_, output) =
+            insert_and_export(imported, |lists| export(lists, tz()));
+        assert!(output.lines().all(|line| line.len() <= LINE_LIMIT + 1));
+        let (again, _) = import(&output, tz()).unwrap();
+        assert_eq!(
+            again[1].todo.desc,
+            "A very long description that needs to be folded; twice or more"
+        );
+        assert_eq!(again[1].todo.status(), Status::Waiting);
+        assert_eq!(
+            again[1].todo.recur.as_ref().unwrap().to_string(),
+            "after 3 days"
+        );
+        assert_eq!(again[1].parent.as_deref(), Some("1.dev@taso"));
+
+        // a subtree exported without its parent
+        let subtree = output.replacen("UID:1.dev@taso", "UID:other", 1);
+        let (again, _) = import(&subtree, tz()).unwrap();
+        assert_eq!(again[1].parent.as_deref(), Some("dev#1"));
+    }
+
+    #[test]
+    fn unsupported_rrule() {
+        let mut report = Report::new();
+        let mut unmapped = |field: &str| {
+            report.entry(field.to_owned()).or_default().push("x".into());
+        };
+        let recur = _parse_rrule(
+            "FREQ=MONTHLY;BYDAY=1MO;COUNT=3;WKST=MO",
+            &mut unmapped,
+        )
+        .unwrap();
+        assert_eq!(recur.to_string(), "every month");
+        let recur = _parse_rrule(
+            "FREQ=WEEKLY;BYDAY=MO,FR;UNTIL=20251231T000000Z;BYSETPOS=1",
+            &mut unmapped,
+        )
+        .unwrap();
+        assert_eq!(recur.to_string(), "every week on mon,fri");
+        assert_eq!(
+            report.keys().collect::<Vec<_>>(),
+            [
+                "RRULE BYDAY of a month or year (dropped)",
+                "RRULE BYSETPOS (dropped)",
+                "RRULE COUNT (recurs without end)",
+                "RRULE UNTIL (recurs without end)",
+            ]
+        );
+        assert!(_parse_rrule("FREQ=WEEKLY;BYDAY=XX", &mut |_| {}).is_err());
+        assert!(_parse_rrule("BYDAY=MO", &mut |_| {}).is_err());
+    }
+}
@@ -0,0 +1,312 @@
+//! Nested checklists of GitHub-flavored Markdown.
+//!
+//! A todo is an item `- [ ] DESC`, or `- [ ] [DESC](LINK)` with a link,
+//! nested under its parent by two spaces. The box is `x` once done, `/` in
+//! progress, `>` waiting and `-` cancelled. A done todo ends with `✅ DATE`,
+//! and every exported todo with the marker `<!-- taso:LIST#ID CREATED -->`
+//! of the todo it comes from, `CREATED` being its creation time.
+
+use crate::{
+    date::Tz,
+    format::{ExportedList, Imported},
+    model::{Status, Todo, TodoRef},
+};
+use chrono::{DateTime, NaiveDate, SecondsFormat};
+use log::debug;
+use std::collections::HashSet;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DONE_MARK: &str = "✅";
+const MARKER_START: &str = "<!-- taso:";
+const MARKER_END: &str = "-->";
+
+/// Writes the todos of `lists` as nested checklists, under a heading per
+/// list when there are several of them.
+pub fn export(lists: &[ExportedList], tz: Tz) -> String {
+    let mut output = String::new();
+    for exported in lists {
+        if lists.len() > 1 {
+            if !output.is_empty() {
+                output.push('\n');
+            }
+            output.push_str(&format!("## {}\n\n", exported.list));
+        }
+        let ids: HashSet<_> = exported.ids.iter().collect();
+        let roots = exported.ids.iter().filter(|id| {
+            exported.todos[id]
+                .parent
+                .is_none_or(|pa_id| !ids.contains(&pa_id))
+        });
+        for id in roots {
+            _item(exported, &ids, *id, 0, tz, &mut output);
+        }
+    }
+    output
+}
+
+fn _item(
+    exported: &ExportedList,
+    ids: &HashSet<&u32>,
+    id: u32,
+    depth: usize,
+    tz: Tz,
+    output: &mut String,
+) {
+    let todo = &exported.todos[&id];
+    let check = match todo.status() {
+        Status::Todo => ' ',
+        Status::Progress => '/',
+        Status::Waiting => '>',
+        Status::Cancelled => '-',
+        Status::Done => 'x',
+    };
+    let desc = _escape(&todo.desc);
+    let text = match &todo.link {
+        Some(link) if link.contains([' ', '(', ')']) => {
+            format!("[{desc}](<{link}>)")
+        }
+        Some(link) => format!("[{desc}]({link})"),
+        None => desc,
+    };
+    let done = todo.complete_at().map_or_else(String::new, |complete_at| {
+        format!(" {DONE_MARK} {}", tz.date(&complete_at).format(DATE_FORMAT))
+    });
+    let marker = TodoRef {
+        list: Some(exported.list.to_owned()),
+        id,
+    };
+    let create_at =
+        todo.create_at().to_rfc3339_opts(SecondsFormat::Secs, false);
+    output.push_str(&format!(
+        "{}- [{check}] {text}{done} {MARKER_START}{marker} {create_at} {MARKER_END}\n",
+        "  ".repeat(depth)
+    ));
+    for ch_id in todo.children.iter().flatten() {
+        if ids.contains(ch_id) {
+            _item(exported, ids, *ch_id, depth + 1, tz, output);
+        }
+    }
+}
+
+/// Escapes the brackets of `desc`, which would be read as a link, and the
+/// mark of a done todo.
+fn _escape(desc: &str) -> String {
+    desc.replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(DONE_MARK, &format!("\\{DONE_MARK}"))
+}
+
+fn _unescape(desc: &str) -> String {
+    desc.replace("\\[", "[")
+        .replace("\\]", "]")
+        .replace(&format!("\\{DONE_MARK}"), DONE_MARK)
+}
+
+/// Reads the checklist items of Markdown `input`, other lines are skipped.
+/// Items are keyed by their line number, the parent of an item is the
+/// closest one above it which is less indented.
+pub fn import(input: &str, tz: Tz) -> Result<Vec<Imported>, String> {
+    let mut imported = Vec::new();
+    // (indent, key) of the items enclosing the current one
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        let n = n + 1;
+        let Some((indent, imp)) = _parse_item(line, n, tz)
+            .map_err(|err| format!("line {n}: {err}"))?
+        else {
+            continue;
+        };
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        let imp = Imported {
+            parent: stack.last().map(|(_, key)| key.clone()),
+            ..imp
+        };
+        debug!(target: "format::markdown", "line {n}: {imp:?}");
+        stack.push((indent, imp.key.clone()));
+        imported.push(imp);
+    }
+    Ok(imported)
+}
+
+/// Reads `line` as a checklist item, with its indent. `None` if it isn't.
+fn _parse_item(
+    line: &str,
+    n: usize,
+    tz: Tz,
+) -> Result<Option<(usize, Imported)>, String> {
+    let content = line.trim_start();
+    let indent: usize = line[..line.len() - content.len()]
+        .chars()
+        .map(|c| {
+            if c == '\t' {
+                4
+            } else {
+                1
+            }
+        })
+        .sum();
+    let Some(item) = content
+        .strip_prefix("- [")
+        .or_else(|| content.strip_prefix("* ["))
+        .or_else(|| content.strip_prefix("+ ["))
+    else {
+        return Ok(None);
+    };
+    let mut chars = item.chars();
+    let (Some(check), Some(']')) = (chars.next(), chars.next()) else {
+        return Ok(None);
+    };
+    let status = match check {
+        ' ' => Status::Todo,
+        'x' | 'X' => Status::Done,
+        '/' => Status::Progress,
+        '>' => Status::Waiting,
+        '-' => Status::Cancelled,
+        _ => return Ok(None),
+    };
+    let mut text = chars.as_str().trim();
+
+    let mut existing = None;
+    let mut create_at = None;
+    if let Some((rest, marker)) = text.rsplit_once(MARKER_START) {
+        let mut marker = marker.trim_end_matches(MARKER_END).split_whitespace();
+        existing = marker.next().map(str::parse::<TodoRef>).transpose()?;
+        create_at = marker
+            .next()
+            .map(DateTime::parse_from_rfc3339)
+            .transpose()
+            .map_err(|err| format!("invalid creation time: {err}"))?;
+        text = rest.trim_end();
+    }
+    // only a trailing `✅ DATE` is the completion date, the mark is part of
+    // the description otherwise
+    let mut complete_date = None;
+    if let Some((rest, date)) = text
+        .rsplit_once(DONE_MARK)
+        .filter(|(rest, _)| !rest.ends_with('\\'))
+    {
+        if let Ok(date) = NaiveDate::parse_from_str(date.trim(), DATE_FORMAT) {
+            complete_date = Some(date);
+            text = rest.trim_end();
+        }
+    }
+    let (desc, link) = match _parse_link(text) {
+        Some((desc, link)) => (desc, Some(link.to_owned())),
+        None => (text, None),
+    };
+    let desc = _unescape(desc);
+    if desc.is_empty() {
+        return Err("missing description".to_owned());
+    }
+
+    let now = tz.now();
+    let complete_at = match (status, complete_date) {
+        (Status::Done, Some(date)) => Some(
+            tz.start_of_day(date)
+                .ok_or_else(|| format!("invalid date [{date}] in {tz:?}"))?,
+        ),
+        (Status::Done, None) => Some(now),
+        _ => None,
+    };
+    let mut todo = Todo::new(
+        desc,
+        link,
+        None,
+        None,
+        super::create_at(create_at, complete_at, now),
+        complete_at,
+    );
+    todo.set_status(status, now);
+
+    Ok(Some((
+        indent,
+        Imported {
+            key: format!("line {n}"),
+            list: existing.as_ref().and_then(|r| r.list.clone()),
+            existing,
+            create_at,
+            parent: None,
+            depends_on: Vec::new(),
+            status: Some(todo.status()),
+            todo,
+        },
+    )))
+}
+
+/// Splits `[DESC](LINK)` or `[DESC](<LINK>)`, only if it is the whole text.
+fn _parse_link(text: &str) -> Option<(&str, &str)> {
+    let (desc, link) = text.strip_prefix('[')?.rsplit_once("](")?;
+    let link = link.strip_suffix(')')?;
+    let link = match link.strip_prefix('<') {
+        Some(link) => link.strip_suffix('>')?,
+        None => link,
+    };
+    Some((desc, link))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::tests::{insert_and_export, tz};
+
+    #[test]
+    fn parse_items() {
+        let input = "# Meeting\n\
+                     - [ ] Plan \\[v2\\]\n\
+                     \x20 - [x] [Notes](<http://a b>) ✅ 2025-06-01\n\
+                     \x20 - [>] Budget <!-- taso:work#7 -->\n\
+                     \x20     - [/] Ask\n\
+                     some text\n\
+                     * [-] Old\n\
+                     - [?] Not an item\n";
+        let imported = import(input, tz()).unwrap();
+        let summary: Vec<_> = imported
+            .iter()
+            .map(|imp| {
+                (
+                    imp.key.as_str(),
+                    imp.parent.as_deref(),
+                    imp.todo.desc.as_str(),
+                    imp.todo.status(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("line 2", None, "Plan [v2]", Status::Todo),
+                ("line 3", Some("line 2"), "Notes", Status::Done),
+                ("line 4", Some("line 2"), "Budget", Status::Waiting),
+                ("line 5", Some("line 4"), "Ask", Status::Progress),
+                ("line 7", None, "Old", Status::Cancelled),
+            ]
+        );
+        assert_eq!(imported[1].todo.link.as_deref(), Some("http://a b"));
+        let complete_at = imported[1].todo.complete_at().unwrap();
+        assert_eq!(tz().date(&complete_at).to_string(), "2025-06-01");
+        assert_eq!(imported[1].todo.create_at(), complete_at);
+        assert_eq!(imported[2].existing, "work#7".parse().ok());
+        assert_eq!(imported[2].list.as_deref(), Some("work"));
+        assert_eq!(imported[2].create_at, None);
+        // a mark in a description written by hand
+        let imported = import("- [ ] Party ✅ tomorrow", tz()).unwrap();
+        assert_eq!(imported[0].todo.desc, "Party ✅ tomorrow");
+        assert!(import("- [ ] <!-- taso:x -->", tz()).is_err());
+        assert!(import("- [ ] A <!-- taso:#1 yesterday -->", tz()).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = "- [ ] [Plan](http://p) <!-- taso:dev#1 2025-05-30T09:00:00+00:00 -->\n\
+                     \x20 - [x] Ship ✅ 2025-06-01 <!-- taso:dev#2 2025-05-31T09:00:00+00:00 -->\n\
+                     \x20 - [ ] Party \\✅ tomorrow <!-- taso:dev#3 2025-05-31T09:00:00+00:00 -->\n";
+        let imported = import(input, tz()).unwrap();
+        let (store, output) =
+            insert_and_export(imported, |lists| export(lists, tz()));
+        assert_eq!(output, input);
+        let todos = store.todos(&"dev".to_owned()).unwrap();
+        assert_eq!(todos[&3].desc, "Party ✅ tomorrow");
+    }
+}
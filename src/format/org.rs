@@ -0,0 +1,429 @@
+//! Outlines of Emacs Org mode.
+//!
+//! A todo is a heading `* KEYWORD [#PRIORITY] DESC :TAG:`, nested under its
+//! parent by one more star. The keyword is `TODO`, `STARTED`, `WAITING`,
+//! `DONE` or `CANCELLED`, as the `#+TODO:` line of the file declares. The
+//! planning line holds `CLOSED:` and `SCHEDULED:` (`assign_at`), the
+//! `:PROPERTIES:` drawer `TASO_ID`, `LINK`, `CREATED`, `RECUR`, `ESTIMATE`
+//! and `DEPENDS_ON`.
+
+use crate::{
+    date::Tz,
+    format::{ExportedList, Imported},
+    model::{Status, Timestamp, Todo, TodoRef},
+};
+use chrono::{NaiveDate, NaiveTime};
+use log::debug;
+use regex::Regex;
+use std::collections::HashMap;
+
+const TODO_KEYWORDS: &str = "#+TODO: TODO STARTED WAITING | DONE CANCELLED";
+const DATE_FORMAT: &str = "%Y-%m-%d %a";
+const TIME_FORMAT: &str = "%H:%M";
+const PLANNING_KEYWORDS: [&str; 3] = ["CLOSED", "SCHEDULED", "DEADLINE"];
+/// The escape character of Org, which keeps text from being read as syntax.
+const ZERO_WIDTH_SPACE: char = '\u{200b}';
+
+/// Writes the todos of `lists` as nested headings, under a heading per list
+/// when there are several of them.
+pub fn export(lists: &[ExportedList], tz: Tz) -> String {
+    let mut output = format!("{TODO_KEYWORDS}\n");
+    let level = if lists.len() > 1 {
+        2
+    } else {
+        1
+    };
+    for exported in lists {
+        if lists.len() > 1 {
+            output.push_str(&format!("\n* {}\n", exported.list));
+        }
+        let roots = exported.ids.iter().filter(|id| {
+            exported.todos[id]
+                .parent
+                .is_none_or(|pa_id| !exported.ids.contains(&pa_id))
+        });
+        for id in roots {
+            _heading(exported, *id, level, tz, &mut output);
+        }
+    }
+    output
+}
+
+fn _heading(
+    exported: &ExportedList,
+    id: u32,
+    level: usize,
+    tz: Tz,
+    output: &mut String,
+) {
+    let todo = &exported.todos[&id];
+    let keyword = match todo.status() {
+        Status::Todo => "TODO",
+        Status::Progress => "STARTED",
+        Status::Waiting => "WAITING",
+        Status::Done => "DONE",
+        Status::Cancelled => "CANCELLED",
+    };
+    let mut heading = vec!["*".repeat(level), keyword.to_owned()];
+    heading.extend(todo.priority.map(|p| format!("[#{p}]")));
+    heading.push(_escape(&todo.desc));
+    if let Some(tags) = todo.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        heading.push(format!(":{}:", tags.join(":")));
+    }
+    output.push_str(&heading.join(" "));
+    output.push('\n');
+
+    let mut planning = Vec::new();
+    planning.extend(
+        todo.complete_at().map(|complete_at| {
+            format!("CLOSED: {}", _timestamp(complete_at, tz))
+        }),
+    );
+    planning.extend(
+        todo.assign_at
+            .map(|date| format!("SCHEDULED: <{}>", date.format(DATE_FORMAT))),
+    );
+    if !planning.is_empty() {
+        output.push_str(&planning.join(" "));
+        output.push('\n');
+    }
+
+    let todo_ref = TodoRef {
+        list: Some(exported.list.to_owned()),
+        id,
+    };
+    let mut properties = vec![
+        ("TASO_ID", todo_ref.to_string()),
+        ("CREATED", _timestamp(todo.create_at(), tz)),
+    ];
+    properties.extend(todo.link.clone().map(|link| ("LINK", link)));
+    properties.extend(todo.recur.as_ref().map(|r| ("RECUR", r.to_string())));
+    properties.extend(todo.estimate.map(|e| ("ESTIMATE", e.to_string())));
+    if let Some(depends_on) = &todo.depends_on {
+        let refs: Vec<_> = depends_on.iter().map(|r| r.to_string()).collect();
+        properties.push(("DEPENDS_ON", refs.join(" ")));
+    }
+    output.push_str(":PROPERTIES:\n");
+    for (name, value) in properties {
+        output.push_str(&format!(":{name}: {value}\n"));
+    }
+    output.push_str(":END:\n");
+
+    for ch_id in todo.children.iter().flatten() {
+        if exported.ids.contains(ch_id) {
+            _heading(exported, *ch_id, level + 1, tz, output);
+        }
+    }
+}
+
+/// Guards the start of `desc` which would be read as a priority or a link,
+/// and the end which would be read as tags, with a zero width space.
+fn _escape(desc: &str) -> String {
+    let mut escaped = String::new();
+    if desc.starts_with('[') {
+        escaped.push(ZERO_WIDTH_SPACE);
+    }
+    escaped.push_str(desc);
+    if desc.ends_with(':') {
+        escaped.push(ZERO_WIDTH_SPACE);
+    }
+    escaped
+}
+
+fn _unescape(desc: &str) -> &str {
+    let desc = desc
+        .strip_prefix(ZERO_WIDTH_SPACE)
+        .filter(|rest| rest.starts_with('['))
+        .unwrap_or(desc);
+    desc.strip_suffix(ZERO_WIDTH_SPACE)
+        .filter(|rest| rest.ends_with(':'))
+        .unwrap_or(desc)
+}
+
+/// Writes `timestamp` as an inactive timestamp of Org, to the minute.
+fn _timestamp(timestamp: Timestamp, tz: Tz) -> String {
+    let datetime = tz.datetime(&timestamp);
+    format!(
+        "[{} {}]",
+        datetime.format(DATE_FORMAT),
+        datetime.format(TIME_FORMAT)
+    )
+}
+
+/// Reads `[DATE DAY TIME]` or `<DATE DAY>` in `tz`, repeaters and the end
+/// of time ranges are ignored.
+fn _parse_timestamp(value: &str, tz: Tz) -> Result<Timestamp, String> {
+    let inner = value
+        .trim_start_matches(['[', '<'])
+        .trim_end_matches([']', '>']);
+    let mut words = inner.split_whitespace();
+    let date = words.next().unwrap_or_default();
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|err| format!("invalid timestamp {value}: {err}"))?;
+    let time = words
+        .filter_map(|word| word.split('-').next())
+        .find_map(|word| NaiveTime::parse_from_str(word, TIME_FORMAT).ok())
+        .unwrap_or(NaiveTime::MIN);
+    tz.local(date.and_time(time))
+        .ok_or_else(|| format!("invalid timestamp {value} in {tz:?}"))
+}
+
+/// Reads the headings of Org `input` which have a todo keyword, keyed by
+/// their line number. The parent of a todo is the closest heading above it
+/// with fewer stars, if that one is a todo.
+pub fn import(input: &str, tz: Tz) -> Result<Vec<Imported>, String> {
+    let heading_re = Regex::new(
+        r"^(\*+)\s+(?:(TODO|NEXT|STARTED|WAITING|DONE|CANCELL?ED)(?:\s+|$))?(?:\[#([A-Za-z])\]\s*)?(.*?)(?:\s+(:\S+:))?\s*$",
+    )
+    .map_err(|err| err.to_string())?;
+    // headings with their line number and the lines of their section
+    let mut sections: Vec<(usize, &str, Vec<&str>)> = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        if heading_re.is_match(line) {
+            sections.push((n + 1, line, Vec::new()));
+        } else if let Some((_, _, lines)) = sections.last_mut() {
+            lines.push(line);
+        }
+    }
+
+    let mut imported = Vec::new();
+    // (level, key) of the todos enclosing the current heading
+    let mut stack: Vec<(usize, Option<String>)> = Vec::new();
+    for (n, heading, lines) in sections {
+        let caps = heading_re.captures(heading).unwrap();
+        let level = caps[1].len();
+        while stack.last().is_some_and(|(l, _)| *l >= level) {
+            stack.pop();
+        }
+        let Some(keyword) = caps.get(2) else {
+            stack.push((level, None));
+            continue;
+        };
+        let imp = _parse_todo(&caps, keyword.as_str(), &lines, n, tz)
+            .map_err(|err| format!("line {n}: {err}"))?;
+        let imp = Imported {
+            parent: stack.last().and_then(|(_, key)| key.clone()),
+            ..imp
+        };
+        debug!(target: "format::org", "line {n}: {imp:?}");
+        stack.push((level, Some(imp.key.clone())));
+        imported.push(imp);
+    }
+    Ok(imported)
+}
+
+fn _parse_todo(
+    caps: &regex::Captures,
+    keyword: &str,
+    lines: &[&str],
+    n: usize,
+    tz: Tz,
+) -> Result<Imported, String> {
+    let status = match keyword {
+        "STARTED" => Status::Progress,
+        "WAITING" => Status::Waiting,
+        "DONE" => Status::Done,
+        "CANCELLED" | "CANCELED" => Status::Cancelled,
+        _ => Status::Todo,
+    };
+    let priority = caps.get(3).map(|p| p.as_str().parse()).transpose()?;
+    let mut desc = caps[4].to_owned();
+    let tags: Vec<_> = caps
+        .get(5)
+        .into_iter()
+        .flat_map(|tags| tags.as_str().split(':'))
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect();
+
+    // the planning line comes right after the heading
+    let mut planning = HashMap::new();
+    if let Some(line) = lines.first() {
+        for keyword in PLANNING_KEYWORDS {
+            let value = line
+                .split_once(&format!("{keyword}:"))
+                .map(|(_, rest)| rest.trim_start())
+                .and_then(|rest| {
+                    rest.find([']', '>']).map(|end| &rest[..=end])
+                });
+            if let Some(value) = value {
+                planning.insert(keyword, value);
+            }
+        }
+    }
+    let mut properties = HashMap::new();
+    let drawer = lines
+        .iter()
+        .skip_while(|line| line.trim() != ":PROPERTIES:")
+        .skip(1)
+        .take_while(|line| line.trim() != ":END:");
+    for line in drawer {
+        let property = line.trim().strip_prefix(':').and_then(|property| {
+            property
+                .split_once(':')
+                .filter(|(name, _)| !name.is_empty())
+        });
+        if let Some((name, value)) = property {
+            properties.insert(name.to_uppercase(), value.trim().to_owned());
+        }
+    }
+
+    let mut link = properties.remove("LINK");
+    // a heading which is a whole link `[[LINK][DESC]]` or `[[LINK]]`
+    let org_link = desc
+        .strip_prefix("[[")
+        .and_then(|inner| inner.strip_suffix("]]"))
+        .map(|inner| inner.split_once("][").unwrap_or((inner, inner)));
+    if let Some((target, text)) = org_link {
+        link = link.or_else(|| Some(target.to_owned()));
+        desc = text.to_owned();
+    }
+    let desc = _unescape(&desc).to_owned();
+    if desc.is_empty() {
+        return Err("missing description".to_owned());
+    }
+
+    let now = tz.now();
+    let create_at = properties
+        .get("CREATED")
+        .map(|value| _parse_timestamp(value, tz))
+        .transpose()?;
+    let complete_at = match status {
+        Status::Done => Some(
+            planning
+                .get("CLOSED")
+                .map(|value| _parse_timestamp(value, tz))
+                .transpose()?
+                .unwrap_or(now),
+        ),
+        _ => None,
+    };
+    let mut todo = Todo::new(
+        desc,
+        link,
+        None,
+        None,
+        super::create_at(create_at, complete_at, now),
+        complete_at,
+    );
+    todo.set_status(status, now);
+    todo.assign_at = planning
+        .get("SCHEDULED")
+        .or_else(|| planning.get("DEADLINE"))
+        .map(|value| _parse_timestamp(value, tz).map(|t| tz.date(&t)))
+        .transpose()?;
+    todo.priority = priority;
+    todo.tags = Some(tags).filter(|tags| !tags.is_empty());
+    todo.recur = properties.get("RECUR").map(|r| r.parse()).transpose()?;
+    todo.estimate =
+        properties.get("ESTIMATE").map(|e| e.parse()).transpose()?;
+
+    let existing = properties
+        .get("TASO_ID")
+        .map(|r| r.parse::<TodoRef>())
+        .transpose()?;
+    let depends_on = properties
+        .get("DEPENDS_ON")
+        .into_iter()
+        .flat_map(|refs| refs.split_whitespace())
+        .map(|r| r.parse::<TodoRef>().map(|r| r.to_string()))
+        .collect::<Result<_, _>>()?;
+    Ok(Imported {
+        key: format!("line {n}"),
+        list: existing.as_ref().and_then(|r| r.list.clone()),
+        existing,
+        create_at,
+        parent: None,
+        depends_on,
+        status: Some(todo.status()),
+        todo,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::tests::{insert_and_export, tz};
+
+    #[test]
+    fn parse_headings() {
+        let input = "#+TITLE: Plans\n\
+                     * Project\n\
+                     ** TODO [#B] Plan :ui:ux:\n\
+                     SCHEDULED: <2025-06-03 Tue +1w>\n\
+                     Some text\n\
+                     *** DONE [[http://a][Notes]]\n\
+                     CLOSED: [2025-06-02 Mon 10:30]\n\
+                     \x20 :PROPERTIES:\n\
+                     \x20 :TASO_ID: work#7\n\
+                     \x20 :END:\n\
+                     ** WAITING Budget\n\
+                     * NEXT Ask\n";
+        let imported = import(input, tz()).unwrap();
+        let summary: Vec<_> = imported
+            .iter()
+            .map(|imp| {
+                (
+                    imp.key.as_str(),
+                    imp.parent.as_deref(),
+                    imp.todo.desc.as_str(),
+                    imp.todo.status(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("line 3", None, "Plan", Status::Todo),
+                ("line 6", Some("line 3"), "Notes", Status::Done),
+                ("line 11", None, "Budget", Status::Waiting),
+                ("line 12", None, "Ask", Status::Todo),
+            ]
+        );
+        let plan = &imported[0].todo;
+        assert_eq!(plan.priority, "B".parse().ok());
+        assert_eq!(plan.tags, Some(vec!["ui".to_owned(), "ux".to_owned()]));
+        assert_eq!(plan.assign_at, NaiveDate::from_ymd_opt(2025, 6, 3));
+        let notes = &imported[1];
+        assert_eq!(notes.todo.link.as_deref(), Some("http://a"));
+        assert_eq!(
+            notes.todo.complete_at().unwrap().to_rfc3339(),
+            "2025-06-02T10:30:00+00:00"
+        );
+        assert_eq!(notes.existing, "work#7".parse().ok());
+        assert_eq!(notes.list.as_deref(), Some("work"));
+        assert!(import(&format!("{input}* TODO\n"), tz()).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = "#+TODO: TODO STARTED WAITING | DONE CANCELLED\n\
+                     * TODO [#A] Plan :ui:\n\
+                     SCHEDULED: <2025-06-03 Tue>\n\
+                     :PROPERTIES:\n\
+                     :TASO_ID: dev#1\n\
+                     :CREATED: [2025-06-01 Sun 09:00]\n\
+                     :LINK: http://p\n\
+                     :ESTIMATE: 2h\n\
+                     :END:\n\
+                     ** DONE Ship\n\
+                     CLOSED: [2025-06-02 Mon 10:30]\n\
+                     :PROPERTIES:\n\
+                     :TASO_ID: dev#2\n\
+                     :CREATED: [2025-06-01 Sun 09:00]\n\
+                     :DEPENDS_ON: dev#1\n\
+                     :END:\n\
+                     ** TODO \u{200b}[#A] Fix :bug:\u{200b}\n\
+                     :PROPERTIES:\n\
+                     :TASO_ID: dev#3\n\
+                     :CREATED: [2025-06-01 Sun 09:00]\n\
+                     :END:\n";
+        let imported = import(input, tz()).unwrap();
+        let (store, output) =
+            insert_and_export(imported, |lists| export(lists, tz()));
+        assert_eq!(output, input);
+        let todos = store.todos(&"dev".to_owned()).unwrap();
+        assert_eq!(todos[&3].desc, "[#A] Fix :bug:");
+        assert_eq!((todos[&3].priority, &todos[&3].tags), (None, &None));
+    }
+}
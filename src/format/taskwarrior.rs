@@ -0,0 +1,451 @@
+//! JSON of Taskwarrior, as written by `task export` and read by `task
+//! import`.
+//!
+//! The list is the project, notes are annotations and priorities `A`, `B`
+//! and `C` are `H`, `M` and `L`. Fields Taskwarrior has no use for are kept
+//! in `taso_*` attributes (UDAs) so that a round trip loses nothing but the
+//! time logs, and they are reported along with the fields of Taskwarrior
+//! taso can't read.
+
+use crate::{
+    date::Tz,
+    format::{ExportedList, Imported, Report},
+    model::{Note, Priority, Status, Timestamp, Todo, TodoRef},
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use log::debug;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Fields of Taskwarrior which are only bookkeeping, they are not reported.
+const IGNORED: [&str; 4] = ["id", "urgency", "modified", "imask"];
+
+/// Returns a UUID derived from the list and id of a todo, so that it stays
+/// the same from an export to another.
+pub fn uuid(list: &str, id: u32) -> String {
+    let key = format!("{list}#{id}");
+    // 64-bit FNV-1a with 2 offsets, mixed by the finalizer of MurmurHash3
+    let hash = |offset: u64| {
+        let mut h = key.bytes().fold(offset, |h, b| {
+            (h ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+        h = (h ^ (h >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h = (h ^ (h >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^ (h >> 33)
+    };
+    // version 8 (custom) and variant 10
+    let high = (hash(0xcbf2_9ce4_8422_2325) & !0xf000) | 0x8000;
+    let low = (hash(0x8422_2325_cbf2_9ce4) & !(0b11 << 62)) | (0b10 << 62);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+/// Writes the todos of `lists` as a JSON array of tasks, along with the
+/// report of the fields kept as `taso_*` attributes or dropped.
+pub fn export(lists: &[ExportedList], tz: Tz) -> (String, Report) {
+    let mut report = Report::new();
+    let mut tasks = Vec::new();
+    for exported in lists {
+        for id in &exported.ids {
+            let todo = &exported.todos[id];
+            let todo_ref = format!("{}#{id}", exported.list);
+            let mut unmapped = |field: &str| {
+                report
+                    .entry(field.to_owned())
+                    .or_default()
+                    .push(todo_ref.clone());
+            };
+            tasks.push(_task(exported.list, *id, todo, tz, &mut unmapped));
+        }
+    }
+    let mut output =
+        serde_json::to_string_pretty(&tasks).unwrap_or_else(|_| "[]".into());
+    output.push('\n');
+    (output, report)
+}
+
+fn _task(
+    list: &str,
+    id: u32,
+    todo: &Todo,
+    tz: Tz,
+    unmapped: &mut impl FnMut(&str),
+) -> Value {
+    let mut task = Map::new();
+    task.insert("uuid".into(), json!(uuid(list, id)));
+    task.insert("taso_id".into(), json!(format!("{list}#{id}")));
+    task.insert("description".into(), json!(todo.desc));
+    task.insert("project".into(), json!(list));
+    task.insert("entry".into(), json!(_date_time(&todo.create_at())));
+    let status = todo.status();
+    let tw_status = match status {
+        Status::Todo | Status::Progress | Status::Waiting => "pending",
+        Status::Cancelled => "deleted",
+        Status::Done => "completed",
+    };
+    task.insert("status".into(), json!(tw_status));
+    // taso has no time of the start or the cancellation to write
+    if matches!(status, Status::Progress | Status::Waiting) {
+        unmapped(&format!("status {status} (as taso_status)"));
+        task.insert("taso_status".into(), json!(status.to_string()));
+    }
+    if let Some(complete_at) = todo.complete_at() {
+        task.insert("end".into(), json!(_date_time(&complete_at)));
+    }
+    if let Some(due) = todo.assign_at.and_then(|d| tz.start_of_day(d)) {
+        task.insert("due".into(), json!(_date_time(&due)));
+    }
+    if let Some(tags) = &todo.tags {
+        task.insert("tags".into(), json!(tags));
+    }
+    if let Some(priority) = todo.priority {
+        let tw_priority = match priority.to_string().as_str() {
+            "A" => "H",
+            "B" => "M",
+            "C" => "L",
+            _ => {
+                unmapped("priority below C (as L)");
+                "L"
+            }
+        };
+        task.insert("priority".into(), json!(tw_priority));
+    }
+    if let Some(depends_on) = &todo.depends_on {
+        let uuids: Vec<_> = depends_on
+            .iter()
+            .map(|r| uuid(r.list_or(list), r.id))
+            .collect();
+        task.insert("depends".into(), json!(uuids));
+    }
+    if let Some(notes) = &todo.notes {
+        let annotations: Vec<_> = notes
+            .iter()
+            .map(|note| {
+                json!({
+                    "entry": _date_time(&note.at),
+                    "description": note.text,
+                })
+            })
+            .collect();
+        task.insert("annotations".into(), json!(annotations));
+    }
+
+    if let Some(link) = &todo.link {
+        unmapped("link (as taso_link)");
+        task.insert("taso_link".into(), json!(link));
+    }
+    if let Some(pa_id) = todo.parent {
+        unmapped("parent (as taso_parent)");
+        task.insert("taso_parent".into(), json!(uuid(list, pa_id)));
+    }
+    if let Some(recur) = &todo.recur {
+        unmapped("recurrence (as taso_recur)");
+        task.insert("taso_recur".into(), json!(recur.to_string()));
+    }
+    if let Some(estimate) = todo.estimate {
+        unmapped("estimate (as taso_estimate)");
+        task.insert("taso_estimate".into(), json!(estimate.to_string()));
+    }
+    if todo.time_log.is_some() {
+        unmapped("time log (dropped)");
+    }
+    Value::Object(task)
+}
+
+fn _date_time(timestamp: &Timestamp) -> String {
+    timestamp
+        .with_timezone(&Utc)
+        .format(DATE_TIME_FORMAT)
+        .to_string()
+}
+
+/// Reads the tasks of `input`, a JSON array or one task per line, along
+/// with the report of the fields which couldn't be read. Tasks are keyed by
+/// their UUID, recurring templates are skipped.
+pub fn import(input: &str, tz: Tz) -> Result<(Vec<Imported>, Report), String> {
+    let tasks: Vec<Map<String, Value>> = if input.trim_start().starts_with('[')
+    {
+        serde_json::from_str(input).map_err(|err| err.to_string())?
+    } else {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line)
+                    .map_err(|err| format!("line {}: {err}", n + 1))
+            })
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut report = Report::new();
+    let mut imported = Vec::new();
+    for (n, task) in tasks.into_iter().enumerate() {
+        let n = n + 1;
+        let name = task
+            .get("description")
+            .and_then(Value::as_str)
+            .map_or_else(|| format!("task {n}"), |desc| format!("[{desc}]"));
+        if task.get("status").and_then(Value::as_str) == Some("recurring") {
+            report
+                .entry("recurring template (skipped)".into())
+                .or_default()
+                .push(name);
+            continue;
+        }
+        let mut unmapped = |field: &str| {
+            report
+                .entry(field.to_owned())
+                .or_default()
+                .push(name.clone());
+        };
+        let imp = _parse_task(task, tz, &mut unmapped)
+            .map_err(|err| format!("task {n}: {err}"))?;
+        debug!(target: "format::taskwarrior", "task {n}: {imp:?}");
+        imported.push(imp);
+    }
+    Ok((imported, report))
+}
+
+fn _parse_task(
+    task: Map<String, Value>,
+    tz: Tz,
+    unmapped: &mut impl FnMut(&str),
+) -> Result<Imported, String> {
+    let mut fields: HashMap<String, Value> = task.into_iter().collect();
+    let mut take_str = |name: &str| -> Result<Option<String>, String> {
+        match fields.remove(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(value)) => Ok(Some(value)),
+            Some(value) => Err(format!("invalid {name} [{value}]")),
+        }
+    };
+
+    let key = take_str("uuid")?.ok_or("missing uuid")?;
+    let desc = take_str("description")?
+        .filter(|desc| !desc.is_empty())
+        .ok_or("missing description")?;
+    let existing = take_str("taso_id")?
+        .map(|r| r.parse::<TodoRef>())
+        .transpose()?;
+    let list = take_str("project")?;
+    let status = take_str("status")?;
+    let entry = take_str("entry")?
+        .map(|t| _parse_date_time(&t))
+        .transpose()?;
+    let end = take_str("end")?.map(|t| _parse_date_time(&t)).transpose()?;
+    let due = take_str("due")?.map(|t| _parse_date_time(&t)).transpose()?;
+    let start = take_str("start")?;
+    let wait = take_str("wait")?
+        .map(|t| _parse_date_time(&t))
+        .transpose()?;
+    let priority = take_str("priority")?;
+    let taso_status = take_str("taso_status")?;
+    let link = take_str("taso_link")?;
+    let parent = take_str("taso_parent")?;
+    let recur = take_str("taso_recur")?.map(|r| r.parse()).transpose()?;
+    let estimate = take_str("taso_estimate")?.map(|e| e.parse()).transpose()?;
+
+    let tags = match fields.remove("tags") {
+        Some(value) => serde_json::from_value::<Vec<String>>(value)
+            .map_err(|err| format!("invalid tags: {err}"))?,
+        None => Vec::new(),
+    };
+    // an array, or a string of comma separated UUIDs before Taskwarrior 2.6
+    let depends_on = match fields.remove("depends") {
+        Some(Value::String(uuids)) => uuids
+            .split(',')
+            .map(|uuid| uuid.trim().to_owned())
+            .collect(),
+        Some(value) => serde_json::from_value::<Vec<String>>(value)
+            .map_err(|err| format!("invalid depends: {err}"))?,
+        None => Vec::new(),
+    };
+    let notes = match fields.remove("annotations") {
+        Some(Value::Array(annotations)) => annotations
+            .into_iter()
+            .map(|annotation| {
+                let at = annotation
+                    .get("entry")
+                    .and_then(Value::as_str)
+                    .map(_parse_date_time)
+                    .transpose()?;
+                let text = annotation
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .ok_or("annotation without description")?;
+                Ok(Note {
+                    at: at.unwrap_or_else(|| tz.now()),
+                    text: text.to_owned(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?,
+        Some(value) => return Err(format!("invalid annotations [{value}]")),
+        None => Vec::new(),
+    };
+    for field in fields.keys().filter(|f| !IGNORED.contains(&f.as_str())) {
+        unmapped(field);
+    }
+
+    let now = tz.now();
+    let status = match (status.as_deref(), taso_status) {
+        (Some("completed"), _) => Status::Done,
+        (Some("deleted"), _) => Status::Cancelled,
+        (_, Some(status)) => {
+            <Status as clap::ValueEnum>::from_str(&status, true)?
+        }
+        (Some("waiting"), _) => Status::Waiting,
+        (_, _) if wait.is_some_and(|wait| wait > now) => Status::Waiting,
+        (_, _) if start.is_some() => Status::Progress,
+        (None | Some("pending"), _) => Status::Todo,
+        (Some(status), _) => return Err(format!("unknown status [{status}]")),
+    };
+    let complete_at = (status == Status::Done).then(|| end.unwrap_or(now));
+    let mut todo = Todo::new(
+        desc,
+        link,
+        None,
+        None,
+        super::create_at(entry, complete_at, now),
+        complete_at,
+    );
+    todo.set_status(status, now);
+    todo.assign_at = due.map(|due| tz.date(&due));
+    todo.tags = Some(tags).filter(|tags| !tags.is_empty());
+    todo.priority = match priority.as_deref() {
+        Some("H") => Some("A".parse::<Priority>()?),
+        Some("M") => Some("B".parse()?),
+        Some("L") => Some("C".parse()?),
+        Some(priority) => return Err(format!("unknown priority [{priority}]")),
+        None => None,
+    };
+    todo.notes = Some(notes).filter(|notes| !notes.is_empty());
+    todo.recur = recur;
+    todo.estimate = estimate;
+
+    Ok(Imported {
+        key,
+        existing,
+        create_at: entry,
+        list,
+        parent,
+        depends_on,
+        status: Some(todo.status()),
+        todo,
+    })
+}
+
+/// Reads `20250601T120000Z`, or an RFC 3339 timestamp.
+fn _parse_date_time(value: &str) -> Result<Timestamp, String> {
+    NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT)
+        .map(|t| t.and_utc().fixed_offset())
+        .or_else(|_| DateTime::parse_from_rfc3339(value))
+        .map_err(|err| format!("invalid date [{value}]: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::tests::{insert_and_export, tz};
+    use crate::model::TodoMap;
+
+    #[test]
+    fn stable_uuid() {
+        let id = uuid("dev", 3);
+        assert_eq!(id, uuid("dev", 3));
+        assert_ne!(id, uuid("dev", 4));
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "8");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+    }
+
+    #[test]
+    fn parse_tasks() {
+        let input = r#"[
+            {"id":1,"uuid":"u1","description":"Plan","status":"pending",
+             "entry":"20250601T120000Z","project":"dev","tags":["web"],
+             "priority":"M","start":"20250602T080000Z","urgency":4.5,
+             "annotations":[{"entry":"20250603T090000Z","description":"asked"}],
+             "scheduled":"20250604T000000Z"},
+            {"uuid":"u2","description":"Ship","status":"completed",
+             "end":"20250605T100000Z","depends":"u1,u9","due":"20250606T220000Z"},
+            {"uuid":"u3","description":"Weekly","status":"recurring"}
+        ]"#;
+        let (imported, report) = import(input, tz()).unwrap();
+        assert_eq!(imported.len(), 2);
+
+        let plan = &imported[0];
+        assert_eq!(plan.key, "u1");
+        assert_eq!(plan.list.as_deref(), Some("dev"));
+        assert_eq!(plan.todo.status(), Status::Progress);
+        assert_eq!(plan.todo.priority, "B".parse().ok());
+        assert_eq!(plan.todo.tags, Some(vec!["web".to_owned()]));
+        assert_eq!(plan.todo.notes.as_ref().unwrap()[0].text, "asked");
+
+        let ship = &imported[1];
+        assert_eq!(ship.todo.status(), Status::Done);
+        assert_eq!(ship.depends_on, ["u1", "u9"]);
+        assert_eq!(ship.todo.assign_at.unwrap().to_string(), "2025-06-06");
+
+        assert_eq!(report["scheduled"], ["[Plan]"]);
+        assert_eq!(report["recurring template (skipped)"], ["[Weekly]"]);
+        assert!(!report.contains_key("urgency"));
+
+        assert!(import(r#"{"uuid":"u"}"#, tz()).is_err());
+        assert!(
+            import(r#"{"uuid":"u","description":"x","priority":"X"}"#, tz())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = r#"{"uuid":"u1","description":"Plan","status":"pending","entry":"20250601T120000Z","taso_id":"dev#1","taso_link":"http://x","taso_estimate":"2h"}
+{"uuid":"u2","description":"Ship","status":"pending","entry":"20250601T120000Z","taso_status":"waiting","taso_parent":"u1","priority":"H"}"#;
+        let (imported, _) = import(input, tz()).unwrap();
+        assert_eq!(imported[0].existing, "dev#1".parse().ok());
+        let (_, (output, report)) =
+            insert_and_export(imported, |lists| export(lists, tz()));
+        let (again, _) = import(&output, tz()).unwrap();
+        assert_eq!(again[0].key, uuid("dev", 1));
+        assert_eq!(again[0].todo.link.as_deref(), Some("http://x"));
+        assert_eq!(again[1].todo.status(), Status::Waiting);
+        assert_eq!(again[1].parent, Some(uuid("dev", 1)));
+        assert_eq!(again[1].todo.priority, "A".parse().ok());
+        assert_eq!(report["link (as taso_link)"], ["dev#1"]);
+        assert_eq!(report["parent (as taso_parent)"], ["dev#2"]);
+    }
+
+    #[test]
+    fn stable_export() {
+        let created =
+            DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z").unwrap();
+        let mut todos = TodoMap::new();
+        for (id, status) in [(1, Status::Progress), (2, Status::Cancelled)] {
+            let mut todo =
+                Todo::new(status.to_string(), None, None, None, created, None);
+            todo.set_status(status, tz().now());
+            todos.insert(id, todo);
+        }
+        let exported = || ExportedList {
+            list: "dev",
+            todos: &todos,
+            ids: vec![1, 2],
+        };
+        let (output, report) = export(&[exported()], tz());
+        assert_eq!(output, export(&[exported()], tz()).0);
+        assert!(!output.contains("\"start\"") && !output.contains("\"end\""));
+        assert_eq!(report["status progress (as taso_status)"], ["dev#1"]);
+
+        let (again, _) = import(&output, tz()).unwrap();
+        assert_eq!(again[0].todo.status(), Status::Progress);
+        assert_eq!(again[1].todo.status(), Status::Cancelled);
+    }
+}
@@ -0,0 +1,408 @@
+//! [todo.txt](https://github.com/todotxt/todo.txt) format.
+//!
+//! A todo is written as `[x] [(A)] [COMPLETED] [CREATED] DESC +LIST @TAG
+//! KEY:VALUE`. Fields without a place in the format are kept as `KEY:VALUE`
+//! extensions: `id`, `parent`, `link`, `due`, `status`, `rec`, `estimate`,
+//! `dep` and `pri` (priority of a completed todo). Spaces in values are
+//! written as `%20`, and words of the description which would be read as
+//! one of the fields as `%2B` (`+`), `%40` (`@`) and `%3A` (`:`).
+
+use crate::{
+    date::Tz,
+    format::{ExportedList, Imported},
+    model::{
+        Priority, Status, Todo, TodoRef,
+        recur::{MAX_EVERY, Recur, RecurUnit},
+    },
+};
+use chrono::NaiveDate;
+use log::debug;
+use std::fmt::Display;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Writes the todos of `lists`, one per line.
+pub fn export(lists: &[ExportedList], tz: Tz) -> String {
+    let mut output = String::new();
+    for exported in lists {
+        for id in &exported.ids {
+            let todo = &exported.todos[id];
+            output.push_str(&_line(exported.list, *id, todo, tz));
+            output.push('\n');
+        }
+    }
+    output
+}
+
+fn _line(list: &str, id: u32, todo: &Todo, tz: Tz) -> String {
+    let mut words = Vec::new();
+    let status = todo.status();
+    match todo.complete_at() {
+        Some(complete_at) => {
+            words.push("x".to_owned());
+            words.push(tz.date(&complete_at).format(DATE_FORMAT).to_string());
+        }
+        None => words.extend(todo.priority.map(|p| format!("({p})"))),
+    }
+    words.push(tz.date(&todo.create_at()).format(DATE_FORMAT).to_string());
+    words.push(_encode_desc(&todo.desc));
+    words.push(format!("+{}", _encode(list)));
+    for tag in todo.tags.iter().flatten() {
+        words.push(format!("@{}", _encode(tag)));
+    }
+
+    words.push(format!("id:{id}"));
+    words.extend(todo.parent.map(|pa_id| format!("parent:{pa_id}")));
+    words.extend(todo.link.as_ref().map(|l| format!("link:{}", _encode(l))));
+    words.extend(todo.assign_at.map(|d| format!("due:{d}")));
+    if matches!(
+        status,
+        Status::Progress | Status::Waiting | Status::Cancelled
+    ) {
+        words.push(format!("status:{status}"));
+    }
+    words.extend(todo.recur.as_ref().map(|r| format!("rec:{}", _rec(r))));
+    words.extend(
+        todo.estimate
+            .map(|e| format!("estimate:{}", _encode(&e.to_string()))),
+    );
+    if let Some(depends_on) = &todo.depends_on {
+        let refs: Vec<_> = depends_on.iter().map(|r| r.to_string()).collect();
+        words.push(format!("dep:{}", refs.join(",")));
+    }
+    if todo.complete_at().is_some() {
+        words.extend(todo.priority.map(|p| format!("pri:{p}")));
+    }
+    words.join(" ")
+}
+
+/// Writes `recur` as the `rec:` extension of todo.txt apps, e.g. `+2w` for
+/// every 2 weeks and `3d` for 3 days after completion. Rules on weekdays
+/// are written as taso's own.
+fn _rec(recur: &Recur) -> String {
+    if recur.weekdays.is_some() {
+        return _encode(&recur.to_string());
+    }
+    let unit = match recur.unit {
+        RecurUnit::Day => 'd',
+        RecurUnit::Week => 'w',
+        RecurUnit::Month => 'm',
+        RecurUnit::Year => 'y',
+    };
+    let strict = if recur.after_completion {
+        ""
+    } else {
+        "+"
+    };
+    format!("{strict}{}{unit}", recur.every)
+}
+
+/// Reads todos from todo.txt `input`. Todos with an `id:` are keyed by
+/// `LIST#ID`, the others by their line number.
+pub fn import(input: &str, tz: Tz) -> Result<Vec<Imported>, String> {
+    let mut imported = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        let n = n + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let imp = _parse_line(line, n, tz)
+            .map_err(|err| format!("line {n}: {err}"))?;
+        debug!(target: "format::todotxt", "line {n}: {imp:?}");
+        imported.push(imp);
+    }
+    Ok(imported)
+}
+
+fn _parse_line(line: &str, n: usize, tz: Tz) -> Result<Imported, String> {
+    let mut words = line.split_whitespace().peekable();
+    let done = words.next_if_eq(&"x").is_some();
+    let mut priority = words
+        .next_if(|w| w.len() == 3 && w.starts_with('(') && w.ends_with(')'))
+        .map(|w| w[1..2].parse::<Priority>())
+        .transpose()?;
+    let mut dates = Vec::new();
+    while dates.len() < 1 + usize::from(done) {
+        let Some(date) = words
+            .peek()
+            .and_then(|w| NaiveDate::parse_from_str(w, DATE_FORMAT).ok())
+        else {
+            break;
+        };
+        words.next();
+        dates.push(date);
+    }
+    let (complete_date, create_date) = match (done, dates.as_slice()) {
+        (true, [complete, create]) => (Some(*complete), Some(*create)),
+        (true, [complete]) => (Some(*complete), None),
+        (false, [create]) => (None, Some(*create)),
+        _ => (done.then(|| tz.today()), None),
+    };
+
+    let mut desc = Vec::new();
+    let mut list = None;
+    let mut tags = Vec::new();
+    let mut id = None;
+    let mut parent = None;
+    let mut link = None;
+    let mut assign_at = None;
+    let mut status = None;
+    let mut recur = None;
+    let mut estimate = None;
+    let mut depends_on = Vec::new();
+    for word in words {
+        let project = word
+            .strip_prefix('+')
+            .filter(|p| !p.is_empty() && list.is_none());
+        if let Some(project) = project {
+            list = Some(_decode(project));
+            continue;
+        }
+        if let Some(tag) = word.strip_prefix('@').filter(|t| !t.is_empty()) {
+            tags.push(_decode(tag));
+            continue;
+        }
+        let Some((key, value)) =
+            word.split_once(':').filter(|(_, v)| !v.is_empty())
+        else {
+            desc.push(_decode(word));
+            continue;
+        };
+        let invalid =
+            |err: &dyn Display| format!("invalid {key} [{value}]: {err}");
+        match key {
+            "id" => {
+                id = Some(value.parse::<u32>().map_err(|err| invalid(&err))?)
+            }
+            "parent" => {
+                parent =
+                    Some(value.parse::<u32>().map_err(|err| invalid(&err))?)
+            }
+            "link" => link = Some(_decode(value)),
+            "due" => {
+                assign_at = Some(
+                    NaiveDate::parse_from_str(value, DATE_FORMAT)
+                        .map_err(|err| invalid(&err))?,
+                )
+            }
+            "status" => {
+                status = Some(
+                    <Status as clap::ValueEnum>::from_str(value, true)
+                        .map_err(|err| invalid(&err))?,
+                )
+            }
+            "rec" => {
+                recur = Some(_parse_rec(value).map_err(|err| invalid(&err))?)
+            }
+            "estimate" => estimate = Some(_decode(value).parse()?),
+            "dep" => {
+                for dep in value.split(',') {
+                    depends_on.push(dep.parse::<TodoRef>()?);
+                }
+            }
+            "pri" => priority = Some(value.parse()?),
+            _ => desc.push(_decode(word)),
+        }
+    }
+    if desc.is_empty() {
+        return Err("missing description".to_owned());
+    }
+
+    let start_of_day = |date: NaiveDate| {
+        tz.start_of_day(date)
+            .ok_or_else(|| format!("invalid date [{date}] in {tz:?}"))
+    };
+    let complete_at = complete_date.map(start_of_day).transpose()?;
+    let created = create_date.map(start_of_day).transpose()?;
+    let create_at = super::create_at(created, complete_at, tz.now());
+    let mut todo =
+        Todo::new(desc.join(" "), link, None, None, create_at, complete_at);
+    if let Some(status) = status.filter(|_| complete_at.is_none()) {
+        todo.set_status(status, create_at);
+    }
+    todo.assign_at = assign_at;
+    todo.recur = recur;
+    todo.estimate = estimate;
+    todo.tags = Some(tags).filter(|tags| !tags.is_empty());
+    todo.priority = priority;
+
+    let key = |id: u32| TodoRef {
+        list: list.clone(),
+        id,
+    };
+    Ok(Imported {
+        key: id.map_or_else(|| format!("line {n}"), |id| key(id).to_string()),
+        existing: id.map(key),
+        create_at: created,
+        list: list.clone(),
+        parent: parent.map(|pa_id| key(pa_id).to_string()),
+        depends_on: depends_on
+            .into_iter()
+            .map(|r| match r.list {
+                Some(_) => r.to_string(),
+                None => key(r.id).to_string(),
+            })
+            .collect(),
+        status: Some(todo.status()),
+        todo,
+    })
+}
+
+/// Reads a `rec:` value, `[+]N(d|w|m|y)` or a rule of taso.
+fn _parse_rec(value: &str) -> Result<Recur, String> {
+    let (strict, rule) = match value.strip_prefix('+') {
+        Some(rule) => (true, rule),
+        None => (false, value),
+    };
+    let Some(unit) = rule.chars().last().filter(|_| rule.is_ascii()) else {
+        return Err("empty recurrence".to_owned());
+    };
+    let every = &rule[..rule.len() - 1];
+    let every = match every {
+        "" => Some(1),
+        every => every
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (1..=MAX_EVERY).contains(n)),
+    };
+    match (every, unit.to_string().parse::<RecurUnit>()) {
+        (Some(every), Ok(unit)) => Ok(Recur {
+            every,
+            unit,
+            weekdays: None,
+            after_completion: !strict,
+        }),
+        _ => _decode(value).parse(),
+    }
+}
+
+fn _encode(value: &str) -> String {
+    value.replace('%', "%25").replace(' ', "%20")
+}
+
+fn _decode(value: &str) -> String {
+    value
+        .replace("%20", " ")
+        .replace("%2B", "+")
+        .replace("%40", "@")
+        .replace("%3A", ":")
+        .replace("%25", "%")
+}
+
+/// Encodes the words of `desc` which would be read as a list, a tag or a
+/// `KEY:VALUE` extension.
+fn _encode_desc(desc: &str) -> String {
+    let words: Vec<_> = desc
+        .split(' ')
+        .map(|word| {
+            let word = word.replace('%', "%25").replace(':', "%3A");
+            match word.strip_prefix('+') {
+                Some(rest) => format!("%2B{rest}"),
+                None => match word.strip_prefix('@') {
+                    Some(rest) => format!("%40{rest}"),
+                    None => word,
+                },
+            }
+        })
+        .collect();
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::tests::{insert_and_export, tz};
+    use crate::model::TodoMap;
+
+    fn parse(line: &str) -> Imported {
+        _parse_line(line, 1, tz()).unwrap()
+    }
+
+    #[test]
+    fn parse_spec() {
+        let imp = parse("(A) 2011-03-01 Call Mom +Family @phone @home");
+        assert_eq!(imp.key, "line 1");
+        assert_eq!(imp.list.as_deref(), Some("Family"));
+        assert_eq!(imp.todo.desc, "Call Mom");
+        assert_eq!(imp.todo.priority, "A".parse().ok());
+        assert_eq!(
+            imp.todo.tags,
+            Some(vec!["phone".to_owned(), "home".to_owned()])
+        );
+        assert_eq!(tz().date(&imp.todo.create_at()).to_string(), "2011-03-01");
+        assert_eq!(
+            (imp.existing, imp.create_at),
+            (None, Some(imp.todo.create_at()))
+        );
+
+        let imp = parse("x 2011-03-03 2011-03-01 Review +a +b key:value");
+        assert_eq!(imp.todo.status(), Status::Done);
+        assert_eq!(imp.todo.desc, "Review +b key:value");
+        let complete_at = imp.todo.complete_at().unwrap();
+        assert_eq!(tz().date(&complete_at).to_string(), "2011-03-03");
+
+        // not created after its completion
+        let imp = parse("x 2011-03-03 Review");
+        assert_eq!(imp.todo.create_at(), imp.todo.complete_at().unwrap());
+    }
+
+    #[test]
+    fn parse_extensions() {
+        let imp = parse(
+            "Plan id:3 parent:1 link:http://a.b/c%20d due:2025-06-01 \
+             status:waiting rec:+2w estimate:1d%20+%203pt dep:2,work#4 +dev",
+        );
+        assert_eq!(imp.key, "dev#3");
+        assert_eq!(imp.existing, "dev#3".parse().ok());
+        assert_eq!(imp.create_at, None);
+        assert_eq!(imp.parent.as_deref(), Some("dev#1"));
+        assert_eq!(imp.depends_on, ["dev#2", "work#4"]);
+        assert_eq!(imp.todo.link.as_deref(), Some("http://a.b/c d"));
+        assert_eq!(imp.todo.status(), Status::Waiting);
+        assert_eq!(imp.todo.recur.unwrap().to_string(), "every 2 weeks");
+        assert_eq!(imp.todo.estimate.unwrap().to_string(), "8h + 3pt");
+        assert_eq!(imp.todo.assign_at.unwrap().to_string(), "2025-06-01");
+
+        assert_eq!(_parse_rec("3d").unwrap().to_string(), "after 3 days");
+        assert_eq!(
+            _parse_rec("every%20week%20on%20mon").unwrap().to_string(),
+            "every week on mon"
+        );
+        assert!(_parse_line("id:x Plan", 1, tz()).is_err());
+        assert!(_parse_line("(A) +dev", 1, tz()).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = "x 2025-06-03 2025-06-01 Plan +dev id:1\n\
+                     x 2025-06-03 2025-06-01 Ship it +dev @web id:2 parent:1 \
+                     link:http://x due:2025-06-02 rec:1m dep:dev#1 pri:B\n";
+        let imported = import(input, tz()).unwrap();
+        let (_, output) =
+            insert_and_export(imported, |lists| export(lists, tz()));
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn round_trip_desc() {
+        let desc = "Email @alice re +1 budget due:friday, 50% off";
+        let todo =
+            Todo::new(desc.to_owned(), None, None, None, tz().now(), None);
+        let mut todos = TodoMap::new();
+        todos.insert(1, todo);
+        let exported = ExportedList {
+            list: "dev",
+            todos: &todos,
+            ids: vec![1],
+        };
+        let output = export(&[exported], tz());
+        assert!(output.contains(
+            " Email %40alice re %2B1 budget due%3Afriday, 50%25 off +dev "
+        ));
+        let imp = parse(output.trim_end());
+        assert_eq!(imp.todo.desc, desc);
+        assert_eq!(imp.list.as_deref(), Some("dev"));
+        assert_eq!(imp.todo.tags, None);
+        assert_eq!(imp.todo.assign_at, None);
+    }
+}
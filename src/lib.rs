@@ -1,7 +1,12 @@
 pub mod command;
 pub mod config;
 pub mod context;
+pub mod date;
+pub mod format;
 pub mod model;
+pub mod query;
+pub mod scan;
+pub mod selection;
 pub mod store;
 pub mod ui;
 pub mod util;
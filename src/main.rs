@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use log::{debug, error, info, trace};
 use taso::{
-    command::{Cli, Cmd, Run, RunMut, TodoCmd},
+    command::{Cli, Cmd},
     config::Config,
     context::Context,
     store::Store,
@@ -39,20 +39,7 @@ fn run() -> Result<()> {
         Cmd::Todo(todo_cmd) => {
             let mut ctx = init_ctx(config, cli.global)?;
 
-            match todo_cmd {
-                TodoCmd::Add(add) => add.run_mut(&mut ctx),
-                TodoCmd::Tree(tree) => tree.run(&ctx),
-                TodoCmd::Track(track) => track.run(&ctx),
-                TodoCmd::Modify(modify) => modify.run_mut(&mut ctx),
-                TodoCmd::Move(move_) => move_.run_mut(&mut ctx),
-                TodoCmd::Remove(remove) => remove.run_mut(&mut ctx),
-                TodoCmd::Done(done) => done.run_mut(&mut ctx),
-                TodoCmd::Clean(clean) => clean.run_mut(&mut ctx),
-                TodoCmd::ListShow(l_show) => l_show.run(&ctx),
-                TodoCmd::ListAdd(l_add) => l_add.run_mut(&mut ctx),
-                TodoCmd::ListRemove(l_remove) => l_remove.run_mut(&mut ctx),
-                TodoCmd::ListDefault(l_default) => l_default.run_mut(&mut ctx),
-            }
+            todo_cmd.run(&mut ctx)
         }
     }
 }
@@ -1,4 +1,14 @@
-use chrono::NaiveDate;
+pub mod effort;
+pub mod recur;
+
+use crate::model::{
+    effort::{Effort, Estimate, TimeEntry},
+    recur::Recur,
+};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveTime, SecondsFormat, TimeZone,
+};
+use clap::ValueEnum;
 use log::trace;
 use serde::de::Error;
 use serde::ser::SerializeMap;
@@ -7,16 +17,228 @@ use std::{
     collections::HashMap,
     fmt::{Debug, Display},
     ops::{Deref, DerefMut},
+    str::FromStr,
 };
 
+/// A point in time with the UTC offset it was recorded in, stored as RFC 3339.
+pub type Timestamp = DateTime<FixedOffset>;
+
+/// Lifecycle state of a todo.
+///
+/// `Done` is kept in sync with `Todo::complete_at`, a todo read from an older
+/// store without `status` is `Done` as long as `complete_at` is filled in.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Deserialize,
+    Serialize,
+    ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    #[default]
+    Todo,
+    Progress,
+    Waiting,
+    Cancelled,
+    Done,
+}
+
+impl Status {
+    /// Statuses which still need some work.
+    pub const OPEN: [Status; 3] =
+        [Status::Todo, Status::Progress, Status::Waiting];
+
+    pub fn is_open(&self) -> bool {
+        Self::OPEN.contains(self)
+    }
+
+    /// Two-column marker displayed in front of a todo.
+    pub fn marker(&self) -> &'static str {
+        match self {
+            Status::Todo => "  ",
+            Status::Progress => "▶ ",
+            Status::Waiting => "⏸ ",
+            Status::Cancelled => "✘ ",
+            Status::Done => "✔ ",
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            Status::Todo => "todo",
+            Status::Progress => "progress",
+            Status::Waiting => "waiting",
+            Status::Cancelled => "cancelled",
+            Status::Done => "done",
+        };
+        write!(f, "{status}")
+    }
+}
+
+/// Reference to a todo, possibly in another list.
+///
+/// Parsed from `ID`, `#ID` or `LIST#ID`, stored as `LIST#ID` once resolved.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TodoRef {
+    pub list: Option<String>,
+    pub id: u32,
+}
+
+impl TodoRef {
+    /// Fills in `list` if the reference is relative to it.
+    pub fn resolve(self, list: &str) -> Self {
+        Self {
+            list: self.list.or_else(|| Some(list.to_owned())),
+            id: self.id,
+        }
+    }
+
+    /// Returns the list of the reference, or `list` if it is relative.
+    pub fn list_or<'a>(&'a self, list: &'a str) -> &'a str {
+        self.list.as_deref().unwrap_or(list)
+    }
+}
+
+impl FromStr for TodoRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (list, id) = match s.rsplit_once('#') {
+            Some(("", id)) => (None, id),
+            Some((list, id)) => (Some(list.to_owned()), id),
+            None => (None, s),
+        };
+        let id = id
+            .parse::<u32>()
+            .map_err(|err| format!("invalid todo id [{s}]: {err}"))?;
+        Ok(Self { list, id })
+    }
+}
+
+impl TryFrom<String> for TodoRef {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<TodoRef> for String {
+    fn from(value: TodoRef) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for TodoRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.list {
+            Some(list) => write!(f, "{list}#{}", self.id),
+            None => write!(f, "#{}", self.id),
+        }
+    }
+}
+
+/// Importance of a todo, from `A` (highest) to `Z` as in todo.txt.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deserialize,
+    Serialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Priority(char);
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => {
+                Ok(Self(c.to_ascii_uppercase()))
+            }
+            _ => Err(format!("invalid priority [{s}], expected A to Z")),
+        }
+    }
+}
+
+impl TryFrom<String> for Priority {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Priority> for String {
+    fn from(value: Priority) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A dated remark on a todo, e.g. an annotation of Taskwarrior.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Note {
+    #[serde(
+        serialize_with = "serialize_timestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub at: Timestamp,
+    pub text: String,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Todo {
     pub desc: String,
     pub link: Option<String>,
+    pub assign_at: Option<NaiveDate>,
     pub children: Option<Vec<u32>>,
     pub parent: Option<u32>,
-    create_at: NaiveDate,
-    pub complete_at: Option<NaiveDate>,
+    #[serde(
+        serialize_with = "serialize_timestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    create_at: Timestamp,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_opt_timestamp",
+        deserialize_with = "deserialize_opt_timestamp"
+    )]
+    complete_at: Option<Timestamp>,
+    #[serde(default)]
+    status: Status,
+    pub depends_on: Option<Vec<TodoRef>>,
+    pub recur: Option<Recur>,
+    pub tags: Option<Vec<String>>,
+    pub priority: Option<Priority>,
+    pub notes: Option<Vec<Note>>,
+    pub time_log: Option<Vec<TimeEntry>>,
+    pub estimate: Option<Estimate>,
+    /// UID of the iCalendar to-do the todo was imported from, when it
+    /// wasn't exported from taso.
+    pub uid: Option<String>,
 }
 
 impl Todo {
@@ -25,24 +247,73 @@ impl Todo {
         link: Option<String>,
         children: Option<Vec<u32>>,
         parent: Option<u32>,
-        create_at: NaiveDate,
-        complete_at: Option<NaiveDate>,
+        create_at: Timestamp,
+        complete_at: Option<Timestamp>,
     ) -> Self {
         let todo = Self {
             desc,
             link,
+            assign_at: None,
             children,
             parent,
             create_at,
             complete_at,
+            status: complete_at.map_or(Status::Todo, |_| Status::Done),
+            depends_on: None,
+            recur: None,
+            tags: None,
+            priority: None,
+            notes: None,
+            time_log: None,
+            estimate: None,
+            uid: None,
         };
         trace!(target: "model::new", "create new Todo: {todo:#?}");
         todo
     }
 
-    pub fn create_at(&self) -> NaiveDate {
+    pub fn create_at(&self) -> Timestamp {
         self.create_at
     }
+
+    pub fn complete_at(&self) -> Option<Timestamp> {
+        self.complete_at
+    }
+
+    pub fn status(&self) -> Status {
+        match self.complete_at {
+            Some(_) => Status::Done,
+            None if self.status == Status::Done => Status::Todo,
+            None => self.status,
+        }
+    }
+
+    /// Total time logged on the todo itself.
+    pub fn spent(&self) -> Effort {
+        self.time_log.iter().flatten().map(|e| e.effort).sum()
+    }
+
+    /// Moves the todo into `status`, `complete_at` is set to `now` when it
+    /// becomes `Done` and cleared otherwise.
+    ///
+    /// Returns `false` if nothing changed: the todo is already in `status`, or
+    /// it is `Done` and `status` is not `Todo` (only undo can reopen it).
+    pub fn set_status(&mut self, status: Status, now: Timestamp) -> bool {
+        let current = self.status();
+        if current == status
+            || (current == Status::Done && status != Status::Todo)
+        {
+            trace!(target: "model::set_status", "skip {current} -> {status}");
+            return false;
+        }
+        self.complete_at = match status {
+            Status::Done => Some(now),
+            _ => None,
+        };
+        self.status = status;
+        trace!(target: "model::set_status", "{current} -> {status}");
+        true
+    }
 }
 
 impl Display for Todo {
@@ -52,10 +323,34 @@ impl Display for Todo {
             .link
             .as_ref()
             .map_or_else(|| "(none)".to_owned(), |u| u.clone());
-        let create_at = self.create_at();
+        let assign_at = self
+            .assign_at
+            .map_or_else(|| "(none)".to_owned(), |d| d.to_string());
+        let recur = self
+            .recur
+            .as_ref()
+            .map_or_else(|| "(none)".to_owned(), |r| r.to_string());
+        let tags = self
+            .tags
+            .as_ref()
+            .map_or_else(|| "(none)".to_owned(), |tags| tags.join(","));
+        let priority = self
+            .priority
+            .map_or_else(|| "(none)".to_owned(), |p| p.to_string());
+        let notes = self.notes.as_ref().map_or_else(
+            || "(none)".to_owned(),
+            |notes| {
+                notes
+                    .iter()
+                    .map(|n| format!("\n  {} {}", local_time(&n.at), n.text))
+                    .collect()
+            },
+        );
+        let status = self.status();
+        let create_at = local_time(&self.create_at);
         let complete_at = self
             .complete_at
-            .map_or_else(|| "(todo)".to_owned(), |d| d.to_string());
+            .map_or_else(|| "(todo)".to_owned(), |t| local_time(&t));
         let children = self.children.as_ref().map_or_else(
             || "(none)".to_owned(),
             |ch_id| {
@@ -70,10 +365,23 @@ impl Display for Todo {
             .parent
             .as_ref()
             .map_or_else(|| "(none)".to_owned(), |pa_id| pa_id.to_string());
+        let estimate = self
+            .estimate
+            .map_or_else(|| "(none)".to_owned(), |e| e.to_string());
+        let spent = self.spent();
+        let depends_on = self.depends_on.as_ref().map_or_else(
+            || "(none)".to_owned(),
+            |refs| {
+                refs.iter()
+                    .map(|r| r.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            },
+        );
 
         write!(
             f,
-            "Todo: {desc}\nLink: {link}\nCreate At: {create_at}\nComplete At: {complete_at}\nChildren: {children}\nParent: {parent}"
+            "Todo: {desc}\nLink: {link}\nTags: {tags}\nPriority: {priority}\nStatus: {status}\nAssign At: {assign_at}\nRecur: {recur}\nCreate At: {create_at}\nComplete At: {complete_at}\nChildren: {children}\nParent: {parent}\nDepends On: {depends_on}\nEstimate: {estimate}\nTime Spent: {spent}\nNotes: {notes}"
         )
     }
 }
@@ -88,12 +396,170 @@ pub struct TodoMap {
     todos: HashMap<u32, Todo>,
 }
 
+impl Default for TodoMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TodoMap {
     pub fn new() -> Self {
         Self {
             todos: HashMap::new(),
         }
     }
+
+    /// Returns the numbers of completed and all children of todo `id`,
+    /// cancelled children are not counted. `None` if it has no children.
+    pub fn progress(&self, id: &u32) -> Option<(usize, usize)> {
+        let ch_ids = self.todos.get(id)?.children.as_ref()?;
+        let (done, total) = ch_ids
+            .iter()
+            .filter_map(|ch_id| self.todos.get(ch_id))
+            .map(|ch| ch.status())
+            .filter(|status| *status != Status::Cancelled)
+            .fold((0, 0), |(done, total), status| {
+                (done + usize::from(status == Status::Done), total + 1)
+            });
+        Some((done, total))
+    }
+
+    /// Returns the completion ratio of todo `id` in `0.0..=1.0`. The ratio of
+    /// a parent todo is the mean of its (not cancelled) children's.
+    pub fn completion(&self, id: &u32) -> f64 {
+        let Some(todo) = self.todos.get(id) else {
+            return 0.0;
+        };
+        let ratios: Vec<_> = todo
+            .children
+            .iter()
+            .flatten()
+            .filter(|ch_id| {
+                self.todos
+                    .get(ch_id)
+                    .is_some_and(|ch| ch.status() != Status::Cancelled)
+            })
+            .map(|ch_id| self.completion(ch_id))
+            .collect();
+        if ratios.is_empty() {
+            return f64::from(u8::from(todo.status() == Status::Done));
+        }
+        ratios.iter().sum::<f64>() / ratios.len() as f64
+    }
+
+    /// Returns the time logged on todo `id` and all its descendants.
+    pub fn total_spent(&self, id: &u32) -> Effort {
+        let Some(todo) = self.todos.get(id) else {
+            return Effort::default();
+        };
+        todo.spent()
+            + todo
+                .children
+                .iter()
+                .flatten()
+                .map(|ch_id| self.total_spent(ch_id))
+                .sum()
+    }
+
+    /// Returns the estimates of todo `id` and all its descendants summed up,
+    /// cancelled todos are not counted.
+    pub fn total_estimate(&self, id: &u32) -> Estimate {
+        let Some(todo) = self.todos.get(id) else {
+            return Estimate::default();
+        };
+        if todo.status() == Status::Cancelled {
+            return Estimate::default();
+        }
+        todo.estimate.unwrap_or_default()
+            + todo
+                .children
+                .iter()
+                .flatten()
+                .map(|ch_id| self.total_estimate(ch_id))
+                .sum()
+    }
+
+    /// Returns the ancestors of todo `id`, from its root down to its parent.
+    pub fn ancestors(&self, id: &u32) -> Vec<u32> {
+        let mut ancestors = Vec::new();
+        let mut current = self.todos.get(id).and_then(|t| t.parent);
+        while let Some(pa_id) = current {
+            // a broken store could link parents in a cycle
+            if ancestors.contains(&pa_id) {
+                break;
+            }
+            ancestors.push(pa_id);
+            current = self.todos.get(&pa_id).and_then(|t| t.parent);
+        }
+        ancestors.reverse();
+        ancestors
+    }
+
+    /// Updates todo `pa_id` and its ancestors from their children: a parent is
+    /// completed when all its children are done, and reopened when one of them
+    /// is not. Cancelled parents are left as they are.
+    ///
+    /// Returns the todo(s) whose status changed.
+    pub fn roll_up(&mut self, pa_id: u32, now: Timestamp) -> Vec<u32> {
+        let mut changed = Vec::new();
+        let mut current = Some(pa_id);
+        while let Some(id) = current {
+            let (done, total) = self.progress(&id).unwrap_or((0, 0));
+            let Some(todo) = self.todos.get_mut(&id) else {
+                break;
+            };
+            let status = todo.status();
+            let rolled = if total == 0 || status == Status::Cancelled {
+                status
+            } else if done == total {
+                Status::Done
+            } else if status == Status::Done {
+                Status::Todo
+            } else {
+                status
+            };
+            if todo.set_status(rolled, now) {
+                trace!(target: "model::roll_up", "todo [{id}] {status} -> {rolled}");
+                changed.push(id);
+            }
+            current = todo.parent;
+        }
+        changed
+    }
+
+    /// Removes todo(s) `ids`, along with which their children must be
+    /// removed. A parent left behind loses the todo and is rolled up.
+    ///
+    /// Returns the removed todo(s), in the order of `ids`.
+    pub fn remove_todos(&mut self, ids: &[u32], now: Timestamp) -> Vec<Todo> {
+        let mut removed = Vec::new();
+        let mut detached = Vec::new();
+        for id in ids {
+            let Some(todo) = self.todos.remove(id) else {
+                continue;
+            };
+            if let Some(pa_id) = todo.parent.filter(|pa| !ids.contains(pa)) {
+                if let Some(pa_todo) = self.todos.get_mut(&pa_id) {
+                    if let Some(ch_ids) = pa_todo.children.as_mut() {
+                        ch_ids.retain(|ch_id| ch_id != id);
+                    }
+                    if pa_todo.children.as_ref().is_some_and(|ch| ch.is_empty())
+                    {
+                        pa_todo.children = None;
+                    }
+                }
+                if !detached.contains(&pa_id) {
+                    detached.push(pa_id);
+                }
+            }
+            removed.push(todo);
+        }
+        for pa_id in detached {
+            let rolled = self.roll_up(pa_id, now);
+            trace!(target: "model::remove_todos", "roll up todo [{rolled:?}]");
+        }
+        removed
+    }
 }
 
 impl Deref for TodoMap {
@@ -120,6 +586,72 @@ impl IntoIterator for TodoMap {
     }
 }
 
+/// Formats `timestamp` in the local time of the system.
+pub fn local_time(timestamp: &Timestamp) -> String {
+    timestamp
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn serialize_timestamp<S>(
+    timestamp: &Timestamp,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer
+        .serialize_str(&timestamp.to_rfc3339_opts(SecondsFormat::Secs, false))
+}
+
+fn serialize_opt_timestamp<S>(
+    timestamp: &Option<Timestamp>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match timestamp {
+        Some(timestamp) => serialize_timestamp(timestamp, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Stores written before only have the date `YYYY-MM-DD`, it is read as the
+/// start of that day in local time.
+fn parse_timestamp(raw: &str) -> Result<Timestamp, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(timestamp);
+    }
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .map_err(|err| format!("invalid timestamp [{raw}]: {err}"))?;
+    Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+        .map(|t| t.fixed_offset())
+        .ok_or_else(|| format!("invalid local time [{raw}]"))
+}
+
+fn deserialize_timestamp<'de, D>(deserializer: D) -> Result<Timestamp, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    parse_timestamp(&raw).map_err(D::Error::custom)
+}
+
+fn deserialize_opt_timestamp<'de, D>(
+    deserializer: D,
+) -> Result<Option<Timestamp>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = Option::<String>::deserialize(deserializer)?;
+    raw.map(|raw| parse_timestamp(&raw).map_err(D::Error::custom))
+        .transpose()
+}
+
 /// toml table key can't be a number or numeric string without quote
 fn serialize_todos<S>(
     todos: &HashMap<u32, Todo>,
@@ -161,3 +693,46 @@ where
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32) -> Timestamp {
+        Timestamp::parse_from_rfc3339(&format!("2025-06-{day:02}T09:00:00Z"))
+            .unwrap()
+    }
+
+    #[test]
+    fn remove_todos() {
+        // 1 > 2 (done), 3 > 4
+        let todo = |desc: &str, children: Option<Vec<u32>>, parent| {
+            Todo::new(desc.to_owned(), None, children, parent, at(1), None)
+        };
+        let mut todos = TodoMap::new();
+        todos.insert(1, todo("Release", Some(vec![2, 3]), None));
+        todos.insert(
+            2,
+            Todo::new(
+                "Tag".to_owned(),
+                None,
+                None,
+                Some(1),
+                at(1),
+                Some(at(2)),
+            ),
+        );
+        todos.insert(3, todo("Docs", Some(vec![4]), Some(1)));
+        todos.insert(4, todo("Proofread", None, Some(3)));
+
+        let removed = todos.remove_todos(&[3, 4], at(3));
+        assert_eq!(removed.len(), 2);
+        assert_eq!(todos[&1].children, Some(vec![2]));
+        // the only child left is done
+        assert_eq!(todos[&1].status(), Status::Done);
+
+        todos.remove_todos(&[2], at(3));
+        assert_eq!(todos[&1].children, None);
+        assert_eq!(todos.len(), 1);
+    }
+}
@@ -0,0 +1,326 @@
+use crate::model::{
+    Timestamp, TodoRef, deserialize_timestamp, serialize_timestamp,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, AddAssign},
+    str::FromStr,
+};
+
+/// Amount of time spent on (or planned for) a todo, in seconds.
+///
+/// Written as a sequence of `<N>d`, `<N>h`, `<N>m` and `<N>s`, e.g. `1h30m`
+/// or `45m`, a bare number is read as minutes. A day means a working day of
+/// [`WORKDAY_HOURS`] hours, so it is displayed in hours.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deserialize,
+    Serialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Effort(u64);
+
+/// Length of the day in an effort like `3d`.
+pub const WORKDAY_HOURS: u64 = 8;
+
+impl Effort {
+    pub fn from_secs(secs: u64) -> Self {
+        Self(secs)
+    }
+
+    pub fn as_secs(&self) -> u64 {
+        self.0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Time elapsed from `start` to `end`, zero if `end` is earlier.
+    pub fn between(start: &Timestamp, end: &Timestamp) -> Self {
+        Self(u64::try_from((*end - *start).num_seconds()).unwrap_or(0))
+    }
+}
+
+impl FromStr for Effort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, false)
+    }
+}
+
+impl Effort {
+    /// Parses a duration, one too large is an error unless `saturate` is set.
+    fn parse(s: &str, saturate: bool) -> Result<Self, String> {
+        let raw = s.trim().to_lowercase();
+        if raw.is_empty() {
+            return Err("empty duration, expected e.g. 1h30m, 45m".to_owned());
+        }
+        let too_large = || format!("duration [{s}] is too large");
+        let fit = |secs: Option<u64>| match secs {
+            Some(secs) => Ok(secs),
+            None if saturate => Ok(u64::MAX),
+            None => Err(too_large()),
+        };
+        if let Ok(minutes) = raw.parse::<u64>() {
+            return fit(minutes.checked_mul(60)).map(Self);
+        }
+
+        let mut secs = 0u64;
+        let mut rest = raw.as_str();
+        while !rest.is_empty() {
+            let split = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let (n, tail) = rest.split_at(split);
+            let n = n.parse::<u64>().map_err(|_| {
+                format!("invalid duration [{s}], expected e.g. 1h30m, 45m")
+            })?;
+            let unit = tail.chars().next().unwrap_or('m');
+            let scale = match unit {
+                'd' => WORKDAY_HOURS * 3600,
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => {
+                    return Err(format!(
+                        "unknown unit [{unit}] in [{s}], expected d, h, m or s"
+                    ));
+                }
+            };
+            secs = fit(n.checked_mul(scale).and_then(|n| secs.checked_add(n)))?;
+            rest = tail.get(unit.len_utf8()..).unwrap_or_default();
+        }
+        Ok(Self(secs))
+    }
+}
+
+/// Reads a stored duration, one too large is saturated rather than making
+/// the store unreadable.
+impl TryFrom<String> for Effort {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value, true)
+    }
+}
+
+impl From<Effort> for String {
+    fn from(value: Effort) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Effort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (h, m, s) = (self.0 / 3600, self.0 / 60 % 60, self.0 % 60);
+        if self.0 == 0 {
+            return write!(f, "0m");
+        }
+        if h > 0 {
+            write!(f, "{h}h")?;
+        }
+        if m > 0 {
+            write!(f, "{m}m")?;
+        }
+        if s > 0 {
+            write!(f, "{s}s")?;
+        }
+        Ok(())
+    }
+}
+
+impl Add for Effort {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Effort {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for Effort {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Time spent on a todo in one go, from a timer or logged manually.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TimeEntry {
+    #[serde(
+        serialize_with = "serialize_timestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub start: Timestamp,
+    pub effort: Effort,
+}
+
+/// The running timer of a store, there is at most one at a time.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Timer {
+    pub todo: TodoRef,
+    #[serde(
+        serialize_with = "serialize_timestamp",
+        deserialize_with = "deserialize_timestamp"
+    )]
+    pub start: Timestamp,
+}
+
+/// Planned effort of a todo, in time and/or story points.
+///
+/// Written as `2h`, `3d`, `5pt` or both joined by `+`, e.g. `1d + 3pt` which
+/// is what a sum over a subtree with mixed estimates looks like.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Estimate {
+    pub time: Effort,
+    pub points: u32,
+}
+
+impl Estimate {
+    pub fn is_zero(&self) -> bool {
+        self.time.is_zero() && self.points == 0
+    }
+}
+
+impl FromStr for Estimate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, false)
+    }
+}
+
+impl Estimate {
+    /// Parses an estimate, one too large is an error unless `saturate` is
+    /// set.
+    fn parse(s: &str, saturate: bool) -> Result<Self, String> {
+        let mut estimate = Self::default();
+        for part in s.split('+') {
+            let part = part.trim().to_lowercase();
+            let points = ["pts", "pt", "sp", "p"]
+                .iter()
+                .find_map(|suffix| part.strip_suffix(suffix));
+            match points {
+                Some(points) => {
+                    let points =
+                        points.trim().parse::<u32>().map_err(|_| {
+                            format!("invalid story points [{part}] in [{s}]")
+                        })?;
+                    estimate.points = match estimate.points.checked_add(points)
+                    {
+                        Some(points) => points,
+                        None if saturate => u32::MAX,
+                        None => {
+                            return Err(format!("estimate [{s}] is too large"));
+                        }
+                    };
+                }
+                None => estimate.time += Effort::parse(&part, saturate)?,
+            }
+        }
+        if estimate.is_zero() {
+            return Err(format!(
+                "empty estimate [{s}], expected e.g. 2h, 3d or 5pt"
+            ));
+        }
+        Ok(estimate)
+    }
+}
+
+/// Reads a stored estimate, one too large is saturated as a duration is.
+impl TryFrom<String> for Estimate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value, true)
+    }
+}
+
+impl From<Estimate> for String {
+    fn from(value: Estimate) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.time.is_zero(), self.points) {
+            (true, 0) => write!(f, "0m"),
+            (false, 0) => write!(f, "{}", self.time),
+            (true, points) => write!(f, "{points}pt"),
+            (false, points) => write!(f, "{} + {points}pt", self.time),
+        }
+    }
+}
+
+impl Add for Estimate {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            time: self.time + rhs.time,
+            points: self.points.saturating_add(rhs.points),
+        }
+    }
+}
+
+impl Sum for Estimate {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_effort() {
+        assert_eq!("90".parse(), Ok(Effort::from_secs(5400)));
+        assert_eq!("1h30m".parse(), Ok(Effort::from_secs(5400)));
+        assert!(
+            "9999999999999999h"
+                .parse::<Effort>()
+                .unwrap_err()
+                .ends_with("too large")
+        );
+        assert!("999999999999999999".parse::<Effort>().is_err());
+        assert!("1x".parse::<Effort>().is_err());
+    }
+
+    #[test]
+    fn read_stored() {
+        // a stored value too large is saturated, not an unreadable store
+        let stored = |s: &str| Effort::try_from(s.to_owned());
+        assert_eq!(
+            stored("9999999999999999h"),
+            Ok(Effort::from_secs(u64::MAX))
+        );
+        assert_eq!(stored("1h30m"), Ok(Effort::from_secs(5400)));
+        assert!(stored("1x").is_err());
+        let estimate =
+            Estimate::try_from("4294967295pt + 1pt".to_owned()).unwrap();
+        assert_eq!(estimate.points, u32::MAX);
+        assert!("4294967295pt + 1pt".parse::<Estimate>().is_err());
+    }
+}
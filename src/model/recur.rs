@@ -0,0 +1,333 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// Unit of a recurrence interval.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecurUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl RecurUnit {
+    fn name(&self) -> &'static str {
+        match self {
+            RecurUnit::Day => "day",
+            RecurUnit::Week => "week",
+            RecurUnit::Month => "month",
+            RecurUnit::Year => "year",
+        }
+    }
+}
+
+impl FromStr for RecurUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim_end_matches('s') {
+            "day" | "d" => Ok(RecurUnit::Day),
+            "week" | "w" => Ok(RecurUnit::Week),
+            "month" | "m" => Ok(RecurUnit::Month),
+            "year" | "y" => Ok(RecurUnit::Year),
+            _ => Err(format!("unknown recurrence unit [{s}]")),
+        }
+    }
+}
+
+/// Largest interval of a rule, in its unit.
+pub const MAX_EVERY: u32 = 1000;
+
+/// Recurrence rule of a todo.
+///
+/// Written as `[every|after] [N] UNIT [on WEEKDAY,...]`, e.g. `every 2 weeks
+/// on mon,thu` or `after 3 days`, with `daily`, `weekly`, `monthly` and
+/// `yearly` as shorthands. `every` keeps a fixed schedule from the planned
+/// date, `after` schedules the next occurrence from the completion date.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Recur {
+    pub every: u32,
+    pub unit: RecurUnit,
+    /// Days of a week the todo occurs on, only for daily and weekly rules.
+    pub weekdays: Option<Vec<Weekday>>,
+    pub after_completion: bool,
+}
+
+impl Recur {
+    /// Returns the date of the occurrence following `scheduled`, the planned
+    /// date of the current one, completed on `complete_at`.
+    ///
+    /// With a fixed schedule, missed occurrences are skipped so that the next
+    /// one falls after `complete_at`.
+    pub fn next(
+        &self,
+        scheduled: NaiveDate,
+        complete_at: NaiveDate,
+    ) -> NaiveDate {
+        if self.after_completion {
+            return self.step(complete_at);
+        }
+        let mut next = self.step(scheduled);
+        while next <= complete_at {
+            next = self.step(next);
+        }
+        next
+    }
+
+    /// Returns the occurrence right after `date`.
+    fn step(&self, date: NaiveDate) -> NaiveDate {
+        let Some(weekdays) = &self.weekdays else {
+            return match self.unit {
+                RecurUnit::Day => date + Days::new(self.every.into()),
+                RecurUnit::Week => date + Days::new(7 * u64::from(self.every)),
+                RecurUnit::Month => date + Months::new(self.every),
+                RecurUnit::Year => date + Months::new(12 * self.every),
+            };
+        };
+
+        let days = date.weekday().num_days_from_monday();
+        // later this week
+        if let Some(offset) = weekdays
+            .iter()
+            .map(|w| w.num_days_from_monday())
+            .filter(|w| *w > days)
+            .min()
+        {
+            return date + Days::new((offset - days).into());
+        }
+        // first one of the next (N-th) week
+        let weeks = match self.unit {
+            RecurUnit::Week => self.every,
+            _ => 1,
+        };
+        let first = weekdays
+            .iter()
+            .map(|w| w.num_days_from_monday())
+            .min()
+            .unwrap_or(0);
+        date - Days::new(days.into()) + Days::new((7 * weeks + first).into())
+    }
+}
+
+impl FromStr for Recur {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let recur = Self::parse(s, MAX_EVERY)?;
+        if recur.skips_days() {
+            return Err(format!(
+                "weekdays only apply to every day or every N weeks [{s}]"
+            ));
+        }
+        Ok(recur)
+    }
+}
+
+impl Recur {
+    /// Whether the rule has weekdays and an interval of several days, which
+    /// can't be scheduled together.
+    fn skips_days(&self) -> bool {
+        self.unit == RecurUnit::Day && self.weekdays.is_some() && self.every > 1
+    }
+
+    /// Parses a rule whose interval is at most `max_every`.
+    fn parse(s: &str, max_every: u32) -> Result<Self, String> {
+        let lower = s.trim().to_lowercase();
+        let (rule, weekdays) = match lower.split_once(" on ") {
+            Some((rule, weekdays)) => (rule, Some(weekdays)),
+            None => (lower.as_str(), None),
+        };
+
+        let mut words = rule.split_whitespace().peekable();
+        let after_completion = match words.peek() {
+            Some(&"after") => {
+                words.next();
+                true
+            }
+            Some(&"every") => {
+                words.next();
+                false
+            }
+            _ => false,
+        };
+
+        let (every, unit) = match words.collect::<Vec<_>>().as_slice() {
+            ["daily"] => (1, RecurUnit::Day),
+            ["weekly"] => (1, RecurUnit::Week),
+            ["monthly"] => (1, RecurUnit::Month),
+            ["yearly"] => (1, RecurUnit::Year),
+            [unit] => (1, unit.parse()?),
+            [every, unit] => (
+                every
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|n| (1..=max_every).contains(n))
+                    .ok_or_else(|| {
+                        format!(
+                            "invalid interval [{every}], expected 1 to {max_every}"
+                        )
+                    })?,
+                unit.parse()?,
+            ),
+            _ => return Err(format!("invalid recurrence [{s}]")),
+        };
+
+        let weekdays = match weekdays {
+            Some(_) if !matches!(unit, RecurUnit::Day | RecurUnit::Week) => {
+                return Err(format!(
+                    "weekdays only apply to daily or weekly recurrence [{s}]"
+                ));
+            }
+            Some(weekdays) => Some(
+                weekdays
+                    .split(',')
+                    .map(|w| {
+                        w.trim()
+                            .parse::<Weekday>()
+                            .map_err(|_| format!("invalid weekday [{w}]"))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
+        };
+
+        Ok(Self {
+            every,
+            unit,
+            weekdays,
+            after_completion,
+        })
+    }
+}
+
+/// Reads a stored rule, an interval written before it was bounded is
+/// clamped to [`MAX_EVERY`] rather than making the store unreadable, and
+/// one of several days with weekdays is read as every day.
+impl TryFrom<String> for Recur {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let mut recur = Self::parse(&value, u32::MAX)?;
+        if recur.every > MAX_EVERY {
+            warn!(target: "recur", "interval of [{value}] is clamped to {MAX_EVERY}");
+            recur.every = MAX_EVERY;
+        }
+        if recur.skips_days() {
+            warn!(target: "recur", "interval of [{value}] is ignored with weekdays");
+            recur.every = 1;
+        }
+        Ok(recur)
+    }
+}
+
+impl From<Recur> for String {
+    fn from(value: Recur) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Recur {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = if self.after_completion {
+            "after"
+        } else {
+            "every"
+        };
+        let unit = self.unit.name();
+        if self.every == 1 {
+            write!(f, "{mode} {unit}")?;
+        } else {
+            write!(f, "{mode} {} {unit}s", self.every)?;
+        }
+        if let Some(weekdays) = &self.weekdays {
+            let weekdays: Vec<_> = weekdays
+                .iter()
+                .map(|w| w.to_string().to_lowercase())
+                .collect();
+            write!(f, " on {}", weekdays.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn next(rule: &str, scheduled: &str, complete_at: &str) -> NaiveDate {
+        let recur: Recur = rule.parse().unwrap();
+        recur.next(date(scheduled), date(complete_at))
+    }
+
+    #[test]
+    fn parse() {
+        let recur: Recur = "Every 2 weeks on mon,thu".parse().unwrap();
+        assert_eq!(recur.every, 2);
+        assert_eq!(recur.unit, RecurUnit::Week);
+        assert_eq!(recur.weekdays, Some(vec![Weekday::Mon, Weekday::Thu]));
+        assert_eq!(recur.to_string(), "every 2 weeks on mon,thu");
+        assert_eq!("daily".parse::<Recur>().unwrap().to_string(), "every day");
+        assert!("every 0 days".parse::<Recur>().is_err());
+        assert!("every 1001 years".parse::<Recur>().is_err());
+        assert!("every 4294967295 years".parse::<Recur>().is_err());
+        assert!("monthly on mon".parse::<Recur>().is_err());
+        assert!("every 2 days on mon".parse::<Recur>().is_err());
+        let stored = Recur::try_from("every 2 days on mon".to_owned());
+        assert_eq!(stored.unwrap().to_string(), "every day on mon");
+    }
+
+    #[test]
+    fn weekdays() {
+        // later in the week, then the first weekday two weeks on
+        let rule = "every 2 weeks on mon,thu";
+        assert_eq!(next(rule, "2025-06-02", "2025-06-02"), date("2025-06-05"));
+        assert_eq!(next(rule, "2025-06-05", "2025-06-05"), date("2025-06-16"));
+        assert_eq!(
+            next("daily on sat,sun", "2025-06-08", "2025-06-08"),
+            date("2025-06-14")
+        );
+    }
+
+    #[test]
+    fn after_completion() {
+        let rule = "after 3 days";
+        assert_eq!(next(rule, "2025-06-01", "2025-06-10"), date("2025-06-13"));
+        assert_eq!(next(rule, "2025-06-10", "2025-06-01"), date("2025-06-04"));
+    }
+
+    #[test]
+    fn month_end() {
+        assert_eq!(
+            next("monthly", "2025-01-31", "2025-01-31"),
+            date("2025-02-28")
+        );
+        assert_eq!(
+            next("every 3 months", "2025-11-30", "2025-11-30"),
+            date("2026-02-28")
+        );
+        assert_eq!(
+            next("yearly", "2024-02-29", "2024-02-29"),
+            date("2025-02-28")
+        );
+    }
+
+    #[test]
+    fn missed_occurrences() {
+        // a fixed schedule skips the occurrences missed by a late completion
+        let rule = "every week";
+        assert_eq!(next(rule, "2025-06-02", "2025-06-20"), date("2025-06-23"));
+        assert_eq!(next(rule, "2025-06-02", "2025-06-09"), date("2025-06-16"));
+        assert_eq!(next(rule, "2025-06-02", "2025-05-30"), date("2025-06-09"));
+        assert_eq!(
+            next("every 2 weeks on mon,thu", "2025-06-02", "2025-06-12"),
+            date("2025-06-16")
+        );
+    }
+}
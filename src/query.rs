@@ -0,0 +1,681 @@
+use crate::{
+    date::{Tz, parse_date},
+    model::{Status, Todo, TodoMap},
+};
+use chrono::NaiveDate;
+use log::debug;
+use regex::{Regex, RegexBuilder};
+use std::{cmp::Ordering, collections::HashMap, fmt::Display};
+
+/// Hint appended to parse errors.
+const EXPECTED: &str = "expected FIELD:VALUE (status, tag, desc, link, id, parent, depth, created, completed, due), and, or, not, (...) or @SAVED_QUERY";
+
+/// A filter expression selecting todos.
+///
+/// Conditions are written `FIELD OP VALUE` and combined with `and`, `or`,
+/// `not` and parentheses, e.g.
+/// `status:open and (tag:bug or desc~"tls") and created>=-7d and depth<=2`.
+/// Adjacent conditions without an operator are joined by `and`, and
+/// `@NAME` stands for a saved query.
+///
+/// | Field                          | Value
+/// | :----------------------------- | :--------------------------------------
+/// | `status`                       | `open`, `closed` or statuses joined by `,`
+/// | `tag`, `desc`, `link`          | text, `:` contains it ignoring case
+/// | `id`, `parent`, `depth`        | number, depth of top level todos is 0
+/// | `created`, `completed`, `due`  | date expression, e.g. `2025-06-01`, `-7d`
+///
+/// Operators are `:`, `=`, `!=`, `<`, `<=`, `>` and `>=`, plus `~` to match
+/// text by a regular expression. `none` is the value of a missing `tag`,
+/// `link`, `parent`, `completed` or `due`.
+#[derive(Debug)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Cond(Cond),
+}
+
+/// A single `FIELD OP VALUE` condition of a [`Query`].
+#[derive(Debug)]
+pub struct Cond {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    Status,
+    Tag,
+    Desc,
+    Link,
+    Id,
+    Parent,
+    Depth,
+    Created,
+    Completed,
+    Due,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    /// `:`, contains for text, equals otherwise
+    Is,
+    /// `~`
+    Match,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug)]
+enum Value {
+    None,
+    Status(Vec<Status>),
+    /// lowercased for [`Op::Is`]
+    Text(String),
+    Regex(Regex),
+    Number(u32),
+    Date(NaiveDate),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Saved(String),
+    Cond {
+        field: String,
+        op: String,
+        value: String,
+    },
+}
+
+impl Query {
+    /// Parses `input` with dates relative to `today`, `@NAME` is looked up in
+    /// `saved`.
+    pub fn parse(
+        input: &str,
+        today: NaiveDate,
+        saved: &HashMap<String, String>,
+    ) -> Result<Self, String> {
+        Parser {
+            today,
+            saved,
+            expanding: Vec::new(),
+        }
+        .parse(input)
+    }
+
+    /// Tells whether todo `id` of `todos` matches, timestamps are split into
+    /// days in `tz`.
+    pub fn matches(&self, todos: &TodoMap, id: &u32, tz: Tz) -> bool {
+        match self {
+            Query::And(lhs, rhs) => {
+                lhs.matches(todos, id, tz) && rhs.matches(todos, id, tz)
+            }
+            Query::Or(lhs, rhs) => {
+                lhs.matches(todos, id, tz) || rhs.matches(todos, id, tz)
+            }
+            Query::Not(query) => !query.matches(todos, id, tz),
+            Query::Cond(cond) => todos
+                .get(id)
+                .is_some_and(|todo| cond.matches(todos, id, todo, tz)),
+        }
+    }
+
+    /// Returns the ids of the todos matching, in ascending order.
+    pub fn select(&self, todos: &TodoMap, tz: Tz) -> Vec<u32> {
+        let mut ids: Vec<_> = todos
+            .keys()
+            .filter(|id| self.matches(todos, id, tz))
+            .copied()
+            .collect();
+        ids.sort();
+        debug!(target: "query::select", "{ids:?}");
+        ids
+    }
+}
+
+impl Cond {
+    fn matches(&self, todos: &TodoMap, id: &u32, todo: &Todo, tz: Tz) -> bool {
+        match self.field {
+            Field::Status => {
+                let Value::Status(status) = &self.value else {
+                    return false;
+                };
+                status.contains(&todo.status()) == (self.op != Op::Ne)
+            }
+            Field::Tag => {
+                let tags = todo.tags.as_deref().unwrap_or_default();
+                match (&self.value, self.op) {
+                    (Value::None, op) => tags.is_empty() == (op != Op::Ne),
+                    (_, Op::Ne) => !tags.iter().any(|t| self.is_text(t)),
+                    _ => tags.iter().any(|t| self.is_text(t)),
+                }
+            }
+            Field::Desc => self.text(Some(&todo.desc)),
+            Field::Link => self.text(todo.link.as_deref()),
+            Field::Id => self.number(Some(*id)),
+            Field::Parent => self.number(todo.parent),
+            Field::Depth => {
+                self.number(u32::try_from(todos.ancestors(id).len()).ok())
+            }
+            Field::Created => self.date(Some(tz.date(&todo.create_at()))),
+            Field::Completed => {
+                self.date(todo.complete_at().map(|t| tz.date(&t)))
+            }
+            Field::Due => self.date(todo.assign_at),
+        }
+    }
+
+    /// Compares `text` with a text value, tags are compared as a whole.
+    fn is_text(&self, text: &str) -> bool {
+        match (&self.value, self.op) {
+            (Value::Regex(re), _) => re.is_match(text),
+            (Value::Text(value), Op::Is) if self.field != Field::Tag => {
+                text.to_lowercase().contains(value.as_str())
+            }
+            (Value::Text(value), Op::Is) => text.to_lowercase() == *value,
+            (Value::Text(value), _) => text == value,
+            _ => false,
+        }
+    }
+
+    fn text(&self, text: Option<&str>) -> bool {
+        match (text, &self.value, self.op) {
+            (text, Value::None, op) => text.is_none() == (op != Op::Ne),
+            (None, _, op) => op == Op::Ne,
+            (Some(text), _, Op::Ne) => !self.is_text(text),
+            (Some(text), _, _) => self.is_text(text),
+        }
+    }
+
+    fn number(&self, number: Option<u32>) -> bool {
+        match &self.value {
+            Value::Number(value) => _compare(self.op, number, Some(value)),
+            _ => _compare(self.op, number, None),
+        }
+    }
+
+    fn date(&self, date: Option<NaiveDate>) -> bool {
+        match &self.value {
+            Value::Date(value) => _compare(self.op, date, Some(value)),
+            _ => _compare(self.op, date, None),
+        }
+    }
+}
+
+/// Compares a field with the value of a condition, `None` on the right is the
+/// `none` value.
+fn _compare<T: Ord>(op: Op, field: Option<T>, value: Option<&T>) -> bool {
+    let (field, value) = match (field, value) {
+        (field, None) => return field.is_none() == (op != Op::Ne),
+        (None, Some(_)) => return op == Op::Ne,
+        (Some(field), Some(value)) => (field, value),
+    };
+    let ord = field.cmp(value);
+    match op {
+        Op::Is | Op::Eq | Op::Match => ord == Ordering::Equal,
+        Op::Ne => ord != Ordering::Equal,
+        Op::Lt => ord == Ordering::Less,
+        Op::Le => ord != Ordering::Greater,
+        Op::Gt => ord == Ordering::Greater,
+        Op::Ge => ord != Ordering::Less,
+    }
+}
+
+struct Parser<'a> {
+    today: NaiveDate,
+    saved: &'a HashMap<String, String>,
+    /// saved queries being expanded, to refuse cycles
+    expanding: Vec<String>,
+}
+
+impl Parser<'_> {
+    fn parse(&mut self, input: &str) -> Result<Query, String> {
+        let mut tokens = _tokenize(input)?;
+        tokens.reverse();
+        let query = self.or(&mut tokens)?;
+        match tokens.pop() {
+            None => Ok(query),
+            Some(token) => {
+                Err(format!("unexpected {token} in [{input}], {EXPECTED}"))
+            }
+        }
+    }
+
+    /// Tokens are in reverse order, the next one is at the end.
+    fn or(&mut self, tokens: &mut Vec<Token>) -> Result<Query, String> {
+        let mut query = self.and(tokens)?;
+        while tokens.last() == Some(&Token::Or) {
+            tokens.pop();
+            query = Query::Or(Box::new(query), Box::new(self.and(tokens)?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self, tokens: &mut Vec<Token>) -> Result<Query, String> {
+        let mut query = self.not(tokens)?;
+        loop {
+            match tokens.last() {
+                Some(Token::And) => {
+                    tokens.pop();
+                }
+                Some(Token::Or | Token::RParen) | None => break,
+                // conditions next to each other
+                Some(_) => {}
+            }
+            query = Query::And(Box::new(query), Box::new(self.not(tokens)?));
+        }
+        Ok(query)
+    }
+
+    fn not(&mut self, tokens: &mut Vec<Token>) -> Result<Query, String> {
+        if tokens.last() == Some(&Token::Not) {
+            tokens.pop();
+            return Ok(Query::Not(Box::new(self.not(tokens)?)));
+        }
+        self.primary(tokens)
+    }
+
+    fn primary(&mut self, tokens: &mut Vec<Token>) -> Result<Query, String> {
+        match tokens.pop() {
+            Some(Token::LParen) => {
+                let query = self.or(tokens)?;
+                match tokens.pop() {
+                    Some(Token::RParen) => Ok(query),
+                    _ => Err("missing closing parenthesis".to_owned()),
+                }
+            }
+            Some(Token::Saved(name)) => {
+                if self.expanding.contains(&name) {
+                    return Err(format!(
+                        "saved query [@{name}] refers to itself"
+                    ));
+                }
+                let input = self
+                    .saved
+                    .get(&name)
+                    .ok_or_else(|| format!("unknown saved query [@{name}]"))?;
+                debug!(target: "query::parse", "@{name} = {input}");
+                self.expanding.push(name);
+                let query = self.parse(input);
+                self.expanding.pop();
+                query
+            }
+            Some(Token::Cond { field, op, value }) => {
+                self.cond(&field, &op, &value).map(Query::Cond)
+            }
+            Some(token) => Err(format!("unexpected {token}, {EXPECTED}")),
+            None => Err(format!("unexpected end of query, {EXPECTED}")),
+        }
+    }
+
+    fn cond(&self, field: &str, op: &str, value: &str) -> Result<Cond, String> {
+        let field = match field.to_lowercase().as_str() {
+            "status" => Field::Status,
+            "tag" => Field::Tag,
+            "desc" => Field::Desc,
+            "link" => Field::Link,
+            "id" => Field::Id,
+            "parent" => Field::Parent,
+            "depth" => Field::Depth,
+            "created" => Field::Created,
+            "completed" => Field::Completed,
+            "due" => Field::Due,
+            _ => return Err(format!("unknown field [{field}], {EXPECTED}")),
+        };
+        let op = match op {
+            ":" => Op::Is,
+            "~" => Op::Match,
+            "=" => Op::Eq,
+            "!=" => Op::Ne,
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            ">" => Op::Gt,
+            ">=" => Op::Ge,
+            _ => return Err(format!("unknown operator [{op}]")),
+        };
+        let invalid_op =
+            || format!("operator [{op:?}] can't be used on [{field:?}]");
+        let is_none = value.eq_ignore_ascii_case("none")
+            && matches!(
+                field,
+                Field::Tag
+                    | Field::Link
+                    | Field::Parent
+                    | Field::Completed
+                    | Field::Due
+            );
+        if is_none && !matches!(op, Op::Is | Op::Eq | Op::Ne) {
+            return Err(invalid_op());
+        }
+
+        let value = match field {
+            _ if is_none => Value::None,
+            Field::Status => {
+                if !matches!(op, Op::Is | Op::Eq | Op::Ne) {
+                    return Err(invalid_op());
+                }
+                Value::Status(_parse_status(value)?)
+            }
+            Field::Tag | Field::Desc | Field::Link => match op {
+                Op::Match => Value::Regex(
+                    RegexBuilder::new(value)
+                        .build()
+                        .map_err(|err| format!("invalid regex: {err}"))?,
+                ),
+                Op::Is => Value::Text(value.to_lowercase()),
+                Op::Eq | Op::Ne => Value::Text(value.to_owned()),
+                _ => return Err(invalid_op()),
+            },
+            Field::Id | Field::Parent | Field::Depth => {
+                if op == Op::Match {
+                    return Err(invalid_op());
+                }
+                Value::Number(value.parse().map_err(|_| {
+                    format!("invalid number [{value}] for [{field:?}]")
+                })?)
+            }
+            Field::Created | Field::Completed | Field::Due => {
+                if op == Op::Match {
+                    return Err(invalid_op());
+                }
+                Value::Date(parse_date(value, self.today)?)
+            }
+        };
+        Ok(Cond { field, op, value })
+    }
+}
+
+fn _parse_status(value: &str) -> Result<Vec<Status>, String> {
+    let mut status = Vec::new();
+    for word in value.split(',') {
+        match word.trim().to_lowercase().as_str() {
+            "open" => status.extend(Status::OPEN),
+            "closed" => status.extend([Status::Done, Status::Cancelled]),
+            "todo" => status.push(Status::Todo),
+            "progress" => status.push(Status::Progress),
+            "waiting" => status.push(Status::Waiting),
+            "cancelled" => status.push(Status::Cancelled),
+            "done" => status.push(Status::Done),
+            _ => {
+                return Err(format!(
+                    "unknown status [{word}], expected open, closed, todo, progress, waiting, cancelled or done"
+                ));
+            }
+        }
+    }
+    Ok(status)
+}
+
+fn _is_op(c: char) -> bool {
+    matches!(c, ':' | '~' | '=' | '!' | '<' | '>')
+}
+
+fn _tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' {
+                Token::LParen
+            } else {
+                Token::RParen
+            });
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) =
+            chars.peek().filter(|c| !c.is_whitespace() && !_is_op(**c))
+        {
+            if c == '(' || c == ')' {
+                break;
+            }
+            word.push(c);
+            chars.next();
+        }
+        if chars.peek().is_none_or(|c| !_is_op(*c)) {
+            let token = match word.to_lowercase().as_str() {
+                "and" => Token::And,
+                "or" => Token::Or,
+                "not" => Token::Not,
+                _ => match word.strip_prefix('@') {
+                    Some(name) if !name.is_empty() => {
+                        Token::Saved(name.to_owned())
+                    }
+                    _ => {
+                        return Err(format!(
+                            "unknown word [{word}] in [{input}], {EXPECTED}"
+                        ));
+                    }
+                },
+            };
+            tokens.push(token);
+            continue;
+        }
+
+        let mut op = String::new();
+        while let Some(&c) = chars.peek().filter(|c| _is_op(**c)) {
+            op.push(c);
+            chars.next();
+        }
+
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            // only `\"` is unescaped, regexes keep their backslashes
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') if chars.peek() == Some(&'"') => {
+                        value.extend(chars.next());
+                    }
+                    Some(c) => value.push(c),
+                    None => {
+                        return Err(format!(
+                            "missing closing quote in [{input}]"
+                        ));
+                    }
+                }
+            }
+        } else {
+            while let Some(&c) =
+                chars.peek().filter(|c| !c.is_whitespace() && **c != ')')
+            {
+                value.push(c);
+                chars.next();
+            }
+        }
+        if word.is_empty() || value.is_empty() {
+            return Err(format!(
+                "incomplete condition [{word}{op}{value}] in [{input}], {EXPECTED}"
+            ));
+        }
+        tokens.push(Token::Cond {
+            field: word,
+            op,
+            value,
+        });
+    }
+    Ok(tokens)
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::And => write!(f, "and"),
+            Token::Or => write!(f, "or"),
+            Token::Not => write!(f, "not"),
+            Token::Saved(name) => write!(f, "@{name}"),
+            Token::Cond { field, op, value } => {
+                write!(f, "{field}{op}{value}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveTime};
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 11).unwrap()
+    }
+
+    fn utc() -> Tz {
+        "utc".parse().unwrap()
+    }
+
+    fn at(y: i32, m: u32, d: u32) -> crate::model::Timestamp {
+        let date = NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        DateTime::from_naive_utc_and_offset(
+            date.and_time(NaiveTime::MIN),
+            "+00:00".parse().unwrap(),
+        )
+    }
+
+    /// 1 "Release 1.0" (done) > 2 "Renew TLS cert" [infra, bug] > 3 "Audit"
+    fn todos() -> TodoMap {
+        let mut todos = TodoMap::new();
+        let mut release = Todo::new(
+            "Release 1.0".to_owned(),
+            None,
+            Some(vec![2]),
+            None,
+            at(2025, 5, 1),
+            Some(at(2025, 6, 10)),
+        );
+        release.tags = Some(vec!["release".to_owned()]);
+        let mut tls = Todo::new(
+            "Renew TLS cert".to_owned(),
+            Some("https://tls.example".to_owned()),
+            Some(vec![3]),
+            Some(1),
+            at(2025, 6, 8),
+            None,
+        );
+        tls.tags = Some(vec!["infra".to_owned(), "Bug".to_owned()]);
+        tls.assign_at = Some(today());
+        let audit = Todo::new(
+            "Audit".to_owned(),
+            None,
+            None,
+            Some(2),
+            at(2025, 6, 10),
+            None,
+        );
+        todos.insert(1, release);
+        todos.insert(2, tls);
+        todos.insert(3, audit);
+        todos
+    }
+
+    fn select(input: &str) -> Vec<u32> {
+        select_with(input, &HashMap::new())
+    }
+
+    fn select_with(input: &str, saved: &HashMap<String, String>) -> Vec<u32> {
+        Query::parse(input, today(), saved)
+            .unwrap()
+            .select(&todos(), utc())
+    }
+
+    fn error(input: &str) -> String {
+        Query::parse(input, today(), &HashMap::new()).unwrap_err()
+    }
+
+    #[test]
+    fn fields() {
+        assert_eq!(select("status:open"), [2, 3]);
+        assert_eq!(select("status:done,cancelled"), [1]);
+        assert_eq!(select("status!=done"), [2, 3]);
+        assert_eq!(select("tag:bug"), [2]);
+        assert_eq!(select("tag=bug"), Vec::<u32>::new());
+        assert_eq!(select("tag:none"), [3]);
+        assert_eq!(select("desc:tls"), [2]);
+        assert_eq!(select(r#"desc~"^R.*\d""#), [1]);
+        assert_eq!(select(r#"desc:"\"tls\"""#), Vec::<u32>::new());
+        assert_eq!(select("link:none"), [1, 3]);
+        assert_eq!(select("link!=none"), [2]);
+        assert_eq!(select("id>=2"), [2, 3]);
+        assert_eq!(select("parent:none"), [1]);
+        assert_eq!(select("depth<=1"), [1, 2]);
+        assert_eq!(select("depth:2"), [3]);
+        assert_eq!(select("due:today"), [2]);
+        assert_eq!(select("completed!=none"), [1]);
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(select("created>=-7d"), [2, 3]);
+        assert_eq!(select("created<2025-06-08"), [1]);
+        assert_eq!(select("completed:yesterday"), [1]);
+        // todos without the date don't compare
+        assert_eq!(select("due<tomorrow"), [2]);
+    }
+
+    #[test]
+    fn operators() {
+        let all = [1, 2, 3];
+        assert_eq!(
+            select(
+                r#"status:open and (tag:bug or desc~"tls") and created>=-7d and depth<=2"#
+            ),
+            [2]
+        );
+        assert_eq!(select("status:open depth:2"), [3]);
+        assert_eq!(select("not status:open"), [1]);
+        assert_eq!(select("tag:release or depth:2"), [1, 3]);
+        // `and` before `or`
+        assert_eq!(select("id:1 or id:2 and id:3"), [1]);
+        assert_eq!(select("(id:1 or id:2) and not id:1"), [2]);
+        assert_eq!(select("NOT not id:1 OR id>0"), all);
+    }
+
+    #[test]
+    fn saved_queries() {
+        let saved = HashMap::from([
+            ("open".to_owned(), "status:open".to_owned()),
+            ("bugs".to_owned(), "@open and tag:bug".to_owned()),
+            ("loop".to_owned(), "id:1 or @loop".to_owned()),
+        ]);
+        assert_eq!(select_with("@bugs", &saved), [2]);
+        assert_eq!(select_with("not @open", &saved), [1]);
+        let err = Query::parse("@loop", today(), &saved).unwrap_err();
+        assert!(err.contains("refers to itself"), "{err}");
+        let err = Query::parse("@nope", today(), &saved).unwrap_err();
+        assert!(err.contains("unknown saved query"), "{err}");
+    }
+
+    #[test]
+    fn errors() {
+        assert!(error("owner:me").starts_with("unknown field [owner]"));
+        assert!(error("status:open and").contains("unexpected end"));
+        assert!(error("(status:open").contains("missing closing"));
+        assert!(error("status:open)").starts_with("unexpected )"));
+        assert!(error("desc:\"tls").contains("missing closing quote"));
+        assert!(error("tls").starts_with("unknown word [tls]"));
+        assert!(error("status<done").starts_with("operator"));
+        assert!(error("id:one").starts_with("invalid number"));
+        assert!(error("due>none").starts_with("operator"));
+        assert!(error("desc~(").starts_with("invalid regex"));
+    }
+}
@@ -0,0 +1,193 @@
+//! Comments starting with a keyword such as `TODO:` in source files.
+//!
+//! The comment syntax is picked from the extension (or the name) of a file,
+//! a keyword only counts at the start of a comment: `// TODO: fix`,
+//! `# FIXME(bob) flaky` or `<!-- HACK: -->`, not `// see the TODO list`.
+
+use std::path::Path;
+
+const C_LIKE: &[&str] = &["//", "/*", "*"];
+const C_LIKE_HASH: &[&str] = &["//", "/*", "*", "#"];
+const HASH: &[&str] = &["#"];
+const DASHES: &[&str] = &["--"];
+const SEMICOLON: &[&str] = &[";"];
+const PERCENT: &[&str] = &["%"];
+const MARKUP: &[&str] = &["<!--"];
+const MARKUP_SCRIPT: &[&str] = &["<!--", "//", "/*", "*"];
+
+/// A comment found in a file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Comment {
+    /// Line number, from 1.
+    pub line: usize,
+    pub keyword: String,
+    /// Text after the keyword, empty if there is none.
+    pub text: String,
+}
+
+/// Returns the markers starting a comment in the file at `path`. `None` if
+/// its syntax isn't known.
+pub fn markers(path: &Path) -> Option<&'static [&'static str]> {
+    let name = path.file_name()?.to_str()?;
+    if matches!(name, "Makefile" | "Dockerfile" | "Rakefile" | "Gemfile") {
+        return Some(HASH);
+    }
+    let markers = match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cxx" | "java" | "kt"
+        | "kts" | "scala" | "swift" | "go" | "js" | "jsx" | "mjs" | "ts"
+        | "tsx" | "cs" | "dart" | "css" | "scss" | "less" | "proto" | "zig" => {
+            C_LIKE
+        }
+        "php" => C_LIKE_HASH,
+        "py" | "sh" | "bash" | "zsh" | "fish" | "rb" | "pl" | "r" | "yaml"
+        | "yml" | "toml" | "conf" | "cmake" | "nix" | "ex" | "exs" | "tf"
+        | "ps1" | "jl" => HASH,
+        "sql" | "lua" | "hs" | "elm" | "ada" => DASHES,
+        "lisp" | "el" | "clj" | "cljs" | "scm" | "asm" | "ini" => SEMICOLON,
+        "tex" | "erl" => PERCENT,
+        "html" | "htm" | "xml" | "md" | "markdown" => MARKUP,
+        "vue" | "svelte" => MARKUP_SCRIPT,
+        _ => return None,
+    };
+    Some(markers)
+}
+
+/// Finds the comments of `content` starting with one of `keywords`, which
+/// are matched case-sensitively.
+pub fn comments(
+    content: &str,
+    markers: &[&str],
+    keywords: &[String],
+) -> Vec<Comment> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(n, line)| {
+            let (keyword, text) = _parse_line(line, markers, keywords)?;
+            Some(Comment {
+                line: n + 1,
+                keyword,
+                text,
+            })
+        })
+        .collect()
+}
+
+/// Reads the first comment of `line` which starts with a keyword. Markers
+/// found inside strings are tried as well, e.g. `"http://"`.
+fn _parse_line(
+    line: &str,
+    markers: &[&str],
+    keywords: &[String],
+) -> Option<(String, String)> {
+    let mut starts: Vec<_> = markers
+        .iter()
+        .flat_map(|marker| {
+            let found: Vec<_> = if *marker == "*" {
+                // the continuation of a block comment, only at the start
+                let trimmed = line.trim_start();
+                trimmed
+                    .strip_prefix('*')
+                    .filter(|rest| !rest.starts_with('/'))
+                    .map(|rest| line.len() - rest.len())
+                    .into_iter()
+                    .collect()
+            } else {
+                line.match_indices(marker)
+                    .map(|(i, _)| i + marker.len())
+                    .collect()
+            };
+            found
+        })
+        .collect();
+    starts.sort();
+    starts.dedup();
+
+    starts.into_iter().find_map(|start| {
+        let comment = line[start..]
+            .trim_start_matches(['/', '*', '#', ';', '-', '%', '!', '<'])
+            .trim_start();
+        keywords.iter().find_map(|keyword| {
+            let rest = comment.strip_prefix(keyword.as_str())?;
+            if !rest.is_empty() && !rest.starts_with([':', '(', ' ', '\t']) {
+                return None;
+            }
+            // an author or an issue, `TODO(bob):`
+            let rest = match rest.strip_prefix('(') {
+                Some(rest) => rest.split_once(')').map_or(rest, |(_, r)| r),
+                None => rest,
+            };
+            let text = rest
+                .trim_start_matches(':')
+                .trim()
+                .trim_end_matches("*/")
+                .trim_end_matches("-->")
+                .trim();
+            Some((keyword.clone(), text.to_owned()))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords() -> Vec<String> {
+        ["TODO", "FIXME", "HACK"].map(str::to_owned).to_vec()
+    }
+
+    fn parse(line: &str, markers: &[&str]) -> Option<(String, String)> {
+        _parse_line(line, markers, &keywords())
+    }
+
+    #[test]
+    fn parse_lines() {
+        let some = |keyword: &str, text: &str| {
+            Some((keyword.to_owned(), text.to_owned()))
+        };
+        assert_eq!(parse("// TODO: fix it", C_LIKE), some("TODO", "fix it"));
+        assert_eq!(
+            parse("    /// FIXME flaky", C_LIKE),
+            some("FIXME", "flaky")
+        );
+        assert_eq!(
+            parse("let x = 1; /* HACK(bob): skip */", C_LIKE),
+            some("HACK", "skip")
+        );
+        assert_eq!(parse(" * TODO wrap", C_LIKE), some("TODO", "wrap"));
+        assert_eq!(
+            parse(r#"get("http://x"); // TODO retry"#, C_LIKE),
+            some("TODO", "retry")
+        );
+        assert_eq!(parse("# TODO", HASH), some("TODO", ""));
+        assert_eq!(parse("x = 1  # FIXME: typo", HASH), some("FIXME", "typo"));
+        assert_eq!(parse("-- TODO index", DASHES), some("TODO", "index"));
+        assert_eq!(parse("<!-- TODO: docs -->", MARKUP), some("TODO", "docs"));
+        assert_eq!(parse("// see the TODO list", C_LIKE), None);
+        assert_eq!(parse("// TODOS", C_LIKE), None);
+        assert_eq!(parse("// todo: lower", C_LIKE), None);
+        assert_eq!(parse("let todo = \"TODO\";", C_LIKE), None);
+        assert_eq!(parse("# TODO in rust", C_LIKE), None);
+    }
+
+    #[test]
+    fn find_comments() {
+        let content = "fn main() {\n    // TODO: args\n}\n# FIXME no\n";
+        assert_eq!(
+            comments(
+                content,
+                markers(Path::new("src/main.rs")).unwrap(),
+                &keywords()
+            ),
+            [Comment {
+                line: 2,
+                keyword: "TODO".to_owned(),
+                text: "args".to_owned(),
+            }]
+        );
+        assert_eq!(markers(Path::new("Makefile")), Some(HASH));
+        assert_eq!(markers(Path::new("a.PY")), Some(HASH));
+        assert_eq!(markers(Path::new("image.png")), None);
+        assert_eq!(markers(Path::new("README")), None);
+    }
+}
@@ -0,0 +1,248 @@
+use crate::{
+    context::Context,
+    model::TodoMap,
+    ui::form::ConfirmUI,
+    util::{SyncBlock, check_key, get_query},
+};
+use anyhow::{Result, anyhow, bail};
+use clap::Args;
+use log::debug;
+use std::{fmt::Display, str::FromStr};
+
+/// Hint appended to parse errors.
+const EXPECTED: &str =
+    "expected an id (3), a range (3-10), children (4/*) or a subtree (4/**)";
+
+/// Todos of a list picked by ids, ranges or subtrees, and/or a query.
+#[derive(Debug, Args)]
+pub struct SelectionOptions {
+    /// Todo ids, ranges like 3-10, children like 4/* or whole subtrees like
+    /// 4/**
+    #[arg(
+        value_name = "TODO_ID",
+        value_delimiter = ',',
+        required_unless_present = "query"
+    )]
+    pub todo_id: Vec<Selector>,
+    /// Select todos matching QUERY, e.g. `status:open and tag:bug`. Along with
+    /// TODO_ID, only the matching ones of them are selected
+    #[arg(short, long, value_name = "QUERY")]
+    pub query: Option<String>,
+    /// Don't ask for confirmation before changing more than the given ids
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// A way to pick todos of a list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Selector {
+    /// `3` or `#3`
+    Id(u32),
+    /// `3-10`, ids in between that exist
+    Range(u32, u32),
+    /// `4/*`, the direct children of a todo
+    Children(u32),
+    /// `4/**`, all the descendants of a todo
+    Descendants(u32),
+}
+
+impl SelectionOptions {
+    /// Whether more than the ids given one by one can be selected.
+    pub fn is_bulk(&self) -> bool {
+        self.query.is_some()
+            || self.todo_id.iter().any(|s| !matches!(s, Selector::Id(_)))
+    }
+
+    /// Returns the selected todos of `list`, after a confirmation listing
+    /// them if the selection is bulk and `--yes` is missing. `None` if the
+    /// user declines.
+    ///
+    /// `action` completes "N todo(s) to be ...".
+    pub fn select(
+        &self,
+        ctx: &Context,
+        list: &String,
+        action: &str,
+    ) -> Result<Option<Vec<u32>>> {
+        let todos = ctx.store.todos(list)?;
+        let mut ids = Vec::new();
+        for selector in &self.todo_id {
+            for id in selector.select(todos)? {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        if let Some(query) = get_query(self.query.as_deref(), ctx)? {
+            let tz = ctx.config.timezone();
+            if self.todo_id.is_empty() {
+                ids = query.select(todos, tz);
+            } else {
+                ids.retain(|id| query.matches(todos, id, tz));
+            }
+        }
+        debug!(target: "selection::select", "todo [{ids:?}]");
+        if ids.is_empty() {
+            bail!("no todo is selected");
+        }
+        if !self.is_bulk() || self.yes {
+            return Ok(Some(ids));
+        }
+
+        println!("{} todo(s) to be {action}:", ids.len());
+        for id in &ids {
+            let todo = ctx.store.todo_by_id(list, id)?;
+            println!("{}{list}#{id} {}", todo.status().marker(), todo.desc);
+        }
+        let rt = SyncBlock::new()?;
+        let confirmed =
+            rt.block_on(ConfirmUI::run("Proceed?")).map_err(|err| {
+                anyhow!(
+                    "can't ask for confirmation ({err}), use --yes to skip it"
+                )
+            })?;
+        debug!(target: "selection::select", "confirmed: {confirmed}");
+        Ok(confirmed.then_some(ids))
+    }
+}
+
+impl Selector {
+    /// Returns the ids picked in `todos`, in ascending order for ranges and
+    /// in the order of the hierarchy for subtrees.
+    pub fn select(&self, todos: &TodoMap) -> Result<Vec<u32>> {
+        match *self {
+            Selector::Id(id) => {
+                check_key(todos, &id)?;
+                Ok(vec![id])
+            }
+            Selector::Range(start, end) => {
+                let mut ids: Vec<_> = todos
+                    .keys()
+                    .filter(|id| (start..=end).contains(*id))
+                    .copied()
+                    .collect();
+                ids.sort();
+                Ok(ids)
+            }
+            Selector::Children(id) => {
+                check_key(todos, &id)?;
+                Ok(todos[&id].children.clone().unwrap_or_default())
+            }
+            Selector::Descendants(id) => {
+                check_key(todos, &id)?;
+                let mut ids = Vec::new();
+                _descendants(todos, id, &mut ids);
+                ids.retain(|ch_id| *ch_id != id);
+                Ok(ids)
+            }
+        }
+    }
+}
+
+/// Pushes the descendants of todo `id` depth first.
+fn _descendants(todos: &TodoMap, id: u32, ids: &mut Vec<u32>) {
+    for ch_id in todos
+        .get(&id)
+        .and_then(|t| t.children.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        // a broken store could link children in a cycle
+        if !ids.contains(ch_id) {
+            ids.push(*ch_id);
+            _descendants(todos, *ch_id, ids);
+        }
+    }
+}
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let raw = s.trim();
+        let parse_id = |id: &str| {
+            id.strip_prefix('#')
+                .unwrap_or(id)
+                .parse::<u32>()
+                .map_err(|_| format!("invalid selection [{s}], {EXPECTED}"))
+        };
+        if let Some(id) = raw.strip_suffix("/**") {
+            return parse_id(id).map(Selector::Descendants);
+        }
+        if let Some(id) = raw.strip_suffix("/*") {
+            return parse_id(id).map(Selector::Children);
+        }
+        if let Some((start, end)) = raw.split_once('-') {
+            let (start, end) = (parse_id(start)?, parse_id(end)?);
+            if start > end {
+                return Err(format!("empty range [{s}], {EXPECTED}"));
+            }
+            return Ok(Selector::Range(start, end));
+        }
+        parse_id(raw).map(Selector::Id)
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Selector::Id(id) => write!(f, "{id}"),
+            Selector::Range(start, end) => write!(f, "{start}-{end}"),
+            Selector::Children(id) => write!(f, "{id}/*"),
+            Selector::Descendants(id) => write!(f, "{id}/**"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Todo;
+    use chrono::Local;
+
+    /// 1 > (2 > 4, 3), 5
+    fn todos() -> TodoMap {
+        let now = Local::now().fixed_offset();
+        let todo = |children: Option<Vec<u32>>, parent: Option<u32>| {
+            Todo::new(String::new(), None, children, parent, now, None)
+        };
+        let mut todos = TodoMap::new();
+        todos.insert(1, todo(Some(vec![2, 3]), None));
+        todos.insert(2, todo(Some(vec![4]), Some(1)));
+        todos.insert(3, todo(None, Some(1)));
+        todos.insert(4, todo(None, Some(2)));
+        todos.insert(5, todo(None, None));
+        todos
+    }
+
+    fn select(input: &str) -> Vec<u32> {
+        input.parse::<Selector>().unwrap().select(&todos()).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("3".parse(), Ok(Selector::Id(3)));
+        assert_eq!("#3".parse(), Ok(Selector::Id(3)));
+        assert_eq!("3-10".parse(), Ok(Selector::Range(3, 10)));
+        assert_eq!("4/*".parse(), Ok(Selector::Children(4)));
+        assert_eq!("#4/**".parse(), Ok(Selector::Descendants(4)));
+        assert!("10-3".parse::<Selector>().unwrap_err().starts_with("empty"));
+        assert!(
+            "a-3"
+                .parse::<Selector>()
+                .unwrap_err()
+                .starts_with("invalid")
+        );
+        assert!("4/".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn select_todos() {
+        assert_eq!(select("3"), [3]);
+        assert_eq!(select("2-9"), [2, 3, 4, 5]);
+        assert_eq!(select("1/*"), [2, 3]);
+        assert_eq!(select("1/**"), [2, 4, 3]);
+        assert_eq!(select("5/**"), Vec::<u32>::new());
+        assert!("9".parse::<Selector>().unwrap().select(&todos()).is_err());
+    }
+}
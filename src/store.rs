@@ -1,28 +1,47 @@
-use crate::model::{Todo, TodoMap};
+use crate::model::{
+    Timestamp, Todo, TodoMap, TodoRef,
+    effort::{Effort, TimeEntry, Timer},
+};
 use crate::{config::Config, util::check_key};
-use anyhow::Result;
-use log::debug;
+use anyhow::{Result, anyhow, bail};
+use log::{debug, warn};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::{
     collections::HashMap,
-    fmt::Debug,
+    fmt::{Debug, Display},
     fs,
     path::{Path, PathBuf},
 };
 
 type Lists = HashMap<String, TodoMap>;
 
+/// Keys of the store file next to the lists, which can't name a list.
+const RESERVED_KEYS: [&str; 2] = ["default_list", "timer"];
+
+/// Fails if `name` is a key of the store file which can't name a list.
+pub fn check_list_name(name: &str) -> Result<()> {
+    if RESERVED_KEYS.contains(&name) {
+        bail!("[{name}] is reserved, use another list name");
+    }
+    Ok(())
+}
+
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Store {
     default_list: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timer: Option<Timer>,
     #[serde(
         flatten,
         serialize_with = "serialize_lists",
         deserialize_with = "deserialize_lists"
     )]
     lists: Lists,
+    /// Writes are held back until [`Store::commit`], e.g. during a batch
+    #[serde(skip)]
+    deferred: bool,
 }
 
 impl Store {
@@ -74,6 +93,166 @@ impl Store {
         Ok(todos_mut.get_mut(todo_id).unwrap())
     }
 
+    /// Returns the todo referenced by `todo_ref`, relative to `list`.
+    pub fn todo_by_ref(&self, list: &str, todo_ref: &TodoRef) -> Option<&Todo> {
+        self.lists.get(todo_ref.list_or(list))?.get(&todo_ref.id)
+    }
+
+    /// Returns the blockers of todo `todo_id` which are still open. Blockers
+    /// that no longer exist (removed or cleaned up) don't block anymore.
+    pub fn open_blockers(
+        &self,
+        list: &String,
+        todo_id: &u32,
+    ) -> Result<Vec<TodoRef>> {
+        debug!(target: "store::open_blockers", "list [{list}]");
+        debug!(target: "store::open_blockers", "todo [{todo_id}]");
+        let todo = self.todo_by_id(list, todo_id)?;
+        Ok(todo
+            .depends_on
+            .iter()
+            .flatten()
+            .filter(|r| {
+                self.todo_by_ref(list, r)
+                    .is_some_and(|blocker| blocker.status().is_open())
+            })
+            .cloned()
+            .collect())
+    }
+
+    /// Returns whether todo `from` depends on todo `to`, directly or through
+    /// other todos. Both references must be resolved.
+    pub fn depends_on(&self, from: &TodoRef, to: &TodoRef) -> bool {
+        debug!(target: "store::depends_on", "{from} -> {to}");
+        let mut visited = HashSet::new();
+        let mut stack = vec![from.clone()];
+        while let Some(current) = stack.pop() {
+            if current == *to {
+                return true;
+            }
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            let list = current.list_or("").to_owned();
+            let Some(todo) = self.todo_by_ref(&list, &current) else {
+                continue;
+            };
+            stack.extend(
+                todo.depends_on
+                    .iter()
+                    .flatten()
+                    .map(|r| r.clone().resolve(&list)),
+            );
+        }
+        false
+    }
+
+    /// Points references to todos of list `from` moved to list `to`, with
+    /// their new ids in `moved`, at their new place: blockers of every list
+    /// and the timer.
+    pub fn retarget(
+        &mut self,
+        from: &str,
+        to: &str,
+        moved: &HashMap<u32, u32>,
+    ) {
+        let retarget = |r: &mut TodoRef, list: &str| {
+            if r.list_or(list) != from {
+                return;
+            }
+            if let Some(new_id) = moved.get(&r.id) {
+                debug!(target: "store::retarget", "{r} -> {to}#{new_id}");
+                *r = TodoRef {
+                    list: Some(to.to_owned()),
+                    id: *new_id,
+                };
+            }
+        };
+        for (list, todos) in self.lists.iter_mut() {
+            for todo in todos.values_mut() {
+                for r in todo.depends_on.iter_mut().flatten() {
+                    retarget(r, list);
+                }
+            }
+        }
+        if let Some(timer) = self.timer.as_mut() {
+            retarget(&mut timer.todo, "");
+        }
+    }
+
+    /// Drops references to the todos of list `list` which are `removed`:
+    /// blockers of every list and the timer. Ids are reused, so that a stale
+    /// reference would point at an unrelated todo later on.
+    pub fn forget(&mut self, list: &str, removed: impl Fn(&u32) -> bool) {
+        let is_removed = |r: &TodoRef, in_list: &str| {
+            r.list_or(in_list) == list && removed(&r.id)
+        };
+        for (in_list, todos) in self.lists.iter_mut() {
+            for todo in todos.values_mut() {
+                let Some(refs) = todo.depends_on.as_mut() else {
+                    continue;
+                };
+                refs.retain(|r| {
+                    let stale = is_removed(r, in_list);
+                    if stale {
+                        debug!(target: "store::forget", "{r} of {in_list}");
+                    }
+                    !stale
+                });
+                if refs.is_empty() {
+                    todo.depends_on = None;
+                }
+            }
+        }
+        if self.timer.as_ref().is_some_and(|t| is_removed(&t.todo, "")) {
+            warn!(target: "store::forget", "the timer of a removed todo is dropped");
+            self.timer = None;
+        }
+    }
+
+    pub fn timer(&self) -> Option<&Timer> {
+        self.timer.as_ref()
+    }
+
+    /// Starts the timer on `todo`, which must be resolved. The timer running
+    /// before, if any, is stopped first and returned as [`Store::stop_timer`]
+    /// does.
+    pub fn start_timer(
+        &mut self,
+        todo: TodoRef,
+        now: Timestamp,
+    ) -> Option<(TodoRef, Effort)> {
+        debug!(target: "store::start_timer", "timer on {todo} at {now}");
+        let stopped = self.stop_timer(now);
+        self.timer = Some(Timer { todo, start: now });
+        stopped
+    }
+
+    /// Stops the running timer and logs the elapsed time on its todo.
+    ///
+    /// Returns the todo and the elapsed time, `None` if no timer is running.
+    pub fn stop_timer(&mut self, now: Timestamp) -> Option<(TodoRef, Effort)> {
+        let Timer { todo, start } = self.timer.take()?;
+        let effort = Effort::between(&start, &now);
+        debug!(target: "store::stop_timer", "timer on {todo}: {effort}");
+        let list = todo.list_or("").to_owned();
+        match self
+            .lists
+            .get_mut(&list)
+            .and_then(|todos| todos.get_mut(&todo.id))
+        {
+            Some(_) if effort.is_zero() => {}
+            Some(timed) => timed
+                .time_log
+                .get_or_insert_with(Vec::new)
+                .push(TimeEntry { start, effort }),
+            None => {
+                warn!(target: "store::stop_timer", "todo {todo} no longer exists, {effort} is discarded");
+            }
+        }
+        Some((todo, effort))
+    }
+
     fn max_id(&self, list: &String) -> Result<&u32> {
         debug!(target: "store::max_id", "list [{list}]");
         Ok(self.todos(list)?.keys().max().unwrap_or(&0))
@@ -122,7 +301,9 @@ impl Store {
         debug!(target: "store::create", "initialized lists: {lists:#?}");
         Self {
             default_list,
+            timer: None,
             lists,
+            deferred: false,
         }
     }
 
@@ -132,29 +313,69 @@ impl Store {
     ) -> Result<Self> {
         debug!(target: "store::read", "store at: {path:?}");
         debug!(target: "store::read", "config: {config:#?}");
-        let content = fs::read_to_string(path)?;
+        let content = fs::read_to_string(&path)?;
         debug!(target: "store::read", "file content: {content}");
 
-        // create a new store if the file is empty
-        Ok(toml::from_str(&content).unwrap_or_else(|_| {
+        // create a new store only if the file is empty, a store which can't
+        // be read must not be replaced by the next write
+        if content.trim().is_empty() {
             let new =
                 Store::create(config.default_list().to_owned(), Vec::new());
             debug!(target: "store::read", "create new store: {new:#?}");
-            new
-        }))
+            return Ok(new);
+        }
+        let invalid = |err: &dyn Display| {
+            anyhow!("invalid store [{}]: {err}", path.as_ref().display())
+        };
+        let mut table = toml::from_str::<toml::Table>(&content)
+            .map_err(|err| invalid(&err))?;
+        let renamed = _rename_timer_list(&mut table);
+        let mut store = toml::Value::Table(table)
+            .try_into::<Store>()
+            .map_err(|err| invalid(&err))?;
+        if let Some((from, to)) = renamed {
+            let ids: HashMap<_, _> =
+                store.todos(&to)?.keys().map(|id| (*id, *id)).collect();
+            store.retarget(&from, &to, &ids);
+        }
+        Ok(store)
     }
 
+    /// Writes the store to `path`, unless writes are deferred.
+    ///
+    /// The file is replaced at once by renaming a temporary file next to it,
+    /// so that an interrupted write leaves the old store intact.
     pub fn write(&self, path: &PathBuf) -> Result<()> {
+        if self.deferred {
+            debug!(target: "store::write", "deferred: {path:?}");
+            return Ok(());
+        }
         debug!(target: "store::write", "store at: {path:?}");
-        fs::write(path, toml::to_string(self)?)?;
+        let mut tmp = path.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, toml::to_string(self)?)?;
+        fs::rename(&tmp, path)?;
         Ok(())
     }
+
+    /// Holds back writes until [`Store::commit`].
+    pub fn defer_writes(&mut self) {
+        self.deferred = true;
+    }
+
+    /// Writes the store to `path` once writes have been deferred.
+    pub fn commit(&mut self, path: &PathBuf) -> Result<()> {
+        self.deferred = false;
+        self.write(path)
+    }
 }
 
 impl Debug for Store {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Store")
             .field("default_list", &self.default_list)
+            .field("timer", &self.timer)
+            .field("deferred", &self.deferred)
             .field(
                 "lists",
                 &self
@@ -167,11 +388,37 @@ impl Debug for Store {
     }
 }
 
+/// Renames a list named `timer`, from a store written before the timer was
+/// kept next to the lists, to a free name.
+///
+/// Returns the old and the new name of the list if it is renamed.
+fn _rename_timer_list(table: &mut toml::Table) -> Option<(String, String)> {
+    let from = "timer".to_owned();
+    let is_list = table
+        .get(&from)
+        .and_then(toml::Value::as_table)
+        .is_some_and(|timer| !timer.contains_key("start"));
+    if !is_list {
+        return None;
+    }
+    let mut to = format!("{from}-list");
+    while table.contains_key(&to) {
+        to.push('_');
+    }
+    warn!(target: "store::read", "list [{from}] is renamed to [{to}], the name is reserved");
+    let list = table.remove(&from)?;
+    table.insert(to.clone(), list);
+    if table.get("default_list").and_then(toml::Value::as_str) == Some(&from) {
+        table.insert("default_list".to_owned(), to.clone().into());
+    }
+    Some((from, to))
+}
+
 fn serialize_lists<S>(lists: &Lists, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
 {
-    let sorted: BTreeMap<_, _> = lists.iter().map(|(k, v)| (k, v)).collect();
+    let sorted: BTreeMap<_, _> = lists.iter().collect();
     let mut map = serializer.serialize_map(Some(sorted.len()))?;
     for (k, v) in sorted {
         map.serialize_entry(k, &v)?;
@@ -185,3 +432,87 @@ where
 {
     HashMap::deserialize(deserializer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::recur::{MAX_EVERY, Recur};
+
+    #[test]
+    fn read() {
+        let dir = std::env::temp_dir()
+            .join(format!("taso-store-read-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(".todo");
+        let config = Config::default();
+
+        // only an empty file is a new store
+        fs::write(&path, " \n").unwrap();
+        assert!(Store::read(&path, &config).is_ok());
+        fs::write(&path, "default_list = [").unwrap();
+        let err = Store::read(&path, &config).unwrap_err().to_string();
+        assert!(err.starts_with("invalid store ["), "{err}");
+        assert!(err.contains(".todo]"), "{err}");
+
+        // an interval written before it was bounded is clamped
+        let mut store = Store::create("default".to_owned(), Vec::new());
+        let mut todo = Todo::new(
+            "Water plants".to_owned(),
+            None,
+            None,
+            None,
+            Timestamp::parse_from_rfc3339("2025-06-01T09:00:00Z").unwrap(),
+            None,
+        );
+        let mut recur: Recur = "every 2 days".parse().unwrap();
+        recur.every = 2000;
+        todo.recur = Some(recur);
+        store
+            .todos_mut(&"default".to_owned())
+            .unwrap()
+            .insert(1, todo);
+        store.write(&path).unwrap();
+        let store = Store::read(&path, &config).unwrap();
+        let todos = store.todos(&"default".to_owned()).unwrap();
+        assert_eq!(todos[&1].recur.as_ref().unwrap().every, MAX_EVERY);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn forget() {
+        let mut store =
+            Store::create("default".to_owned(), vec!["work".to_owned()]);
+        let mut todo = Todo::new(
+            "Deploy".to_owned(),
+            None,
+            None,
+            None,
+            Timestamp::parse_from_rfc3339("2025-06-01T09:00:00Z").unwrap(),
+            None,
+        );
+        todo.depends_on = Some(vec![
+            "#3".parse().unwrap(),
+            "#5".parse().unwrap(),
+            "work#5".parse().unwrap(),
+        ]);
+        store
+            .todos_mut(&"default".to_owned())
+            .unwrap()
+            .insert(4, todo);
+
+        store.forget("default", |id| *id == 5);
+        let todos = store.todos(&"default".to_owned()).unwrap();
+        let refs: Vec<_> = todos[&4]
+            .depends_on
+            .iter()
+            .flatten()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(refs, ["#3", "work#5"]);
+
+        store.forget("work", |_| true);
+        store.forget("default", |id| *id == 3);
+        let todos = store.todos(&"default".to_owned()).unwrap();
+        assert_eq!(todos[&4].depends_on, None);
+    }
+}
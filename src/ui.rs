@@ -1,2 +1,5 @@
+pub mod board;
+pub mod calendar;
+pub mod chart;
 pub mod form;
 pub mod tree;
@@ -0,0 +1,251 @@
+use anyhow::Result;
+use promkit::{
+    Prompt, Signal, async_trait,
+    core::{
+        Pane,
+        crossterm::{
+            self,
+            event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+            style::{Attribute, ContentStyle, Stylize},
+        },
+        grapheme::StyledGraphemes,
+        render::{Renderer, SharedRenderer},
+    },
+};
+
+/// A todo on the board.
+#[derive(Clone, Debug)]
+pub struct Card {
+    pub id: u32,
+    pub desc: String,
+    pub blocked: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Index {
+    Title = 0,
+    Board = 1,
+    Tip = 2,
+}
+
+pub struct BoardUI;
+
+impl BoardUI {
+    /// Displays titled `columns` of cards side by side and lets the user
+    /// move cards between them.
+    ///
+    /// Returns the cards moved with the index of their new column, `None` if
+    /// the changes are discarded.
+    pub async fn run(
+        title: &str,
+        columns: Vec<(String, Vec<Card>)>,
+    ) -> Result<Option<Vec<(u32, usize)>>> {
+        Board {
+            renderer: None,
+            title: title.to_owned(),
+            columns,
+            col: 0,
+            row: 0,
+            moved: Vec::new(),
+            save: false,
+        }
+        .run()
+        .await
+    }
+}
+
+struct Board {
+    renderer: Option<SharedRenderer<Index>>,
+    title: String,
+    columns: Vec<(String, Vec<Card>)>,
+    col: usize,
+    row: usize,
+    moved: Vec<(u32, usize)>,
+    save: bool,
+}
+
+#[async_trait::async_trait]
+impl Prompt for Board {
+    async fn initialize(&mut self) -> anyhow::Result<()> {
+        let size = crossterm::terminal::size()?;
+        self.renderer = Some(SharedRenderer::new(
+            Renderer::try_new_with_panes(self.panes(size.0, size.1), true)
+                .await?,
+        ));
+        Ok(())
+    }
+
+    /// | Key                          | Action
+    /// | :--------------------------- | :------------------------------
+    /// | <kbd>h</kbd> <kbd>l</kbd>    | Select the previous/next column
+    /// | <kbd>k</kbd> <kbd>j</kbd>    | Select the previous/next card
+    /// | <kbd>H</kbd> <kbd>L</kbd>    | Move the card to the previous/next column
+    /// | <kbd>Enter</kbd> <kbd>q</kbd> | Save the changes and exit
+    /// | <kbd>Esc</kbd>               | Discard the changes and exit
+    /// | <kbd>Ctrl + C</kbd>          | Interrupt the current operation
+    async fn evaluate(&mut self, event: &Event) -> anyhow::Result<Signal> {
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        {
+            match (code, *modifiers) {
+                (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                    return Err(anyhow::anyhow!("ctrl+c"));
+                }
+                (KeyCode::Enter | KeyCode::Char('q'), _) => {
+                    self.save = true;
+                    return Ok(Signal::Quit);
+                }
+                (KeyCode::Esc, _) => return Ok(Signal::Quit),
+                (KeyCode::Char('h') | KeyCode::Left, _) => {
+                    self.select(self.col.saturating_sub(1), self.row);
+                }
+                (KeyCode::Char('l') | KeyCode::Right, _) => {
+                    self.select(self.col + 1, self.row);
+                }
+                (KeyCode::Char('k') | KeyCode::Up, _) => {
+                    self.select(self.col, self.row.saturating_sub(1));
+                }
+                (KeyCode::Char('j') | KeyCode::Down, _) => {
+                    self.select(self.col, self.row + 1);
+                }
+                (KeyCode::Char('H'), _) => {
+                    self.move_card(self.col.checked_sub(1));
+                }
+                (KeyCode::Char('L'), _) => {
+                    self.move_card(Some(self.col + 1));
+                }
+                _ => {}
+            }
+        }
+
+        let size = crossterm::terminal::size()?;
+        let panes = self.panes(size.0, size.1);
+        match self.renderer.as_ref() {
+            Some(renderer) => renderer.update(panes).render().await?,
+            None => return Err(anyhow::anyhow!("Renderer not initialized")),
+        }
+        Ok(Signal::Continue)
+    }
+
+    type Return = Option<Vec<(u32, usize)>>;
+
+    fn finalize(&mut self) -> anyhow::Result<Self::Return> {
+        Ok(self.save.then(|| std::mem::take(&mut self.moved)))
+    }
+}
+
+impl Board {
+    /// Selects the card at `row` of column `col`, or the closest one.
+    fn select(&mut self, col: usize, row: usize) {
+        self.col = col.min(self.columns.len().saturating_sub(1));
+        let len = self.columns.get(self.col).map_or(0, |(_, c)| c.len());
+        self.row = row.min(len.saturating_sub(1));
+    }
+
+    /// Moves the selected card to the end of column `to`.
+    fn move_card(&mut self, to: Option<usize>) {
+        let Some(to) = to.filter(|to| *to < self.columns.len()) else {
+            return;
+        };
+        if self.row >= self.columns[self.col].1.len() {
+            return;
+        }
+        let card = self.columns[self.col].1.remove(self.row);
+        self.moved.retain(|(id, _)| *id != card.id);
+        self.moved.push((card.id, to));
+        self.columns[to].1.push(card);
+        let row = self.columns[to].1.len() - 1;
+        self.select(to, row);
+    }
+
+    fn panes(&self, width: u16, height: u16) -> [(Index, Pane); 3] {
+        let cols = self.columns.len().max(1);
+        let cell = (width as usize / cols).max(8);
+        let title = StyledGraphemes::from_str(
+            &self.title,
+            ContentStyle::new().attribute(Attribute::Bold),
+        );
+        let tip = StyledGraphemes::from_str(
+            "hjkl select, H/L move card, Enter save, Esc discard",
+            ContentStyle::new().dark_grey(),
+        );
+
+        let mut rows = vec![StyledGraphemes::from_iter(
+            self.columns.iter().map(|(column, cards)| {
+                StyledGraphemes::from_str(
+                    _fit(&format!("{column} ({})", cards.len()), cell),
+                    ContentStyle::new().bold().underlined(),
+                )
+            }),
+        )];
+
+        // scroll so that the selected card stays visible
+        let lines = (height as usize).saturating_sub(4).max(1);
+        let start = self.row.saturating_sub(lines - 1);
+        let depth =
+            self.columns.iter().map(|(_, c)| c.len()).max().unwrap_or(0);
+        for row in (start..depth).take(lines) {
+            rows.push(StyledGraphemes::from_iter(
+                self.columns.iter().enumerate().map(|(col, (_, cards))| {
+                    let Some(card) = cards.get(row) else {
+                        return StyledGraphemes::from(" ".repeat(cell));
+                    };
+                    let blocked = if card.blocked {
+                        "⊘ "
+                    } else {
+                        ""
+                    };
+                    let text = _fit(
+                        &format!("#{} {blocked}{}", card.id, card.desc),
+                        cell,
+                    );
+                    let style = if (col, row) == (self.col, self.row) {
+                        ContentStyle::new().reverse()
+                    } else if card.blocked {
+                        ContentStyle::new().red()
+                    } else {
+                        ContentStyle::default()
+                    };
+                    StyledGraphemes::from_str(text, style)
+                }),
+            ));
+        }
+
+        [
+            (Index::Title, Pane::new(vec![title], 0)),
+            (Index::Board, Pane::new(rows, 0)),
+            (Index::Tip, Pane::new(vec![tip], 0)),
+        ]
+    }
+}
+
+/// Cuts or pads `text` to `width` columns of display width, leaving a space
+/// as the gap between columns.
+fn _fit(text: &str, width: usize) -> String {
+    let max = width.saturating_sub(1);
+    let graphemes = StyledGraphemes::from(text);
+    let (mut fitted, mut used) = (String::new(), 0);
+    if graphemes.widths() <= max {
+        fitted.push_str(text);
+        used = graphemes.widths();
+    } else if max > 0 {
+        // leave a column to the ellipsis
+        for (ch, grapheme) in
+            graphemes.chars().into_iter().zip(graphemes.iter())
+        {
+            if used + grapheme.width() > max - 1 {
+                break;
+            }
+            fitted.push(ch);
+            used += grapheme.width();
+        }
+        fitted.push('…');
+        used += 1;
+    }
+    fitted.push_str(&" ".repeat(width.saturating_sub(used)));
+    fitted
+}
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use promkit::{
+    Prompt, Signal, async_trait,
+    core::{
+        Pane,
+        crossterm::{
+            event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+            style::{Attribute, ContentStyle, Stylize},
+        },
+        grapheme::StyledGraphemes,
+        render::{Renderer, SharedRenderer},
+    },
+};
+use std::collections::HashMap;
+
+/// Width of a day in the month grid.
+const CELL: usize = 11;
+
+/// Todos due, created and completed on a day.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DayCount {
+    pub due: usize,
+    pub created: usize,
+    pub completed: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Index {
+    Grid = 0,
+    Tip = 1,
+}
+
+pub struct CalendarUI;
+
+impl CalendarUI {
+    /// Displays the month of `cursor` and lets the user pick a day.
+    ///
+    /// Returns the picked day, `None` if the calendar is closed without one.
+    pub async fn run(
+        cursor: NaiveDate,
+        today: NaiveDate,
+        counts: HashMap<NaiveDate, DayCount>,
+    ) -> Result<Option<NaiveDate>> {
+        Calendar {
+            renderer: None,
+            cursor,
+            today,
+            counts,
+            picked: None,
+        }
+        .run()
+        .await
+    }
+}
+
+struct Calendar {
+    renderer: Option<SharedRenderer<Index>>,
+    cursor: NaiveDate,
+    today: NaiveDate,
+    counts: HashMap<NaiveDate, DayCount>,
+    picked: Option<NaiveDate>,
+}
+
+#[async_trait::async_trait]
+impl Prompt for Calendar {
+    async fn initialize(&mut self) -> anyhow::Result<()> {
+        self.renderer = Some(SharedRenderer::new(
+            Renderer::try_new_with_panes(self.panes(), true).await?,
+        ));
+        Ok(())
+    }
+
+    /// | Key                          | Action
+    /// | :--------------------------- | :------------------------------
+    /// | <kbd>←</kbd> <kbd>h</kbd>    | Previous day
+    /// | <kbd>→</kbd> <kbd>l</kbd>    | Next day
+    /// | <kbd>↑</kbd> <kbd>k</kbd>    | Previous week
+    /// | <kbd>↓</kbd> <kbd>j</kbd>    | Next week
+    /// | <kbd>PageUp</kbd> <kbd><</kbd>  | Previous month
+    /// | <kbd>PageDown</kbd> <kbd>></kbd> | Next month
+    /// | <kbd>Enter</kbd>             | List todos of the day
+    /// | <kbd>q</kbd> <kbd>Esc</kbd>  | Exit the calendar
+    /// | <kbd>Ctrl + C</kbd>          | Interrupt the current operation
+    async fn evaluate(&mut self, event: &Event) -> anyhow::Result<Signal> {
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event
+        else {
+            return self.render().await.map(|_| Signal::Continue);
+        };
+
+        let cursor = self.cursor;
+        self.cursor = match (code, *modifiers) {
+            (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                return Err(anyhow::anyhow!("ctrl+c"));
+            }
+            (KeyCode::Enter, _) => {
+                self.picked = Some(cursor);
+                return Ok(Signal::Quit);
+            }
+            (KeyCode::Char('q') | KeyCode::Esc, _) => {
+                return Ok(Signal::Quit);
+            }
+            (KeyCode::Left | KeyCode::Char('h'), _) => cursor.pred_opt(),
+            (KeyCode::Right | KeyCode::Char('l'), _) => cursor.succ_opt(),
+            (KeyCode::Up | KeyCode::Char('k'), _) => {
+                cursor.checked_sub_days(Days::new(7))
+            }
+            (KeyCode::Down | KeyCode::Char('j'), _) => {
+                cursor.checked_add_days(Days::new(7))
+            }
+            (KeyCode::PageUp | KeyCode::Char('<'), _) => {
+                cursor.checked_sub_months(Months::new(1))
+            }
+            (KeyCode::PageDown | KeyCode::Char('>'), _) => {
+                cursor.checked_add_months(Months::new(1))
+            }
+            _ => None,
+        }
+        .unwrap_or(cursor);
+
+        self.render().await?;
+        Ok(Signal::Continue)
+    }
+
+    type Return = Option<NaiveDate>;
+
+    fn finalize(&mut self) -> anyhow::Result<Self::Return> {
+        Ok(self.picked)
+    }
+}
+
+impl Calendar {
+    fn panes(&self) -> [(Index, Pane); 2] {
+        let tip = StyledGraphemes::from_str(
+            "←↓↑→ move, </> month, Enter list todos, q quit",
+            ContentStyle::new().dark_grey(),
+        );
+        [
+            (
+                Index::Grid,
+                Pane::new(
+                    grid(
+                        self.cursor,
+                        Some(self.cursor),
+                        self.today,
+                        &self.counts,
+                    ),
+                    0,
+                ),
+            ),
+            (Index::Tip, Pane::new(vec![tip], 0)),
+        ]
+    }
+
+    async fn render(&mut self) -> anyhow::Result<()> {
+        let panes = self.panes();
+        match self.renderer.as_ref() {
+            Some(renderer) => renderer.update(panes).render().await,
+            None => Err(anyhow::anyhow!("Renderer not initialized")),
+        }
+    }
+}
+
+/// Draws the month of `date` as a grid, two lines per week: day numbers, then
+/// the todos due (`!`), created (`+`) and completed (`✔`) on each day.
+pub fn grid(
+    date: NaiveDate,
+    cursor: Option<NaiveDate>,
+    today: NaiveDate,
+    counts: &HashMap<NaiveDate, DayCount>,
+) -> Vec<StyledGraphemes> {
+    let first = date.with_day(1).unwrap_or(date);
+    let mut rows = vec![
+        StyledGraphemes::from_str(
+            format!("{:^width$}", first.format("%B %Y"), width = CELL * 7),
+            ContentStyle::new().attribute(Attribute::Bold),
+        ),
+        StyledGraphemes::from_str(
+            (0..7)
+                .map(|n| {
+                    let weekday = Weekday::try_from(n as u8).unwrap();
+                    format!("{:<CELL$}", weekday.to_string())
+                })
+                .collect::<String>(),
+            ContentStyle::new().dim(),
+        ),
+    ];
+
+    let offset = first.weekday().num_days_from_monday() as usize;
+    let days: Vec<_> = first
+        .iter_days()
+        .take_while(|d| d.month() == first.month())
+        .collect();
+    let mut cells: Vec<Option<NaiveDate>> = vec![None; offset];
+    cells.extend(days.into_iter().map(Some));
+
+    for week in cells.chunks(7) {
+        let mut numbers = Vec::new();
+        let mut marks = Vec::new();
+        for day in week {
+            let Some(day) = day else {
+                numbers.push(StyledGraphemes::from(" ".repeat(CELL)));
+                marks.push(StyledGraphemes::from(" ".repeat(CELL)));
+                continue;
+            };
+            let style = if Some(*day) == cursor {
+                ContentStyle::new().reverse()
+            } else if *day == today {
+                ContentStyle::new().bold().underlined()
+            } else {
+                ContentStyle::default()
+            };
+            numbers.push(StyledGraphemes::from_iter([
+                StyledGraphemes::from_str(format!("{:>2}", day.day()), style),
+                StyledGraphemes::from(" ".repeat(CELL - 2)),
+            ]));
+            marks.push(_marks(counts.get(day).copied().unwrap_or_default()));
+        }
+        rows.push(StyledGraphemes::from_iter(numbers));
+        rows.push(StyledGraphemes::from_iter(marks));
+    }
+    rows
+}
+
+fn _marks(count: DayCount) -> StyledGraphemes {
+    let mut marks = Vec::new();
+    let mut width = 0;
+    for (n, symbol, style) in [
+        (count.due, "!", ContentStyle::new().red()),
+        (count.created, "+", ContentStyle::new().blue()),
+        (count.completed, "✔", ContentStyle::new().green()),
+    ] {
+        if n == 0 {
+            continue;
+        }
+        let mark = format!("{symbol}{n} ");
+        width += mark.chars().count();
+        marks.push(StyledGraphemes::from_str(mark, style));
+    }
+    marks.push(StyledGraphemes::from(
+        " ".repeat(CELL.saturating_sub(width)),
+    ));
+    StyledGraphemes::from_iter(marks)
+}
@@ -0,0 +1,189 @@
+//! Unicode block art for reports printed in the terminal.
+
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One character per value, scaled to the largest one. Zero is drawn as a
+/// space so that empty days stand out.
+pub fn sparkline(values: &[usize]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|v| match *v {
+            0 => ' ',
+            v => LEVELS[v * (LEVELS.len() - 1) / max],
+        })
+        .collect()
+}
+
+/// Horizontal bar of `value` out of `max` in `width` columns, with eighths of
+/// a column for the remainder.
+pub fn bar(value: usize, max: usize, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    let eighths = value * width * 8 / max;
+    let mut bar = "█".repeat(eighths / 8);
+    match eighths % 8 {
+        0 => {}
+        rest => bar.push(['▏', '▎', '▍', '▌', '▋', '▊', '▉'][rest - 1]),
+    }
+    bar
+}
+
+/// Vertical chart with one column per value and `height` rows, top row first.
+///
+/// Columns are filled with `█` up to `values`, in eighths of a row, and with
+/// `░` from there up to `behind` (e.g. the scope of a burnup chart). `·`
+/// marks `guide` (e.g. the ideal burndown) in cells left empty.
+pub fn columns(
+    values: &[usize],
+    behind: Option<&[usize]>,
+    guide: Option<&[f64]>,
+    max: usize,
+    height: usize,
+) -> Vec<String> {
+    let max = max.max(1);
+    (0..height)
+        .rev()
+        .map(|row| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| {
+                    let fill = (v * height * 8 / max).saturating_sub(row * 8);
+                    if fill >= 8 {
+                        return '█';
+                    }
+                    if fill > 0 {
+                        return LEVELS[fill - 1];
+                    }
+                    let behind = behind
+                        .and_then(|b| b.get(i))
+                        .map_or(0, |b| (b * height).div_ceil(max));
+                    if row < behind {
+                        return '░';
+                    }
+                    let on_guide =
+                        guide.and_then(|g| g.get(i)).is_some_and(|g| {
+                            (g * height as f64 / max as f64).floor() as usize
+                                == row
+                        });
+                    if on_guide {
+                        '·'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A series of a [`svg_chart`].
+pub struct Line<'a> {
+    pub label: &'a str,
+    pub color: &'a str,
+    pub values: &'a [f64],
+    pub dashed: bool,
+}
+
+/// Renders `lines` as a standalone SVG line chart, `start` and `end` label
+/// the first and the last point of the x-axis.
+pub fn svg_chart(
+    title: &str,
+    start: &str,
+    end: &str,
+    lines: &[Line],
+) -> String {
+    const WIDTH: f64 = 640.0;
+    const HEIGHT: f64 = 360.0;
+    const LEFT: f64 = 50.0;
+    const RIGHT: f64 = 20.0;
+    const TOP: f64 = 40.0;
+    const BOTTOM: f64 = 60.0;
+    let plot_w = WIDTH - LEFT - RIGHT;
+    let plot_h = HEIGHT - TOP - BOTTOM;
+
+    let max = lines
+        .iter()
+        .flat_map(|l| l.values.iter().copied())
+        .fold(0.0, f64::max)
+        .ceil()
+        .max(1.0);
+    let points = lines.iter().map(|l| l.values.len()).max().unwrap_or(0);
+    let x = |i: usize| {
+        LEFT + plot_w * i as f64 / points.saturating_sub(1).max(1) as f64
+    };
+    let y = |v: f64| TOP + plot_h * (1.0 - v / max);
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}" font-family="sans-serif" font-size="12">
+<rect width="100%" height="100%" fill="white"/>
+<text x="{}" y="24" text-anchor="middle" font-size="16">{}</text>
+"#,
+        WIDTH / 2.0,
+        _escape(title)
+    );
+
+    // axes with ticks at 0, half and max
+    for tick in [0.0, max / 2.0, max] {
+        svg.push_str(&format!(
+            r##"<line x1="{LEFT}" y1="{0:.1}" x2="{1}" y2="{0:.1}" stroke="#ddd"/>
+<text x="{2}" y="{3:.1}" text-anchor="end">{tick}</text>
+"##,
+            y(tick),
+            WIDTH - RIGHT,
+            LEFT - 6.0,
+            y(tick) + 4.0,
+        ));
+    }
+    svg.push_str(&format!(
+        r##"<line x1="{LEFT}" y1="{TOP}" x2="{LEFT}" y2="{0}" stroke="#333"/>
+<line x1="{LEFT}" y1="{0}" x2="{1}" y2="{0}" stroke="#333"/>
+<text x="{LEFT}" y="{2}" text-anchor="start">{3}</text>
+<text x="{1}" y="{2}" text-anchor="end">{4}</text>
+"##,
+        TOP + plot_h,
+        WIDTH - RIGHT,
+        TOP + plot_h + 18.0,
+        _escape(start),
+        _escape(end),
+    ));
+
+    for (n, line) in lines.iter().enumerate() {
+        let points: Vec<_> = line
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| format!("{:.1},{:.1}", x(i), y(*v)))
+            .collect();
+        let dash = if line.dashed {
+            r#" stroke-dasharray="6 4""#
+        } else {
+            ""
+        };
+        let legend_x = LEFT + 140.0 * n as f64;
+        let legend_y = HEIGHT - 16.0;
+        svg.push_str(&format!(
+            r#"<polyline fill="none" stroke="{0}" stroke-width="2"{dash} points="{1}"/>
+<line x1="{legend_x}" y1="{2}" x2="{3}" y2="{2}" stroke="{0}" stroke-width="2"{dash}/>
+<text x="{4}" y="{legend_y}">{5}</text>
+"#,
+            line.color,
+            points.join(" "),
+            legend_y - 4.0,
+            legend_x + 20.0,
+            legend_x + 26.0,
+            _escape(line.label),
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn _escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
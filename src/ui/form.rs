@@ -3,7 +3,7 @@ use anyhow::Result;
 use promkit::{
     Prompt,
     core::crossterm::style::{ContentStyle, Stylize},
-    preset::readline::Readline,
+    preset::{confirm::Confirm, readline::Readline},
 };
 
 trait InitialTextEx {
@@ -35,16 +35,12 @@ pub struct ModifyUI;
 
 impl ModifyUI {
     pub async fn run(
-        old_desc: &String,
+        old_desc: &str,
         old_link: &Option<String>,
     ) -> Result<(Option<String>, Option<String>)> {
-        let new_desc_raw = readline_render(
-            old_desc.as_str(),
-            "Change to > ",
-            Some(old_desc.as_str()),
-            false,
-        )
-        .await?;
+        let new_desc_raw =
+            readline_render(old_desc, "Change to > ", Some(old_desc), false)
+                .await?;
         let new_desc = new_desc_raw.into_option();
 
         let Some(old_link_raw) = old_link else {
@@ -69,6 +65,16 @@ impl ModifyUI {
     }
 }
 
+pub struct ConfirmUI;
+
+impl ConfirmUI {
+    /// Asks `question`, returns whether the answer is yes.
+    pub async fn run(question: &str) -> Result<bool> {
+        let answer = Confirm::new(question).run().await?;
+        Ok(matches!(answer.as_str(), "y" | "Y" | "yes"))
+    }
+}
+
 async fn readline_render<T: AsRef<str>>(
     title: T,
     prefix: T,
@@ -88,7 +94,7 @@ async fn readline_render<T: AsRef<str>>(
     if empty {
         rl.run().await
     } else {
-        rl.validator(|t| t.len() > 0, |_| String::from("Cannot be empty"))
+        rl.validator(|t| !t.is_empty(), |_| String::from("Cannot be empty"))
             .run()
             .await
     }
@@ -1,10 +1,14 @@
 use crate::{
-    model::{Todo, TodoMap},
-    ui::tree::{hack::TodoText, preset_tree::Tree, widget_node::Node},
+    model::{Status, Todo, TodoMap, local_time},
+    ui::tree::{
+        hack::{Progress, TodoText},
+        preset_tree::Tree,
+        widget_node::Node,
+    },
 };
 use anyhow::Result;
 use promkit::Prompt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 pub mod hack;
 pub mod preset_evaluate;
@@ -21,19 +25,27 @@ pub struct TreeUI;
 
 impl TreeUI {
     pub async fn run(
-        title: &String,
-        root_id: &String,
+        title: &str,
+        root_id: &str,
         todos: &TodoMap,
+        shown: &HashSet<u32>,
+        blocked: &HashSet<u32>,
         tree_line: u8,
     ) -> Result<Vec<String>> {
         Tree::new(Node::NonLeaf {
             id: TodoText {
-                id: root_id.clone(),
+                id: root_id.to_owned(),
+                list: None,
                 desc: String::new(),
                 link: None,
                 complete: None,
+                status: Status::Todo,
+                progress: None,
+                blocked: false,
+                spent: None,
+                estimate: None,
             },
-            children: build_tree(todos),
+            children: build_tree(todos, shown, blocked),
             children_visible: true,
         })
         .title(title)
@@ -44,11 +56,79 @@ impl TreeUI {
     }
 }
 
-fn build_tree(todos: &TodoMap) -> Vec<Node> {
+pub struct AgendaUI;
+
+impl AgendaUI {
+    /// Displays `sections` as headings with their todos below.
+    pub async fn run(
+        title: &str,
+        sections: Vec<(String, Vec<TodoText>)>,
+        tree_line: u8,
+    ) -> Result<Vec<String>> {
+        let heading = |desc: String| TodoText {
+            id: String::new(),
+            list: None,
+            desc,
+            link: None,
+            complete: None,
+            status: Status::Todo,
+            progress: None,
+            blocked: false,
+            spent: None,
+            estimate: None,
+        };
+        let children = sections
+            .into_iter()
+            .map(|(title, todos)| Node::NonLeaf {
+                id: heading(title),
+                children: todos.into_iter().map(Node::Leaf).collect(),
+                children_visible: true,
+            })
+            .collect();
+        Tree::new(Node::NonLeaf {
+            id: heading(String::new()),
+            children,
+            children_visible: true,
+        })
+        .title(title)
+        .tree_lines(tree_line as usize)
+        .evaluator(|event, ctx| Box::pin(preset_evaluate::default(event, ctx)))
+        .run()
+        .await
+    }
+}
+
+/// Collects `shown` todos, along with all their ancestors so that a matched
+/// todo is still displayed in its place of the hierarchy.
+fn visible_ids(todos: &TodoMap, shown: &HashSet<u32>) -> HashSet<u32> {
+    let mut visible = HashSet::new();
+    for (id, todo) in todos.iter() {
+        if !shown.contains(id) {
+            continue;
+        }
+        let mut current = Some((*id, todo));
+        while let Some((id, todo)) = current {
+            if !visible.insert(id) {
+                break;
+            }
+            current = todo
+                .parent
+                .and_then(|pa_id| todos.get(&pa_id).map(|pa| (pa_id, pa)));
+        }
+    }
+    visible
+}
+
+fn build_tree(
+    todos: &TodoMap,
+    shown: &HashSet<u32>,
+    blocked: &HashSet<u32>,
+) -> Vec<Node> {
+    let visible = visible_ids(todos, shown);
     let mut nodes = HashMap::new();
     let top_nodes: Vec<_> = todos
         .iter()
-        .filter(|(_, todo)| todo.parent.is_none())
+        .filter(|(id, todo)| todo.parent.is_none() && visible.contains(id))
         .collect();
 
     for (top_id, top_todo) in top_nodes {
@@ -56,25 +136,30 @@ fn build_tree(todos: &TodoMap) -> Vec<Node> {
         stack.push((top_id, top_todo, StackState::Pending));
         while let Some((id, todo, state)) = stack.pop() {
             match state {
-                StackState::Pending => match &todo.children {
-                    Some(ch_ids) => {
-                        stack.push((id, todo, StackState::Ready));
-                        for ch_id in ch_ids {
-                            stack.push((
-                                &ch_id,
-                                todos.get(ch_id).unwrap(),
-                                StackState::Pending,
-                            ));
-                        }
-                    }
-                    None => {
-                        let leaf = Node::Leaf(desc(id, &todo));
+                StackState::Pending => {
+                    let ch_ids = visible_children(todo, &visible);
+                    if ch_ids.is_empty() {
+                        let leaf = Node::Leaf(todo_text(
+                            todos,
+                            id,
+                            todo,
+                            blocked.contains(id),
+                        ));
                         nodes.insert(id, leaf);
+                        continue;
+                    }
+                    stack.push((id, todo, StackState::Ready));
+                    for ch_id in ch_ids {
+                        stack.push((
+                            ch_id,
+                            todos.get(ch_id).unwrap(),
+                            StackState::Pending,
+                        ));
                     }
-                },
+                }
                 StackState::Ready => {
                     let mut children = Vec::new();
-                    for ch_id in todo.children.as_ref().unwrap() {
+                    for ch_id in visible_children(todo, &visible) {
                         let ch_node = nodes.remove_entry(ch_id).unwrap();
                         children.push(ch_node);
                     }
@@ -84,7 +169,7 @@ fn build_tree(todos: &TodoMap) -> Vec<Node> {
                     let children =
                         children.into_iter().map(|(_, t)| t).collect();
                     let non_leaf = Node::NonLeaf {
-                        id: desc(id, &todo),
+                        id: todo_text(todos, id, todo, blocked.contains(id)),
                         children,
                         children_visible: true,
                     };
@@ -101,11 +186,39 @@ fn build_tree(todos: &TodoMap) -> Vec<Node> {
     nodes.into_iter().map(|(_, n)| n).collect()
 }
 
-fn desc(todo_id: &u32, todo: &Todo) -> TodoText {
+fn visible_children<'a>(
+    todo: &'a Todo,
+    visible: &HashSet<u32>,
+) -> Vec<&'a u32> {
+    todo.children
+        .iter()
+        .flatten()
+        .filter(|ch_id| visible.contains(ch_id))
+        .collect()
+}
+
+/// Line of todo `todo_id` of `todos`, with the progress, time and estimate
+/// of its subtree.
+pub fn todo_text(
+    todos: &TodoMap,
+    todo_id: &u32,
+    todo: &Todo,
+    blocked: bool,
+) -> TodoText {
     TodoText {
         id: todo_id.to_string(),
+        list: None,
         desc: todo.desc.clone(),
         link: todo.link.clone(),
-        complete: todo.complete_at.map(|c| c.to_string()),
+        complete: todo.complete_at().map(|c| local_time(&c)),
+        status: todo.status(),
+        progress: todos.progress(todo_id).map(|(done, total)| Progress {
+            done,
+            total,
+            percent: (todos.completion(todo_id) * 100.0).round() as u8,
+        }),
+        blocked,
+        spent: Some(todos.total_spent(todo_id)).filter(|s| !s.is_zero()),
+        estimate: Some(todos.total_estimate(todo_id)).filter(|e| !e.is_zero()),
     }
 }
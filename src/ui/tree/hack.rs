@@ -1,26 +1,58 @@
+use crate::model::{
+    Status,
+    effort::{Effort, Estimate},
+};
 use promkit::core::{
     crossterm::style::{ContentStyle, Stylize},
     grapheme::StyledGraphemes,
 };
+use std::fmt::Display;
 
+/// A todo as displayed in the tree, one without `id` is a heading grouping
+/// the todos below it.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TodoText {
     pub id: String,
+    /// List of the todo, when todos of several lists are displayed together.
+    pub list: Option<String>,
     pub desc: String,
     pub link: Option<String>,
     pub complete: Option<String>,
+    pub status: Status,
+    pub progress: Option<Progress>,
+    pub blocked: bool,
+    /// Time logged on the todo and its descendants, `None` if nothing.
+    pub spent: Option<Effort>,
+    /// Estimates of the todo and its descendants, `None` if nothing.
+    pub estimate: Option<Estimate>,
+}
+
+/// Roll-up of a parent todo: completed and all direct children, and the
+/// completion of the whole subtree in percent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+    pub percent: u8,
 }
 
-impl ToString for TodoText {
-    fn to_string(&self) -> String {
-        format!(
+impl Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}/{}] {}%", self.done, self.total, self.percent)
+    }
+}
+
+impl Display for TodoText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
             "#{} {}{}{}",
             self.id,
             self.desc,
             self.link
                 .clone()
                 .map_or_else(String::new, |s| format!("({s})")),
-            self.complete.clone().unwrap_or_else(String::new)
+            self.complete.clone().unwrap_or_default()
         )
     }
 }
@@ -31,6 +63,11 @@ pub struct TodoStyle {
     pub desc: ContentStyle,
     pub link: ContentStyle,
     pub complete: ContentStyle,
+    pub progress: ContentStyle,
+    pub blocked: ContentStyle,
+    pub spent: ContentStyle,
+    pub estimate: ContentStyle,
+    pub heading: ContentStyle,
 }
 
 impl TodoStyle {
@@ -40,6 +77,11 @@ impl TodoStyle {
             desc: ContentStyle::new().blue(),
             link: ContentStyle::new().cyan().italic(),
             complete: ContentStyle::default(),
+            progress: ContentStyle::new().green(),
+            blocked: ContentStyle::new().red().bold(),
+            spent: ContentStyle::new().magenta(),
+            estimate: ContentStyle::new().yellow(),
+            heading: ContentStyle::new().bold().underlined(),
         }
     }
     pub fn inactive_defautl_style() -> Self {
@@ -48,6 +90,11 @@ impl TodoStyle {
             desc: ContentStyle::default(),
             link: ContentStyle::new().dim().cyan().italic(),
             complete: ContentStyle::default(),
+            progress: ContentStyle::new().dim().green(),
+            blocked: ContentStyle::new().red(),
+            spent: ContentStyle::new().dim().magenta(),
+            estimate: ContentStyle::new().dim().yellow(),
+            heading: ContentStyle::new().bold(),
         }
     }
 
@@ -57,26 +104,44 @@ impl TodoStyle {
         is_active: bool,
     ) -> StyledGraphemes {
         let mut styled = Vec::new();
+        if todo.id.is_empty() {
+            styled.push(StyledGraphemes::from_str(&todo.desc, self.heading));
+            return StyledGraphemes::from_iter(styled);
+        }
+        styled.push(StyledGraphemes::from_str(todo.status.marker(), self.id));
         styled.push(StyledGraphemes::from_str(
-            if todo.complete.is_some() {
-                "✔ "
-            } else {
-                "  "
-            },
-            self.id,
-        ));
-        styled.push(StyledGraphemes::from_str(
-            format!("#{} ", todo.id,),
+            format!("{}#{} ", todo.list.unwrap_or_default(), todo.id),
             self.id,
         ));
-        styled.push(StyledGraphemes::from_str(
-            format!("{}", todo.desc,),
-            self.desc,
-        ));
+        if todo.blocked {
+            styled.push(StyledGraphemes::from_str("⊘ ", self.blocked));
+        }
+        styled.push(StyledGraphemes::from_str(&todo.desc, self.desc));
+
+        if let Some(progress) = todo.progress {
+            styled.push(StyledGraphemes::from_str(
+                format!(" {progress}"),
+                self.progress,
+            ));
+        }
+
+        if let Some(estimate) = todo.estimate {
+            styled.push(StyledGraphemes::from_str(
+                format!(" ≈ {estimate}"),
+                self.estimate,
+            ));
+        }
+
+        if let Some(spent) = todo.spent {
+            styled.push(StyledGraphemes::from_str(
+                format!(" ⏱ {spent}"),
+                self.spent,
+            ));
+        }
 
-        if is_active && todo.link.is_some() {
+        if let Some(link) = todo.link.filter(|_| is_active) {
             styled.push(StyledGraphemes::from_str(
-                format!(" ({})", todo.link.unwrap()),
+                format!(" ({link})"),
                 self.link,
             ));
         }
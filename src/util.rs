@@ -1,5 +1,5 @@
-use crate::context::Context;
-use anyhow::{Result, bail};
+use crate::{context::Context, query::Query};
+use anyhow::{Result, anyhow, bail};
 use log::debug;
 use std::{collections::HashMap, env, fmt::Display, ops::Deref, path::PathBuf};
 use tokio::runtime::Runtime;
@@ -55,13 +55,26 @@ pub fn get_list(arg: Option<String>, ctx: &Context) -> String {
     }
 }
 
+/// Parses the query expression given in `arg`, with the saved queries of the
+/// config.
+pub fn get_query(arg: Option<&str>, ctx: &Context) -> Result<Option<Query>> {
+    let Some(input) = arg else {
+        return Ok(None);
+    };
+    debug!(target: "util::get_query", "query [{input}]");
+    let today = ctx.config.timezone().today();
+    Query::parse(input, today, ctx.config.queries())
+        .map(Some)
+        .map_err(|err| anyhow!(err))
+}
+
 pub fn check_key<K, V>(hash: &HashMap<K, V>, key: &K) -> Result<()>
 where
     K: ToString + Display,
     K: std::hash::Hash + std::cmp::Eq,
 {
     debug!(target: "util::check_key", "key [{key}]");
-    if hash.contains_key(&key) {
+    if hash.contains_key(key) {
         Ok(())
     } else {
         bail!("key [{key}] does not exist")